@@ -1,6 +1,8 @@
 import type {
 	IExecuteFunctions,
+	ILoadOptionsFunctions,
 	INodeExecutionData,
+	INodePropertyOptions,
 	INodeType,
 	INodeTypeDescription,
 } from 'n8n-workflow';
@@ -8,6 +10,13 @@ import { NodeConnectionType, NodeOperationError } from 'n8n-workflow';
 import { RustWasmWrapper } from '../../shared/RustWasmWrapper';
 import type { ImageProcessingOptions } from '../../shared/types';
 
+const OUTPUT_FORMAT_OPTIONS: INodePropertyOptions[] = [
+	{ name: 'PNG', value: 'png', description: 'PNG format (lossless)' },
+	{ name: 'JPEG', value: 'jpeg', description: 'JPEG format (lossy, smaller size)' },
+	{ name: 'WebP', value: 'webp', description: 'WebP format (modern, efficient)' },
+	{ name: 'AVIF', value: 'avif', description: 'AVIF format (requires the avif build feature)' },
+];
+
 export class PhotonImageProcessor implements INodeType {
 	description: INodeTypeDescription = {
 		displayName: 'Photon Image Processor',
@@ -53,6 +62,18 @@ export class PhotonImageProcessor implements INodeType {
 						description: 'Apply convolution effects and edge detection',
 						action: 'Apply image effect',
 					},
+					{
+						name: 'Convert Format',
+						value: 'identity',
+						description: 'Re-encode to the output format without changing pixels',
+						action: 'Convert image format',
+					},
+					{
+						name: 'Normalize for Web',
+						value: 'normalize',
+						description: 'Rotate upright from EXIF orientation, strip all metadata and re-encode',
+						action: 'Normalize image for the web',
+					},
 				],
 				default: 'filter',
 			},
@@ -142,7 +163,7 @@ export class PhotonImageProcessor implements INodeType {
 					},
 				},
 				default: 1.0,
-				description: 'Intensity of the filter effect (0.0 to 2.0)',
+				description: 'Strength of the filter (0.0 to 2.0). Warm, cool and vintage scale with it; other filters blend with the original below 1.0',
 			},
 			// Transform options
 			{
@@ -332,6 +353,7 @@ export class PhotonImageProcessor implements INodeType {
 					{ name: 'Posterize', value: 'posterize', description: 'Reduce color levels' },
 					{ name: 'Sharpen', value: 'sharpen', description: 'Sharpen the image' },
 					{ name: 'Sobel Horizontal', value: 'sobel_horizontal', description: 'Horizontal edge detection' },
+					{ name: 'Sobel Magnitude', value: 'sobel', description: 'Combined horizontal and vertical edge strength' },
 					{ name: 'Sobel Vertical', value: 'sobel_vertical', description: 'Vertical edge detection' },
 					{ name: 'Solarize', value: 'solarize', description: 'Solarization effect' },
 					{ name: 'Threshold', value: 'threshold', description: 'Apply threshold effect' },
@@ -358,16 +380,15 @@ export class PhotonImageProcessor implements INodeType {
 			},
 			// Output options
 			{
-				displayName: 'Output Format',
+				displayName: 'Output Format Name or ID',
 				name: 'outputFormat',
 				type: 'options',
-				options: [
-					{ name: 'PNG', value: 'png', description: 'PNG format (lossless)' },
-					{ name: 'JPEG', value: 'jpeg', description: 'JPEG format (lossy, smaller size)' },
-					{ name: 'WebP', value: 'webp', description: 'WebP format (modern, efficient)' },
-				],
+				typeOptions: {
+					loadOptionsMethod: 'getOutputFormats',
+				},
 				default: 'png',
-				description: 'Output image format',
+				description:
+					'Output image format. Formats this build cannot encode are listed as unavailable. Choose from the list, or specify an ID using an <a href="https://docs.n8n.io/code/expressions/">expression</a>.',
 			},
 				{
 					displayName: 'JPEG Quality',
@@ -422,6 +443,20 @@ export class PhotonImageProcessor implements INodeType {
 		],
 	};
 
+	methods = {
+		loadOptions: {
+			// Formats the loaded WASM build cannot encode stay listed but are marked unavailable
+			async getOutputFormats(this: ILoadOptionsFunctions): Promise<INodePropertyOptions[]> {
+				const supported = await new RustWasmWrapper().getSupportedOutputFormats();
+				return OUTPUT_FORMAT_OPTIONS.map((option) =>
+					supported.includes(option.value as string)
+						? option
+						: { ...option, name: `${option.name} (unavailable)`, description: 'Not supported by this build' },
+				);
+			},
+		},
+	};
+
 	async execute(this: IExecuteFunctions): Promise<INodeExecutionData[][]> {
 		const items = this.getInputData();
 		const returnData: INodeExecutionData[] = [];
@@ -466,8 +501,8 @@ export class PhotonImageProcessor implements INodeType {
 
 				// Build processing options based on operation type
 				const options: ImageProcessingOptions = {
-					operation: operation as 'filter' | 'transform' | 'adjust' | 'effect',
-					output_format: outputFormat as 'png' | 'jpeg' | 'webp',
+					operation: operation as 'filter' | 'transform' | 'adjust' | 'effect' | 'identity' | 'normalize',
+					output_format: outputFormat as ImageProcessingOptions['output_format'],
 				};
 
 				// Add operation-specific parameters
@@ -555,6 +590,7 @@ export class PhotonImageProcessor implements INodeType {
 						returnData.push({
 							json: {
 								error: result.error,
+								errorCode: result.error_code,
 								operation,
 								itemIndex,
 							},
@@ -5,23 +5,88 @@ import type {
     BatchProcessResult,
     ImageProcessingOptions,
     ImageProcessingResult,
+    ImageMetadata,
+    BinaryImageProcessingResult,
     BatchImageProcessingResult,
-    ImageValidationResult
+    ImageValidationResult,
+    ProcessorConfig
 } from './types';
 
+// Handle for stopping a cancelable batch between images
+interface WasmCancelToken {
+    cancel(): void;
+    is_cancelled(): boolean;
+    free(): void;
+}
+
+// Decoded RGBA pixels; getters copy out of WASM memory
+interface WasmRawPixels {
+    readonly width: number;
+    readonly height: number;
+    readonly pixels: Uint8Array;
+    free(): void;
+}
+
 // WASM module interface
 interface WasmModule {
+    CancelToken: new () => WasmCancelToken;
     process_input_wasm(input: string, options: string): string;
     validate_input_wasm(input: string): string;
     process_batch_wasm(inputs: string, options: string): string;
+    process_ndjson_wasm(input: string, options: string): string;
+    process_large_array_wasm(
+        input: string,
+        options: string,
+        callback: (elementJson: string, index: number) => void,
+    ): string;
+    merge_patch_wasm(target: string, patch: string): string;
+    json_pointer_get_wasm(input: string, pointer: string): string;
+    json_pointer_set_wasm(input: string, pointer: string, value: string, create_missing: boolean): string;
+    hash_input_wasm(input: string, algo: string): string;
+    detect_format_wasm(input: string): string;
     get_version(): string;
+    get_build_info(): string;
     
     // Image processing functions
     process_image_wasm(base64_input: string, options_json: string): string;
+    process_image_bytes_wasm(input: Uint8Array, options_json: string): string;
+    process_image_binary_wasm(base64_input: string, options_json: string): Uint8Array;
+    get_last_binary_metadata(): string;
     process_image_batch_wasm(images_json: string, options_json: string): string;
+    process_image_batch_with_progress_wasm(
+        images_json: string,
+        options_json: string,
+        callback: (index: number, total: number, success: boolean) => void,
+    ): string;
+    process_image_batch_cancelable_wasm(
+        images_json: string,
+        options_json: string,
+        token: WasmCancelToken,
+        callback?: (index: number, total: number, success: boolean) => void,
+    ): string;
+    process_image_batch_with_options_wasm(images_json: string, options_json: string): string;
+    process_gif_frames_wasm(base64_input: string, options_json: string): string;
+    estimate_batch_memory_wasm(images_json: string): number;
+    compose_grid_wasm(
+        images_json: string,
+        cols: number,
+        cell_width: number,
+        cell_height: number,
+        options_json: string,
+    ): string;
+    create_solid_image_wasm(width: number, height: number, color: string, format: string): string;
+    perceptual_hash_wasm(base64_input: string): string;
+    hamming_distance_wasm(a: string, b: string): number;
+    count_unique_colors_wasm(base64_input: string): number;
     get_available_filters(): string;
     get_available_effects(): string;
+    get_supported_output_formats(): string;
+    get_capabilities(): string;
     validate_image_wasm(base64_input: string): string;
+    get_image_info_wasm(base64_input: string): string;
+    extract_gps_wasm(base64_input: string): string;
+    get_raw_pixels_wasm(base64_input: string): WasmRawPixels;
+    configure_wasm(config_json: string): void;
 }
 
 let wasmModule: WasmModule | null = null;
@@ -122,6 +187,126 @@ export class RustWasmWrapper {
         }
     }
 
+    /**
+     * Process a top-level JSON array one element at a time, calling `onElement`
+     * with each processed element so the whole array is never materialized
+     */
+    async processLargeArray(
+        input: string,
+        options: ProcessOptions,
+        onElement: (element: unknown, index: number) => void,
+    ): Promise<ProcessResult<{ count: number }>> {
+        await this.ensureWasmInitialized();
+
+        const resultJson = this.wasm!.process_large_array_wasm(
+            input,
+            JSON.stringify(options),
+            (elementJson, index) => onElement(JSON.parse(elementJson), index),
+        );
+        return JSON.parse(resultJson);
+    }
+
+    async processNdjson(input: string, options: ProcessOptions): Promise<BatchProcessResult> {
+        try {
+            await this.ensureWasmInitialized();
+
+            const optionsJson = JSON.stringify(options);
+            const resultsJson = this.wasm!.process_ndjson_wasm(input, optionsJson);
+
+            const results = JSON.parse(resultsJson) as ProcessResult[];
+
+            return {
+                processed: results.length,
+                successful: results.filter(r => r.success).length,
+                failed: results.filter(r => !r.success).length,
+                results,
+            };
+        } catch (error: any) {
+            return {
+                processed: 0,
+                successful: 0,
+                failed: 1,
+                results: [{
+                    success: false,
+                    error: `NDJSON processing failed: ${error.message}`,
+                }],
+            };
+        }
+    }
+
+    async mergePatch(target: string, patch: string): Promise<ProcessResult> {
+        try {
+            await this.ensureWasmInitialized();
+
+            const resultJson = this.wasm!.merge_patch_wasm(target, patch);
+            return JSON.parse(resultJson) as ProcessResult;
+        } catch (error: any) {
+            return {
+                success: false,
+                error: `Failed to apply merge patch: ${error.message}`,
+            };
+        }
+    }
+
+    async jsonPointerGet(input: string, pointer: string): Promise<ProcessResult> {
+        try {
+            await this.ensureWasmInitialized();
+
+            const resultJson = this.wasm!.json_pointer_get_wasm(input, pointer);
+            return JSON.parse(resultJson) as ProcessResult;
+        } catch (error: any) {
+            return {
+                success: false,
+                error: `Failed to read JSON Pointer: ${error.message}`,
+            };
+        }
+    }
+
+    async jsonPointerSet(input: string, pointer: string, value: unknown, createMissing = false): Promise<ProcessResult> {
+        try {
+            await this.ensureWasmInitialized();
+
+            const valueJson = JSON.stringify(value);
+            const resultJson = this.wasm!.json_pointer_set_wasm(input, pointer, valueJson, createMissing);
+            return JSON.parse(resultJson) as ProcessResult;
+        } catch (error: any) {
+            return {
+                success: false,
+                error: `Failed to set JSON Pointer: ${error.message}`,
+            };
+        }
+    }
+
+    async hashInput(input: string, algo: 'md5' | 'sha1' | 'sha256' = 'sha256'): Promise<ProcessResult> {
+        try {
+            await this.ensureWasmInitialized();
+
+            const resultJson = this.wasm!.hash_input_wasm(input, algo);
+            return JSON.parse(resultJson) as ProcessResult;
+        } catch (error: any) {
+            return {
+                success: false,
+                error: `Failed to hash input: ${error.message}`,
+            };
+        }
+    }
+
+    async detectFormat(
+        input: string,
+    ): Promise<ProcessResult<{ format: 'json' | 'yaml' | 'csv' | 'xml' | 'unknown'; confidence: number }>> {
+        try {
+            await this.ensureWasmInitialized();
+
+            const resultJson = this.wasm!.detect_format_wasm(input);
+            return JSON.parse(resultJson);
+        } catch (error: any) {
+            return {
+                success: false,
+                error: `Failed to detect format: ${error.message}`,
+            };
+        }
+    }
+
     async getVersion(): Promise<string> {
         try {
             await this.ensureWasmInitialized();
@@ -131,6 +316,15 @@ export class RustWasmWrapper {
         }
     }
 
+    /**
+     * Get the version, git commit hash and build profile of the loaded module
+     */
+    async getBuildInfo(): Promise<{ version: string; git_hash: string; build_profile: string }> {
+        await this.ensureWasmInitialized();
+
+        return JSON.parse(this.wasm!.get_build_info());
+    }
+
     // Image processing methods
     async processImage(base64Input: string, options: ImageProcessingOptions): Promise<ImageProcessingResult> {
         try {
@@ -156,6 +350,43 @@ export class RustWasmWrapper {
         }
     }
 
+    async processImageBytes(input: Uint8Array, options: ImageProcessingOptions): Promise<ImageProcessingResult> {
+        try {
+            await this.ensureWasmInitialized();
+            
+            const optionsJson = JSON.stringify(options);
+            const resultJson = this.wasm!.process_image_bytes_wasm(input, optionsJson);
+            
+            return JSON.parse(resultJson) as ImageProcessingResult;
+        } catch (error: any) {
+            return {
+                success: false,
+                error: `Failed to process image: ${error.message ?? error}`,
+            };
+        }
+    }
+
+    async processImageBinary(base64Input: string, options: ImageProcessingOptions): Promise<BinaryImageProcessingResult> {
+        try {
+            await this.ensureWasmInitialized();
+            
+            const optionsJson = JSON.stringify(options);
+            const bytes = this.wasm!.process_image_binary_wasm(base64Input, optionsJson);
+            const metadata = JSON.parse(this.wasm!.get_last_binary_metadata()) as ImageMetadata;
+            
+            return {
+                success: true,
+                bytes,
+                metadata,
+            };
+        } catch (error: any) {
+            return {
+                success: false,
+                error: `Failed to process image: ${error.message ?? error}`,
+            };
+        }
+    }
+
     async processImageBatch(images: string[], options: ImageProcessingOptions): Promise<BatchImageProcessingResult> {
         try {
             await this.ensureWasmInitialized();
@@ -179,6 +410,114 @@ export class RustWasmWrapper {
         }
     }
 
+    async processImageBatchWithProgress(
+        images: string[],
+        options: ImageProcessingOptions,
+        onProgress: (index: number, total: number, success: boolean) => void,
+    ): Promise<BatchImageProcessingResult> {
+        try {
+            await this.ensureWasmInitialized();
+            
+            const imagesJson = JSON.stringify(images);
+            const optionsJson = JSON.stringify(options);
+            const resultJson = this.wasm!.process_image_batch_with_progress_wasm(imagesJson, optionsJson, onProgress);
+            
+            return JSON.parse(resultJson) as BatchImageProcessingResult;
+        } catch (error: any) {
+            return {
+                processed: 0,
+                successful: 0,
+                failed: images.length,
+                results: images.map(() => ({
+                    success: false,
+                    error: `Batch processing failed: ${error.message ?? error}`,
+                })),
+                total_time_ms: 0,
+            };
+        }
+    }
+
+    /**
+     * Process a batch that stops once `signal` aborts; the signal is checked
+     * between images, and the partial result comes back with `cancelled: true`
+     */
+    async processImageBatchCancelable(
+        images: string[],
+        options: ImageProcessingOptions,
+        signal: AbortSignal,
+        onProgress?: (index: number, total: number, success: boolean) => void,
+    ): Promise<BatchImageProcessingResult> {
+        await this.ensureWasmInitialized();
+
+        const token = new this.wasm!.CancelToken();
+        const cancelIfAborted = () => {
+            if (signal.aborted) token.cancel();
+        };
+        signal.addEventListener('abort', cancelIfAborted);
+        cancelIfAborted();
+        try {
+            const resultJson = this.wasm!.process_image_batch_cancelable_wasm(
+                JSON.stringify(images),
+                JSON.stringify(options),
+                token,
+                (index, total, success) => {
+                    onProgress?.(index, total, success);
+                    cancelIfAborted();
+                },
+            );
+            return JSON.parse(resultJson) as BatchImageProcessingResult;
+        } finally {
+            signal.removeEventListener('abort', cancelIfAborted);
+            token.free();
+        }
+    }
+
+    async processImageBatchWithOptions(images: string[], options: ImageProcessingOptions[]): Promise<BatchImageProcessingResult> {
+        try {
+            await this.ensureWasmInitialized();
+            
+            const imagesJson = JSON.stringify(images);
+            const optionsJson = JSON.stringify(options);
+            const resultJson = this.wasm!.process_image_batch_with_options_wasm(imagesJson, optionsJson);
+            
+            return JSON.parse(resultJson) as BatchImageProcessingResult;
+        } catch (error: any) {
+            return {
+                processed: 0,
+                successful: 0,
+                failed: images.length,
+                results: images.map(() => ({
+                    success: false,
+                    error: `Batch processing failed: ${error.message ?? error}`,
+                })),
+                total_time_ms: 0,
+            };
+        }
+    }
+
+    /** Format, dimensions, color type, bit depth and alpha read from the image header */
+    async getImageInfo(base64Input: string): Promise<ImageMetadata> {
+        await this.ensureWasmInitialized();
+        return JSON.parse(this.wasm!.get_image_info_wasm(base64Input)) as ImageMetadata;
+    }
+
+    /** Decimal GPS position from the image's EXIF (south and west negative), or null when absent */
+    async extractGps(base64Input: string): Promise<{ latitude: number; longitude: number } | null> {
+        await this.ensureWasmInitialized();
+        return JSON.parse(this.wasm!.extract_gps_wasm(base64Input));
+    }
+
+    /** Decoded RGBA bytes (4 per pixel, row by row) with dimensions, e.g. for canvas ImageData */
+    async getRawPixels(base64Input: string): Promise<{ width: number; height: number; pixels: Uint8Array }> {
+        await this.ensureWasmInitialized();
+        const raw = this.wasm!.get_raw_pixels_wasm(base64Input);
+        try {
+            return { width: raw.width, height: raw.height, pixels: raw.pixels };
+        } finally {
+            raw.free();
+        }
+    }
+
     async validateImage(base64Input: string): Promise<ImageValidationResult> {
         try {
             await this.ensureWasmInitialized();
@@ -193,6 +532,95 @@ export class RustWasmWrapper {
         }
     }
 
+    /**
+     * Estimate the bytes needed to decode every image, reading only image headers
+     */
+    async estimateBatchMemory(images: string[]): Promise<number> {
+        await this.ensureWasmInitialized();
+        return this.wasm!.estimate_batch_memory_wasm(JSON.stringify(images));
+    }
+
+    /**
+     * Lay images out in a grid (contact sheet), scaling each into its cell
+     */
+    async composeGrid(
+        images: string[],
+        cols: number,
+        cellWidth: number,
+        cellHeight: number,
+        options: Partial<ImageProcessingOptions> = {},
+    ): Promise<ImageProcessingResult> {
+        try {
+            await this.ensureWasmInitialized();
+
+            const resultJson = this.wasm!.compose_grid_wasm(
+                JSON.stringify(images),
+                cols,
+                cellWidth,
+                cellHeight,
+                JSON.stringify(options),
+            );
+            return JSON.parse(resultJson) as ImageProcessingResult;
+        } catch (error: any) {
+            return {
+                success: false,
+                error: `Failed to compose grid: ${error.message}`,
+            };
+        }
+    }
+
+    /**
+     * Apply the operation to every frame of an animated GIF, keeping frame delays
+     */
+    async processGifFrames(base64Input: string, options: ImageProcessingOptions): Promise<ImageProcessingResult> {
+        try {
+            await this.ensureWasmInitialized();
+
+            const resultJson = this.wasm!.process_gif_frames_wasm(base64Input, JSON.stringify(options));
+            return JSON.parse(resultJson) as ImageProcessingResult;
+        } catch (error: any) {
+            return {
+                success: false,
+                error: `Failed to process GIF: ${error.message}`,
+            };
+        }
+    }
+
+    async createSolidImage(
+        width: number,
+        height: number,
+        color: string,
+        format: 'png' | 'jpeg' | 'webp' = 'png',
+    ): Promise<ImageProcessingResult> {
+        try {
+            await this.ensureWasmInitialized();
+
+            const resultJson = this.wasm!.create_solid_image_wasm(width, height, color, format);
+            return JSON.parse(resultJson) as ImageProcessingResult;
+        } catch (error: any) {
+            return {
+                success: false,
+                error: `Failed to create image: ${error.message}`,
+            };
+        }
+    }
+
+    async perceptualHash(base64Input: string): Promise<string> {
+        await this.ensureWasmInitialized();
+        return this.wasm!.perceptual_hash_wasm(base64Input);
+    }
+
+    async hammingDistance(a: string, b: string): Promise<number> {
+        await this.ensureWasmInitialized();
+        return this.wasm!.hamming_distance_wasm(a, b);
+    }
+
+    /** Distinct RGBA colors; approximate for images above a megapixel */
+    async countUniqueColors(base64Input: string): Promise<number> {
+        await this.ensureWasmInitialized();
+        return this.wasm!.count_unique_colors_wasm(base64Input);
+    }
+
     async getAvailableFilters(): Promise<string[]> {
         try {
             await this.ensureWasmInitialized();
@@ -214,4 +642,30 @@ export class RustWasmWrapper {
             return [];
         }
     }
+
+    async getSupportedOutputFormats(): Promise<string[]> {
+        try {
+            await this.ensureWasmInitialized();
+
+            const formatsJson = this.wasm!.get_supported_output_formats();
+            return JSON.parse(formatsJson) as string[];
+        } catch {
+            return ['png'];
+        }
+    }
+
+    /**
+     * Get the manifest of operations, filters, effects and output formats
+     */
+    async getCapabilities(): Promise<Record<string, unknown>> {
+        await this.ensureWasmInitialized();
+
+        return JSON.parse(this.wasm!.get_capabilities()) as Record<string, unknown>;
+    }
+
+    /** Set process-wide image defaults; throws if they were already configured */
+    async configure(config: Partial<ProcessorConfig>): Promise<void> {
+        await this.ensureWasmInitialized();
+        this.wasm!.configure_wasm(JSON.stringify(config));
+    }
 }
\ No newline at end of file
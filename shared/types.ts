@@ -1,14 +1,27 @@
 // Core data structures matching Rust structs
-export interface ProcessResult {
+export interface ProcessResult<T = any> {
     success: boolean;
-    data?: any;
+    data?: T;
     error?: string;
+    warnings?: string[]; // options that were ignored or had no effect
 }
 
 export interface ProcessOptions {
     format: string;
     validate: boolean;
     extra_data: boolean;
+    schema?: string; // JSON Schema checked when validate is true
+    query?: string; // JSONPath expression for the jsonpath format
+    delimiter?: string; // Single-character CSV field delimiter (default ',')
+    has_headers?: boolean; // Treat the first CSV row as headers (default true)
+    flatten_nested?: boolean; // json_to_csv: expand nested values into dotted columns
+    flatten_delimiter?: string; // flatten/unflatten key separator (default '.')
+    indent?: number; // prettify: spaces per nesting level (default 2)
+    dedup_key?: string; // dedup: compare objects by this field instead of deep equality
+    sort_arrays?: boolean; // sort_keys: also sort arrays of scalars
+    preserve_precision?: boolean; // Keep numbers exactly as written (e.g. IDs wider than 64 bits)
+    decompress?: 'gzip' | 'deflate' | 'zstd'; // input is base64 of data compressed with this codec
+    compress?: 'gzip' | 'deflate' | 'zstd'; // output is returned as base64 of compressed data
 }
 
 export interface BatchProcessResult {
@@ -20,9 +33,10 @@ export interface BatchProcessResult {
 
 // Image processing types
 export interface ImageProcessingOptions {
-    operation: 'filter' | 'transform' | 'adjust' | 'effect';
+    operation: 'filter' | 'transform' | 'adjust' | 'effect' | 'tint' | 'swap_channels' | 'blend' | 'trim' | 'crop_aspect' | 'chroma_key' | 'luma_alpha' | 'canvas' | 'blur_regions' | 'normalize' | 'identity';
     filter?: string;
-    intensity?: number;
+    filters?: string[]; // filter: further filters applied in order after `filter`
+    intensity?: number; // filter strength 0-2 (default 1): warm/cool/vintage scale with it, other filters blend with the original below 1
     brightness?: number;
     contrast?: number;
     saturation?: number;
@@ -34,12 +48,71 @@ export interface ImageProcessingOptions {
     crop_y?: number;
     crop_width?: number;
     crop_height?: number;
+    crop_x_pct?: number; // crop origin/size as 0-100 percentages of the image, instead of pixels
+    crop_y_pct?: number;
+    crop_width_pct?: number;
+    crop_height_pct?: number;
     rotation_angle?: number;
     flip_horizontal?: boolean;
     flip_vertical?: boolean;
-    output_format?: 'png' | 'jpeg' | 'webp';
+    output_format?: 'png' | 'jpeg' | 'webp' | 'avif' | 'tiff'; // avif requires the avif build feature; tiff only with bit_depth 16
     quality?: number;
     output_as_binary?: boolean;
+    block_size?: number;
+    kernel?: number[];
+    kernel_divisor?: number;
+    noise_amount?: number;
+    noise_type?: 'gaussian' | 'salt_pepper';
+    seed?: number;
+    max_pixels?: number;
+    oil_radius?: number;
+    oil_levels?: number;
+    tint_color?: string; // Hex color such as '#f00' or '#ff0000'
+    tint_strength?: number;
+    channel_order?: string; // Permutation of 'rgb', e.g. 'bgr'
+    blend_base64?: string; // Second image drawn over the input
+    blend_mode?: 'normal' | 'multiply' | 'screen' | 'overlay';
+    blend_opacity?: number;
+    palette_size?: number;
+    opacity?: number; // 0-1 alpha multiplier; requires png or webp output
+    max_bytes?: number; // shrink lossy output until it fits in this many bytes
+    transpose?: boolean; // swap rows and columns (mirror across the main diagonal)
+    output_encoding?: 'data_url' | 'base64' | 'binary'; // overrides output_as_binary when set
+    auto_levels?: boolean; // stretch each channel to the full 0-255 range
+    clip_percent?: number; // percent of pixels clipped at each end by auto_levels (0-50)
+    temperature?: number; // white balance: -1.0 (cool) to 1.0 (warm)
+    tint?: number; // white balance: -1.0 (green) to 1.0 (magenta)
+    trim_tolerance?: number; // per-channel difference still treated as border by trim
+    metadata_only?: boolean; // decode and report metadata without encoding any output
+    strip_metadata?: boolean; // default true; false keeps EXIF minus GPS when the format is unchanged
+    threshold_channel?: 'luma' | 'r' | 'g' | 'b'; // channel compared against the threshold cutoff (default 'luma')
+    threshold_invert?: boolean; // swap black and white in the threshold output
+    levels?: number; // posterize steps per channel, 2-256 (default 4)
+    svg_width?: number; // raster width for SVG input (svg feature)
+    svg_height?: number; // raster height for SVG input (svg feature)
+    target_aspect?: string; // ratio for crop_aspect, e.g. '16:9'
+    grayscale_mode?: 'luminance' | 'average' | 'lightness' | 'single_channel_r' | 'single_channel_g' | 'single_channel_b'; // grayscale filter conversion (default: photon's channel average)
+    fail_fast?: boolean; // batch: stop at the first failed image (default false)
+    no_upscale?: boolean; // transform: skip resizes that would enlarge the image
+    base64_variant?: 'standard' | 'url_safe'; // base64 alphabet; input is auto-detected when unset, output defaults to standard
+    jpeg_progressive?: boolean; // Progressive (multi-scan) JPEG output
+    jpeg_subsampling?: '4:4:4' | '4:2:2' | '4:2:0'; // JPEG chroma subsampling (default 4:4:4)
+    resize_filter?: 'nearest' | 'triangle' | 'catmull_rom' | 'gaussian' | 'lanczos3'; // resize sampling filter (defaults to the configured default_resize_filter)
+    key_color?: string; // chroma_key: color made transparent, hex or rgb() (default #00ff00)
+    key_tolerance?: number; // chroma_key: max per-channel difference from key_color (default 32)
+    luma_threshold?: number; // luma_alpha: luminance cutoff (default 240)
+    luma_above?: boolean; // luma_alpha: key out pixels at or above the threshold (default true) or at or below it
+    canvas_width?: number; // canvas: width of the new canvas (defaults to the image width)
+    canvas_height?: number; // canvas: height of the new canvas (defaults to the image height)
+    anchor?: 'center' | 'top_left' | 'top' | 'top_right' | 'left' | 'right' | 'bottom_left' | 'bottom' | 'bottom_right'; // canvas: where the image sits on the canvas (default center)
+    canvas_color?: string; // canvas: fill color, hex or rgb()/rgba() (default transparent)
+    clahe_tile_size?: number; // clahe effect: tile edge in pixels (default 64)
+    clahe_clip_limit?: number; // clahe effect: histogram clip as a multiple of the mean bin count, >= 1 (default 2)
+    linear_light?: boolean; // adjust: apply brightness as a gain in linear light instead of an sRGB offset
+    regions?: [number, number, number, number][]; // blur_regions: rectangles to blur as [x, y, width, height]
+    include_timing?: boolean; // measure processing_time_ms (native builds only); off keeps results byte-stable
+    bit_depth?: 8 | 16; // adjust: 16 keeps 16-bit precision and writes 16-bit png or tiff (default 8)
+    max_input_bytes?: number; // reject inputs whose decoded size exceeds this many bytes, checked before decoding
 }
 
 export interface ImageMetadata {
@@ -48,6 +121,13 @@ export interface ImageMetadata {
     format: string;
     size_bytes: number;
     processing_time_ms: number;
+    palette?: string[]; // '#rrggbb' colors chosen by quantize
+    quality?: number; // quality the output was encoded at, for lossy formats
+    aspect_ratio: number; // width / height
+    megapixels: number;
+    color_type?: 'grayscale' | 'grayscale_alpha' | 'rgb' | 'rgba'; // set by getImageInfo
+    bit_depth?: number; // bits per channel, set by getImageInfo
+    has_alpha?: boolean; // set by getImageInfo
 }
 
 export interface ImageProcessingResult {
@@ -56,6 +136,16 @@ export interface ImageProcessingResult {
     binary_data?: number[]; // raw binary data as array of bytes
     metadata?: ImageMetadata;
     error?: string;
+    error_code?: string; // stable error kind, e.g. 'decode_base64', 'unsupported_format', 'panic'
+    warnings?: string[]; // inputs that were ignored or clamped
+    index?: number; // position of the input within its batch (0 outside batches)
+}
+
+export interface BinaryImageProcessingResult {
+    success: boolean;
+    bytes?: Uint8Array; // encoded image returned directly from WASM
+    metadata?: ImageMetadata;
+    error?: string;
 }
 
 export interface BatchImageProcessingResult {
@@ -64,6 +154,15 @@ export interface BatchImageProcessingResult {
     failed: number;
     results: ImageProcessingResult[];
     total_time_ms: number;
+    cancelled?: boolean; // stopped early by a cancel token; results cover only processed images
+}
+
+// Process-wide defaults for image options left unset
+export interface ProcessorConfig {
+    default_format: 'png' | 'jpeg' | 'webp' | 'avif';
+    default_quality: number; // 1-100
+    default_resize_filter: 'nearest' | 'triangle' | 'catmull_rom' | 'gaussian' | 'lanczos3';
+    max_pixels: number;
 }
 
 export interface ImageValidationResult {
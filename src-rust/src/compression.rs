@@ -0,0 +1,86 @@
+//! Compression codecs for base64-wrapped payloads
+//!
+//! Compressed data crosses the WASM boundary as base64 text, so `decompress`
+//! takes base64 and returns the inflated text, and `compress` does the reverse.
+
+use base64::Engine;
+use std::io::{Read, Write};
+
+/// Upper bound on inflated output, guarding against decompression bombs
+pub const MAX_DECOMPRESSED_BYTES: u64 = 256 * 1024 * 1024;
+
+/// Decode base64 `input` and inflate it with `gzip`, `deflate` or `zstd` into UTF-8 text
+pub fn decompress(input: &str, codec: &str) -> Result<String, String> {
+    let compressed = base64::engine::general_purpose::STANDARD
+        .decode(input.trim())
+        .map_err(|e| format!("Compressed input is not valid base64: {}", e))?;
+
+    let reader: Box<dyn Read + '_> = match codec {
+        "gzip" => Box::new(flate2::read::GzDecoder::new(compressed.as_slice())),
+        "deflate" => Box::new(flate2::read::DeflateDecoder::new(compressed.as_slice())),
+        "zstd" => Box::new(
+            ruzstd::decoding::StreamingDecoder::new(compressed.as_slice())
+                .map_err(|e| format!("zstd decompression error: {}", e))?,
+        ),
+        _ => return Err(unknown_codec(codec)),
+    };
+
+    let mut inflated = Vec::new();
+    reader
+        .take(MAX_DECOMPRESSED_BYTES + 1)
+        .read_to_end(&mut inflated)
+        .map_err(|e| format!("{} decompression error: {}", codec, e))?;
+    if inflated.len() as u64 > MAX_DECOMPRESSED_BYTES {
+        return Err(format!("Decompressed input exceeds {} bytes", MAX_DECOMPRESSED_BYTES));
+    }
+
+    String::from_utf8(inflated).map_err(|e| format!("Decompressed input is not UTF-8: {}", e))
+}
+
+/// Compress `text` with `gzip`, `deflate` or `zstd` and encode the result as base64
+pub fn compress(text: &str, codec: &str) -> Result<String, String> {
+    let compressed = match codec {
+        "gzip" => {
+            let mut encoder = flate2::write::GzEncoder::new(Vec::new(), flate2::Compression::default());
+            encoder.write_all(text.as_bytes()).and_then(|_| encoder.finish())
+        }
+        "deflate" => {
+            let mut encoder = flate2::write::DeflateEncoder::new(Vec::new(), flate2::Compression::default());
+            encoder.write_all(text.as_bytes()).and_then(|_| encoder.finish())
+        }
+        "zstd" => Ok(ruzstd::encoding::compress_to_vec(
+            text.as_bytes(),
+            ruzstd::encoding::CompressionLevel::Fastest,
+        )),
+        _ => return Err(unknown_codec(codec)),
+    }
+    .map_err(|e| format!("{} compression error: {}", codec, e))?;
+
+    Ok(base64::engine::general_purpose::STANDARD.encode(compressed))
+}
+
+fn unknown_codec(codec: &str) -> String {
+    format!("Unknown compression '{}' (expected gzip, deflate or zstd)", codec)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_round_trip_each_codec() {
+        let text = r#"{"items": [1, 2, 3], "name": "compressed"}"#;
+        for codec in ["gzip", "deflate", "zstd"] {
+            let packed = compress(text, codec).unwrap();
+            assert_eq!(decompress(&packed, codec).unwrap(), text, "{}", codec);
+        }
+    }
+
+    #[test]
+    fn test_errors() {
+        assert!(compress("x", "lz4").unwrap_err().contains("Unknown compression"));
+        assert!(decompress("!!!", "gzip").unwrap_err().contains("base64"));
+        let plain = base64::engine::general_purpose::STANDARD.encode("not gzip");
+        assert!(decompress(&plain, "gzip").unwrap_err().starts_with("gzip decompression error"));
+    }
+}
@@ -0,0 +1,225 @@
+//! Minimal EXIF handling
+//!
+//! EXIF blocks are TIFF structures: a byte-order header followed by IFDs
+//! (tables of 12-byte entries). Only enough of the format is understood to
+//! read coordinates from the GPS sub-IFD, or remove it before metadata is
+//! carried forward.
+
+/// IFD0 tag whose value is the offset of the GPS IFD
+const GPS_IFD_POINTER: u16 = 0x8825;
+
+/// GPS IFD tags: each coordinate is a hemisphere letter plus degrees/minutes/seconds
+const GPS_LATITUDE_REF: u16 = 0x0001;
+const GPS_LATITUDE: u16 = 0x0002;
+const GPS_LONGITUDE_REF: u16 = 0x0003;
+const GPS_LONGITUDE: u16 = 0x0004;
+
+/// TIFF field type of an unsigned fraction (two u32s)
+const RATIONAL: u16 = 5;
+
+/// Byte-order aware view over a raw EXIF (TIFF) block
+struct Tiff<'a> {
+    data: &'a [u8],
+    little_endian: bool,
+}
+
+impl<'a> Tiff<'a> {
+    fn parse(data: &'a [u8]) -> Option<Self> {
+        let little_endian = match data.get(..2)? {
+            b"II" => true,
+            b"MM" => false,
+            _ => return None,
+        };
+        let tiff = Tiff { data, little_endian };
+        (tiff.u16_at(2)? == 42).then_some(tiff)
+    }
+
+    fn u16_at(&self, offset: usize) -> Option<u16> {
+        let bytes: [u8; 2] = self.data.get(offset..offset + 2)?.try_into().ok()?;
+        Some(if self.little_endian { u16::from_le_bytes(bytes) } else { u16::from_be_bytes(bytes) })
+    }
+
+    fn u32_at(&self, offset: usize) -> Option<u32> {
+        let bytes: [u8; 4] = self.data.get(offset..offset + 4)?.try_into().ok()?;
+        Some(if self.little_endian { u32::from_le_bytes(bytes) } else { u32::from_be_bytes(bytes) })
+    }
+
+    fn u16_bytes(&self, value: u16) -> [u8; 2] {
+        if self.little_endian { value.to_le_bytes() } else { value.to_be_bytes() }
+    }
+
+    /// Offset of IFD0 and its entry count
+    fn ifd0(&self) -> Option<(usize, usize)> {
+        let offset = self.u32_at(4)? as usize;
+        Some((offset, self.u16_at(offset)? as usize))
+    }
+
+    /// Offset of the IFD0 entry pointing at the GPS IFD, if any
+    fn gps_pointer_entry(&self) -> Option<usize> {
+        let (ifd0, count) = self.ifd0()?;
+        (0..count)
+            .map(|index| ifd0 + 2 + index * 12)
+            .find(|&entry| self.u16_at(entry) == Some(GPS_IFD_POINTER))
+    }
+
+    /// Offset of the entry with `tag` in the IFD at `ifd`
+    fn find_entry(&self, ifd: usize, tag: u16) -> Option<usize> {
+        let count = self.u16_at(ifd)? as usize;
+        (0..count)
+            .map(|index| ifd + 2 + index * 12)
+            .find(|&entry| self.u16_at(entry) == Some(tag))
+    }
+
+    /// Decimal degrees from a degrees/minutes/seconds entry, negated when the
+    /// reference entry holds `negative` (`S` or `W`)
+    fn coordinate(&self, ifd: usize, tag: u16, ref_tag: u16, negative: u8) -> Option<f64> {
+        let entry = self.find_entry(ifd, tag)?;
+        if self.u16_at(entry + 2)? != RATIONAL || self.u32_at(entry + 4)? != 3 {
+            return None;
+        }
+        let values = self.u32_at(entry + 8)? as usize;
+        let mut degrees = 0.0;
+        for (index, unit) in [1.0, 60.0, 3600.0].into_iter().enumerate() {
+            let numerator = self.u32_at(values + index * 8)?;
+            let denominator = self.u32_at(values + index * 8 + 4)?;
+            if denominator == 0 {
+                return None;
+            }
+            degrees += numerator as f64 / denominator as f64 / unit;
+        }
+        // The one-letter ASCII reference fits inline in the entry's value field
+        let reference = self.find_entry(ifd, ref_tag).and_then(|entry| self.data.get(entry + 8));
+        Some(if reference == Some(&negative) { -degrees } else { degrees })
+    }
+}
+
+/// Size in bytes of one value of a TIFF field type
+fn type_size(field_type: u16) -> Option<usize> {
+    match field_type {
+        1 | 2 | 6 | 7 => Some(1),
+        3 | 8 => Some(2),
+        4 | 9 | 11 => Some(4),
+        5 | 10 | 12 => Some(8),
+        _ => None,
+    }
+}
+
+/// Whether the EXIF block links to a GPS IFD
+pub fn has_gps(exif: &[u8]) -> bool {
+    Tiff::parse(exif).and_then(|tiff| tiff.gps_pointer_entry()).is_some()
+}
+
+/// Latitude and longitude in decimal degrees, south and west negative
+///
+/// Returns `None` when the block has no GPS IFD or no complete coordinates.
+pub fn gps_coordinates(exif: &[u8]) -> Option<(f64, f64)> {
+    let tiff = Tiff::parse(exif)?;
+    let gps_ifd = tiff.u32_at(tiff.gps_pointer_entry()? + 8)? as usize;
+    let latitude = tiff.coordinate(gps_ifd, GPS_LATITUDE, GPS_LATITUDE_REF, b'S')?;
+    let longitude = tiff.coordinate(gps_ifd, GPS_LONGITUDE, GPS_LONGITUDE_REF, b'W')?;
+    Some((latitude, longitude))
+}
+
+/// Remove location data from an EXIF block
+///
+/// The GPS pointer is dropped from IFD0 and the GPS IFD and its values are
+/// zeroed so the coordinates are not left behind as orphaned bytes. Returns
+/// `None` when the block cannot be parsed, in which case callers should
+/// drop the EXIF entirely rather than risk forwarding location data.
+pub fn strip_gps(exif: &[u8]) -> Option<Vec<u8>> {
+    let tiff = Tiff::parse(exif)?;
+    let Some(pointer_entry) = tiff.gps_pointer_entry() else {
+        return Some(exif.to_vec());
+    };
+    let (ifd0, count) = tiff.ifd0()?;
+    let gps_ifd = tiff.u32_at(pointer_entry + 8)? as usize;
+    let gps_count = tiff.u16_at(gps_ifd)? as usize;
+
+    // Collect every byte range belonging to the GPS IFD before mutating
+    let mut ranges = Vec::with_capacity(gps_count + 1);
+    ranges.push(gps_ifd..gps_ifd + 2 + gps_count * 12 + 4);
+    for entry in (0..gps_count).map(|index| gps_ifd + 2 + index * 12) {
+        let size = type_size(tiff.u16_at(entry + 2)?)? * tiff.u32_at(entry + 4)? as usize;
+        if size > 4 {
+            let offset = tiff.u32_at(entry + 8)? as usize;
+            ranges.push(offset..offset.checked_add(size)?);
+        }
+    }
+    let ifd0_end = ifd0 + 2 + count * 12 + 4;
+    let count_bytes = tiff.u16_bytes(count as u16 - 1);
+    if ranges.iter().chain([&(ifd0..ifd0_end)]).any(|range| range.end > exif.len()) {
+        return None;
+    }
+
+    let mut stripped = exif.to_vec();
+    for range in ranges {
+        stripped[range].fill(0);
+    }
+    // Shift the remaining entries and the next-IFD offset over the GPS pointer
+    stripped.copy_within(pointer_entry + 12..ifd0_end, pointer_entry);
+    stripped[ifd0_end - 12..ifd0_end].fill(0);
+    stripped[ifd0..ifd0 + 2].copy_from_slice(&count_bytes);
+    Some(stripped)
+}
+
+/// Little-endian EXIF block with an orientation tag and GPS coordinates 51°30'7"N 0°7'39"W
+#[cfg(test)]
+pub(crate) fn sample_with_gps() -> Vec<u8> {
+    let mut exif = vec![b'I', b'I', 42, 0, 8, 0, 0, 0];
+    // IFD0: orientation, GPS pointer to offset 38, no next IFD
+    exif.extend_from_slice(&[2, 0]);
+    exif.extend_from_slice(&[0x12, 0x01, 3, 0, 1, 0, 0, 0, 1, 0, 0, 0]);
+    exif.extend_from_slice(&[0x25, 0x88, 4, 0, 1, 0, 0, 0, 38, 0, 0, 0]);
+    exif.extend_from_slice(&[0, 0, 0, 0]);
+    // GPS IFD: inline N/W references, rationals for latitude at 92 and longitude at 116
+    exif.extend_from_slice(&[4, 0]);
+    exif.extend_from_slice(&[0x01, 0x00, 2, 0, 2, 0, 0, 0, b'N', 0, 0, 0]);
+    exif.extend_from_slice(&[0x02, 0x00, 5, 0, 3, 0, 0, 0, 92, 0, 0, 0]);
+    exif.extend_from_slice(&[0x03, 0x00, 2, 0, 2, 0, 0, 0, b'W', 0, 0, 0]);
+    exif.extend_from_slice(&[0x04, 0x00, 5, 0, 3, 0, 0, 0, 116, 0, 0, 0]);
+    exif.extend_from_slice(&[0, 0, 0, 0]);
+    for value in [51u32, 1, 30, 1, 7, 1, 0, 1, 7, 1, 39, 1] {
+        exif.extend_from_slice(&value.to_le_bytes());
+    }
+    exif
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_strip_gps_removes_pointer_and_values() {
+        let exif = sample_with_gps();
+        assert!(has_gps(&exif));
+
+        let stripped = strip_gps(&exif).unwrap();
+        assert!(!has_gps(&stripped));
+        assert_eq!(stripped.len(), exif.len());
+        // Orientation survives as the only IFD0 entry
+        let tiff = Tiff::parse(&stripped).unwrap();
+        assert_eq!(tiff.ifd0(), Some((8, 1)));
+        assert_eq!(tiff.u16_at(10), Some(0x0112));
+        assert!(stripped[38..].iter().all(|&byte| byte == 0));
+    }
+
+    #[test]
+    fn test_gps_coordinates() {
+        let (latitude, longitude) = gps_coordinates(&sample_with_gps()).unwrap();
+        assert!((latitude - 51.501_944).abs() < 1e-5, "{}", latitude);
+        assert!((longitude + 0.1275).abs() < 1e-5, "{}", longitude);
+
+        assert_eq!(gps_coordinates(&strip_gps(&sample_with_gps()).unwrap()), None);
+        assert_eq!(gps_coordinates(b"not exif"), None);
+    }
+
+    #[test]
+    fn test_strip_gps_passes_through_or_rejects() {
+        let mut without_gps = sample_with_gps();
+        without_gps[8] = 1;
+        assert_eq!(strip_gps(&without_gps), Some(without_gps.clone()));
+
+        assert_eq!(strip_gps(b"not exif"), None);
+        assert_eq!(strip_gps(&sample_with_gps()[..50]), None);
+    }
+}
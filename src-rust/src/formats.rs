@@ -0,0 +1,510 @@
+//! Conversions between JSON and other data formats
+//!
+//! Each parser turns its input into a `serde_json::Value` so the rest of
+//! `process_data` can treat every format as JSON.
+
+use quick_xml::events::{BytesStart, Event};
+use quick_xml::Reader;
+use serde::{Deserialize, Serialize};
+use serde_json::{Map, Value};
+use std::collections::BTreeSet;
+
+/// Parse CSV text into a JSON array.
+///
+/// With headers (the default) each row becomes an object keyed by the
+/// header row; without headers each row becomes an array of strings.
+/// Rows with a different number of fields than the first are rejected.
+pub fn csv_to_json(input: &str, delimiter: Option<char>, has_headers: bool) -> Result<Value, String> {
+    let delimiter = csv_delimiter(delimiter)?;
+    let mut reader = csv::ReaderBuilder::new()
+        .delimiter(delimiter)
+        .has_headers(has_headers)
+        .from_reader(input.as_bytes());
+
+    let headers: Vec<String> = if has_headers {
+        reader.headers()
+            .map_err(|e| format!("CSV parse error: {}", e))?
+            .iter()
+            .map(str::to_string)
+            .collect()
+    } else {
+        Vec::new()
+    };
+
+    let mut rows = Vec::new();
+    for record in reader.records() {
+        let record = record.map_err(|e| format!("CSV parse error: {}", e))?;
+        let row = if has_headers {
+            let object: Map<String, Value> = headers.iter()
+                .zip(record.iter())
+                .map(|(key, field)| (key.clone(), Value::String(field.to_string())))
+                .collect();
+            Value::Object(object)
+        } else {
+            Value::Array(record.iter().map(|field| Value::String(field.to_string())).collect())
+        };
+        rows.push(row);
+    }
+
+    Ok(Value::Array(rows))
+}
+
+/// Serialize a JSON array of objects (or a single object) as CSV text.
+///
+/// The header row is the sorted union of every row's keys, and keys missing
+/// from a row become empty cells. Nested values are rejected unless
+/// `flatten_nested` is set, in which case they are expanded into dotted keys
+/// such as `address.city` and `tags[0]`.
+pub fn json_to_csv(data: &Value, delimiter: Option<char>, flatten_nested: bool) -> Result<String, String> {
+    let rows: Vec<&Value> = match data {
+        Value::Array(items) => items.iter().collect(),
+        Value::Object(_) => vec![data],
+        _ => return Err("json_to_csv expects an array of objects".to_string()),
+    };
+
+    let mut objects = Vec::with_capacity(rows.len());
+    for (index, row) in rows.into_iter().enumerate() {
+        let Value::Object(object) = row else {
+            return Err(format!("Row {} is not an object", index));
+        };
+        if flatten_nested {
+            if let Value::Object(flat) = crate::json_ops::flatten_json(row, ".") {
+                objects.push(flat);
+            }
+        } else {
+            objects.push(object.clone());
+        }
+    }
+
+    let headers: BTreeSet<&String> = objects.iter().flat_map(|object| object.keys()).collect();
+
+    let mut writer = csv::WriterBuilder::new()
+        .delimiter(csv_delimiter(delimiter)?)
+        .from_writer(Vec::new());
+    writer.write_record(&headers).map_err(|e| format!("CSV write error: {}", e))?;
+
+    for (index, object) in objects.iter().enumerate() {
+        let record = headers.iter()
+            .map(|key| match object.get(*key) {
+                Some(value) => csv_cell(value, flatten_nested)
+                    .map_err(|e| format!("Row {} field '{}': {}", index, key, e)),
+                None => Ok(String::new()),
+            })
+            .collect::<Result<Vec<String>, String>>()?;
+        writer.write_record(&record).map_err(|e| format!("CSV write error: {}", e))?;
+    }
+
+    let bytes = writer.into_inner().map_err(|e| format!("CSV write error: {}", e))?;
+    String::from_utf8(bytes).map_err(|e| format!("CSV write error: {}", e))
+}
+
+/// Parse YAML text into JSON.
+///
+/// A single document is returned as-is; a stream of several `---`-separated
+/// documents is returned as an array. Mapping keys keep their source order.
+pub fn yaml_to_json(input: &str) -> Result<Value, String> {
+    let mut documents = serde_yaml::Deserializer::from_str(input)
+        .map(|document| Value::deserialize(document).map_err(|e| format!("YAML parse error: {}", e)))
+        .collect::<Result<Vec<Value>, String>>()?;
+
+    match documents.len() {
+        0 => Ok(Value::Null),
+        1 => Ok(documents.remove(0)),
+        _ => Ok(Value::Array(documents)),
+    }
+}
+
+/// Serialize JSON as YAML text
+pub fn json_to_yaml(data: &Value) -> Result<String, String> {
+    serde_yaml::to_string(data).map_err(|e| format!("YAML write error: {}", e))
+}
+
+/// Parse TOML text into a JSON object.
+///
+/// Datetimes become RFC 3339 strings; non-finite floats become `null`.
+/// Parse errors carry the line and column of the problem.
+pub fn toml_to_json(input: &str) -> Result<Value, String> {
+    let table: toml::Table = input.parse().map_err(|e: toml::de::Error| {
+        format!("TOML parse error: {}", e.to_string().trim_end())
+    })?;
+    Ok(toml_value(toml::Value::Table(table)))
+}
+
+/// Serialize JSON as TOML text; the top level must be an object and
+/// `null` has no TOML equivalent
+pub fn json_to_toml(data: &Value) -> Result<String, String> {
+    match json_to_toml_value(data)? {
+        toml::Value::Table(table) => toml::to_string(&table).map_err(|e| format!("TOML write error: {}", e)),
+        _ => Err("TOML output requires a top-level object".to_string()),
+    }
+}
+
+// Converted by hand because arbitrary-precision numbers don't serialize as TOML numbers
+fn json_to_toml_value(value: &Value) -> Result<toml::Value, String> {
+    Ok(match value {
+        Value::Null => return Err("TOML cannot represent null values".to_string()),
+        Value::Bool(flag) => toml::Value::Boolean(*flag),
+        Value::Number(number) => match number.as_i64() {
+            Some(integer) => toml::Value::Integer(integer),
+            None => toml::Value::Float(number.as_f64().ok_or("Number out of range for TOML")?),
+        },
+        Value::String(text) => toml::Value::String(text.clone()),
+        Value::Array(items) => toml::Value::Array(items.iter().map(json_to_toml_value).collect::<Result<_, _>>()?),
+        Value::Object(object) => toml::Value::Table(
+            object.iter()
+                .map(|(key, value)| Ok((key.clone(), json_to_toml_value(value)?)))
+                .collect::<Result<_, String>>()?
+        ),
+    })
+}
+
+fn toml_value(value: toml::Value) -> Value {
+    match value {
+        toml::Value::String(text) => Value::String(text),
+        toml::Value::Integer(number) => Value::from(number),
+        toml::Value::Float(number) => serde_json::Number::from_f64(number).map_or(Value::Null, Value::Number),
+        toml::Value::Boolean(flag) => Value::Bool(flag),
+        toml::Value::Datetime(datetime) => Value::String(datetime.to_string()),
+        toml::Value::Array(items) => Value::Array(items.into_iter().map(toml_value).collect()),
+        toml::Value::Table(table) => Value::Object(
+            table.into_iter().map(|(key, value)| (key, toml_value(value))).collect()
+        ),
+    }
+}
+
+/// Serialize JSON with `indent` spaces per nesting level
+pub fn prettify_json(data: &Value, indent: usize) -> Result<String, String> {
+    let indent = " ".repeat(indent);
+    let formatter = serde_json::ser::PrettyFormatter::with_indent(indent.as_bytes());
+    let mut serializer = serde_json::Serializer::with_formatter(Vec::new(), formatter);
+    data.serialize(&mut serializer).map_err(|e| format!("Serialize error: {}", e))?;
+    String::from_utf8(serializer.into_inner()).map_err(|e| format!("Serialize error: {}", e))
+}
+
+/// Prefix given to keys holding XML attributes
+pub const XML_ATTRIBUTE_PREFIX: &str = "@";
+/// Key holding the text content of an XML element that also has attributes or children
+pub const XML_TEXT_KEY: &str = "#text";
+
+/// Parse XML text into JSON.
+///
+/// Each element becomes a key of its parent object. Attributes are stored
+/// under `@name` keys and text content under `#text`; an element with only
+/// text collapses to a plain string and an empty element to `null`. Sibling
+/// elements sharing a name are gathered into an array. Comments, processing
+/// instructions and the XML declaration are ignored.
+pub fn xml_to_json(input: &str) -> Result<Value, String> {
+    let mut reader = Reader::from_str(input);
+    reader.config_mut().trim_text(true);
+
+    // Open elements: (name, attributes and children, text content)
+    let mut stack: Vec<(String, Map<String, Value>, String)> =
+        vec![(String::new(), Map::new(), String::new())];
+    let error = |reader: &Reader<&[u8]>, e: &dyn std::fmt::Display| {
+        format!("XML parse error at byte {}: {}", reader.error_position(), e)
+    };
+
+    loop {
+        match reader.read_event() {
+            Ok(Event::Start(start)) => {
+                let (name, attributes) = xml_element(&start).map_err(|e| error(&reader, &e))?;
+                stack.push((name, attributes, String::new()));
+            }
+            Ok(Event::Empty(start)) => {
+                let (name, attributes) = xml_element(&start).map_err(|e| error(&reader, &e))?;
+                let value = xml_value(attributes, String::new());
+                if let Some((_, parent, _)) = stack.last_mut() {
+                    xml_insert(parent, name, value);
+                }
+            }
+            Ok(Event::Text(text)) => {
+                let text = text.unescape().map_err(|e| error(&reader, &e))?;
+                if let Some((_, _, content)) = stack.last_mut() {
+                    content.push_str(&text);
+                }
+            }
+            Ok(Event::CData(data)) => {
+                if let Some((_, _, content)) = stack.last_mut() {
+                    content.push_str(&String::from_utf8_lossy(&data));
+                }
+            }
+            Ok(Event::End(_)) => {
+                if let Some((name, attributes, content)) = stack.pop() {
+                    let value = xml_value(attributes, content);
+                    if let Some((_, parent, _)) = stack.last_mut() {
+                        xml_insert(parent, name, value);
+                    }
+                }
+            }
+            Ok(Event::Eof) => break,
+            Ok(_) => {}
+            Err(e) => return Err(error(&reader, &e)),
+        }
+    }
+
+    match stack.pop() {
+        Some((_, root, _)) if stack.is_empty() => Ok(Value::Object(root)),
+        Some((name, _, _)) => Err(format!(
+            "XML parse error at byte {}: unclosed element <{}>",
+            input.len(),
+            name
+        )),
+        None => Err("XML parse error: unbalanced elements".to_string()),
+    }
+}
+
+fn xml_element(start: &BytesStart) -> Result<(String, Map<String, Value>), quick_xml::Error> {
+    let name = String::from_utf8_lossy(start.name().as_ref()).into_owned();
+    let mut attributes = Map::new();
+    for attribute in start.attributes() {
+        let attribute = attribute.map_err(quick_xml::Error::from)?;
+        let key = format!("{}{}", XML_ATTRIBUTE_PREFIX, String::from_utf8_lossy(attribute.key.as_ref()));
+        attributes.insert(key, Value::String(attribute.unescape_value()?.into_owned()));
+    }
+    Ok((name, attributes))
+}
+
+fn xml_value(mut object: Map<String, Value>, text: String) -> Value {
+    if object.is_empty() {
+        if text.is_empty() { Value::Null } else { Value::String(text) }
+    } else {
+        if !text.is_empty() {
+            object.insert(XML_TEXT_KEY.to_string(), Value::String(text));
+        }
+        Value::Object(object)
+    }
+}
+
+/// Insert a child element, turning repeated names into an array
+fn xml_insert(parent: &mut Map<String, Value>, name: String, value: Value) {
+    match parent.get_mut(&name) {
+        Some(Value::Array(items)) => items.push(value),
+        Some(existing) => {
+            let first = existing.take();
+            *existing = Value::Array(vec![first, value]);
+        }
+        None => {
+            parent.insert(name, value);
+        }
+    }
+}
+
+/// Guess the format of `input` from its shape, returning the format name
+/// (`json`, `yaml`, `csv`, `xml` or `unknown`) and a confidence in 0.0-1.0.
+///
+/// Only cheap textual heuristics are used; nothing is parsed.
+pub fn detect_format(input: &str) -> (&'static str, f64) {
+    let trimmed = input.trim();
+    if trimmed.is_empty() {
+        return ("unknown", 0.0);
+    }
+    if trimmed.starts_with("<?xml") {
+        return ("xml", 0.99);
+    }
+    if trimmed.starts_with('<') {
+        return ("xml", if trimmed.ends_with('>') { 0.9 } else { 0.6 });
+    }
+    if let Some(close) = match trimmed.as_bytes()[0] {
+        b'{' => Some('}'),
+        b'[' => Some(']'),
+        _ => None,
+    } {
+        return ("json", if trimmed.ends_with(close) { 0.95 } else { 0.6 });
+    }
+    if trimmed.starts_with("---") {
+        return ("yaml", 0.9);
+    }
+
+    let lines: Vec<&str> = trimmed.lines().filter(|line| !line.trim().is_empty()).collect();
+    let commas = lines[0].matches(',').count();
+    if commas > 0 && lines.len() > 1 && lines.iter().all(|line| line.matches(',').count() == commas) {
+        return ("csv", 0.85);
+    }
+    let yaml_lines = lines.iter()
+        .filter(|line| {
+            let line = line.trim_start();
+            line.starts_with("- ") || line.split_once(": ").is_some_and(|(key, _)| !key.contains(' '))
+                || line.ends_with(':')
+        })
+        .count();
+    if yaml_lines * 2 >= lines.len() {
+        return ("yaml", if yaml_lines == lines.len() { 0.8 } else { 0.6 });
+    }
+    if commas > 0 {
+        return ("csv", 0.4);
+    }
+    ("unknown", 0.0)
+}
+
+fn csv_delimiter(delimiter: Option<char>) -> Result<u8, String> {
+    match delimiter {
+        Some(c) if c.is_ascii() => Ok(c as u8),
+        Some(c) => Err(format!("CSV delimiter must be an ASCII character, got '{}'", c)),
+        None => Ok(b','),
+    }
+}
+
+/// Render a scalar as a CSV cell. After flattening only empty containers can
+/// remain, and those are written as their JSON text.
+fn csv_cell(value: &Value, flattened: bool) -> Result<String, String> {
+    match value {
+        Value::Null => Ok(String::new()),
+        Value::String(s) => Ok(s.clone()),
+        Value::Bool(_) | Value::Number(_) => Ok(value.to_string()),
+        Value::Array(_) | Value::Object(_) if flattened => Ok(value.to_string()),
+        Value::Array(_) | Value::Object(_) => {
+            Err("nested value; set flatten_nested to expand it into dotted columns".to_string())
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    #[test]
+    fn test_csv_with_headers() {
+        let data = csv_to_json("name,age\nalice,30\nbob,25\n", None, true).unwrap();
+        assert_eq!(data, json!([
+            {"name": "alice", "age": "30"},
+            {"name": "bob", "age": "25"},
+        ]));
+    }
+
+    #[test]
+    fn test_csv_semicolon_delimiter() {
+        let data = csv_to_json("name;age\nalice;30\nbob;25", Some(';'), true).unwrap();
+        assert_eq!(data[1], json!({"name": "bob", "age": "25"}));
+    }
+
+    #[test]
+    fn test_csv_without_headers() {
+        let data = csv_to_json("a\tb\nc\td", Some('\t'), false).unwrap();
+        assert_eq!(data, json!([["a", "b"], ["c", "d"]]));
+    }
+
+    #[test]
+    fn test_csv_ragged_rows_rejected() {
+        let error = csv_to_json("a,b\n1,2\n3,4,5\n", None, true).unwrap_err();
+        assert!(error.starts_with("CSV parse error"));
+        assert!(error.contains("fields"));
+    }
+
+    #[test]
+    fn test_json_to_csv_fills_missing_keys() {
+        let data = json!([{"b": 1, "a": "x"}, {"c": true}]);
+        let csv = json_to_csv(&data, None, false).unwrap();
+        assert_eq!(csv, "a,b,c\nx,1,\n,,true\n");
+    }
+
+    #[test]
+    fn test_json_to_csv_nested_values() {
+        let data = json!([{"id": 1, "address": {"city": "Oslo"}, "tags": ["a", "b"]}]);
+        assert!(json_to_csv(&data, None, false).unwrap_err().contains("nested value"));
+
+        let csv = json_to_csv(&data, None, true).unwrap();
+        assert_eq!(csv, "address.city,id,tags[0],tags[1]\nOslo,1,a,b\n");
+    }
+
+    #[test]
+    fn test_csv_round_trip() {
+        let input = "id,name\n1,alice\n2,bob\n";
+        let data = csv_to_json(input, None, true).unwrap();
+        assert_eq!(json_to_csv(&data, None, false).unwrap(), input);
+    }
+
+    #[test]
+    fn test_yaml_preserves_key_order() {
+        let data = yaml_to_json("name: test\nitems:\n  - 1\n  - 2\nactive: true\n").unwrap();
+        assert_eq!(data, json!({"name": "test", "items": [1, 2], "active": true}));
+        let keys: Vec<&String> = data.as_object().unwrap().keys().collect();
+        assert_eq!(keys, ["name", "items", "active"]);
+    }
+
+    #[test]
+    fn test_yaml_multi_document() {
+        let data = yaml_to_json("---\nid: 1\n---\nid: 2\n").unwrap();
+        assert_eq!(data, json!([{"id": 1}, {"id": 2}]));
+    }
+
+    #[test]
+    fn test_yaml_error_has_line_number() {
+        let error = yaml_to_json("a: 1\nb: [1, 2\n").unwrap_err();
+        assert!(error.starts_with("YAML parse error"));
+        assert!(error.contains("line"), "{}", error);
+    }
+
+    #[test]
+    fn test_json_to_yaml_round_trip() {
+        let data = json!({"name": "test", "tags": ["a", "b"]});
+        let yaml = json_to_yaml(&data).unwrap();
+        assert_eq!(yaml, "name: test\ntags:\n- a\n- b\n");
+        assert_eq!(yaml_to_json(&yaml).unwrap(), data);
+    }
+
+    #[test]
+    fn test_xml_attributes_and_text() {
+        let data = xml_to_json(r#"<root a="1"><child>x</child></root>"#).unwrap();
+        assert_eq!(data, json!({"root": {"@a": "1", "child": "x"}}));
+    }
+
+    #[test]
+    fn test_xml_repeated_and_mixed_elements() {
+        let input = r#"<?xml version="1.0"?>
+            <rss><item id="1">first</item><item id="2"/><item>third</item><empty/></rss>"#;
+        let data = xml_to_json(input).unwrap();
+        assert_eq!(data, json!({"rss": {
+            "item": [{"@id": "1", "#text": "first"}, {"@id": "2"}, "third"],
+            "empty": null,
+        }}));
+    }
+
+    #[test]
+    fn test_xml_error_has_byte_offset() {
+        let error = xml_to_json("<root><child></root>").unwrap_err();
+        assert!(error.starts_with("XML parse error at byte"), "{}", error);
+
+        let error = xml_to_json("<root><child>").unwrap_err();
+        assert!(error.contains("unclosed element <child>"), "{}", error);
+    }
+
+    #[test]
+    fn test_prettify_indent() {
+        let data = json!({"a": [1]});
+        assert_eq!(prettify_json(&data, 2).unwrap(), "{\n  \"a\": [\n    1\n  ]\n}");
+        assert_eq!(prettify_json(&data, 4).unwrap(), "{\n    \"a\": [\n        1\n    ]\n}");
+    }
+
+    #[test]
+    fn test_detect_format() {
+        assert_eq!(detect_format(r#"{"a": 1}"#), ("json", 0.95));
+        assert_eq!(detect_format("  [1, 2, 3]\n"), ("json", 0.95));
+        assert_eq!(detect_format("<?xml version=\"1.0\"?><a/>"), ("xml", 0.99));
+        assert_eq!(detect_format("<root><item/></root>"), ("xml", 0.9));
+        assert_eq!(detect_format("---\nname: app\n"), ("yaml", 0.9));
+        assert_eq!(detect_format("name: app\nitems:\n  - one\n  - two\n"), ("yaml", 0.8));
+        assert_eq!(detect_format("name,age\nalice,30\nbob,25\n"), ("csv", 0.85));
+        assert_eq!(detect_format("just some words"), ("unknown", 0.0));
+        assert_eq!(detect_format("   "), ("unknown", 0.0));
+    }
+
+    #[test]
+    fn test_toml_round_trip() {
+        let data = toml_to_json("[package]\nname = \"demo\"\nkeywords = [\"a\", \"b\"]\nreleased = 2024-01-02\n").unwrap();
+        assert_eq!(data, json!({
+            "package": {"name": "demo", "keywords": ["a", "b"], "released": "2024-01-02"},
+        }));
+
+        let text = json_to_toml(&json!({"server": {"port": 8080}})).unwrap();
+        assert_eq!(text, "[server]\nport = 8080\n");
+        assert!(json_to_toml(&json!([1, 2])).is_err());
+        assert!(json_to_toml(&json!({"missing": null})).is_err());
+    }
+
+    #[test]
+    fn test_toml_error_reports_position() {
+        let error = toml_to_json("[table]\nkey = \n").unwrap_err();
+        assert!(error.contains("line 2, column"), "{}", error);
+    }
+}
@@ -5,69 +5,1106 @@
 
 use photon_rs::PhotonImage;
 use serde::{Deserialize, Serialize};
-use image::{ImageFormat, DynamicImage};
+use image::{ImageDecoder, ImageEncoder, ImageFormat, DynamicImage};
+use rand::{Rng, SeedableRng};
+use rand::rngs::StdRng;
+use std::fmt;
 use std::io::Cursor;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::{LazyLock, OnceLock};
 
-#[derive(Debug, Serialize, Deserialize)]
+/// Errors produced while decoding, processing, or encoding an image
+#[derive(Debug, Clone, PartialEq)]
+pub enum ImageError {
+    DecodeBase64(String),
+    LoadImage(String),
+    UnsupportedFormat(String),
+    TooLarge {
+        width: u32,
+        height: u32,
+        max_pixels: u64,
+    },
+    InvalidCrop {
+        x: u32,
+        y: u32,
+        width: u32,
+        height: u32,
+        image_width: u32,
+        image_height: u32,
+    },
+    EncodeFailed(String),
+    UnknownFilter(String),
+    UnknownEffect(String),
+    InvalidParameter(String),
+    NotImplemented(String),
+    ExceedsMaxBytes {
+        max_bytes: usize,
+        size_bytes: usize,
+    },
+    InputTooLarge {
+        max_bytes: usize,
+        size_bytes: usize,
+    },
+    Panic,
+}
+
+impl ImageError {
+    /// Stable machine-readable identifier for the error kind
+    pub fn code(&self) -> &'static str {
+        match self {
+            ImageError::DecodeBase64(_) => "decode_base64",
+            ImageError::LoadImage(_) => "load_image",
+            ImageError::UnsupportedFormat(_) => "unsupported_format",
+            ImageError::TooLarge { .. } => "image_too_large",
+            ImageError::InvalidCrop { .. } => "invalid_crop",
+            ImageError::EncodeFailed(_) => "encode_failed",
+            ImageError::UnknownFilter(_) => "unknown_filter",
+            ImageError::UnknownEffect(_) => "unknown_effect",
+            ImageError::InvalidParameter(_) => "invalid_parameter",
+            ImageError::NotImplemented(_) => "not_implemented",
+            ImageError::ExceedsMaxBytes { .. } => "exceeds_max_bytes",
+            ImageError::InputTooLarge { .. } => "input_too_large",
+            ImageError::Panic => "panic",
+        }
+    }
+}
+
+impl fmt::Display for ImageError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            ImageError::DecodeBase64(e) => write!(f, "Failed to decode base64: {}", e),
+            ImageError::LoadImage(e) => write!(f, "Failed to load image: {}", e),
+            ImageError::UnsupportedFormat(format) => write!(f, "Unsupported output format: {}", format),
+            ImageError::TooLarge { width, height, max_pixels } => write!(
+                f,
+                "Image dimensions {}x{} exceed the limit of {} pixels",
+                width, height, max_pixels
+            ),
+            ImageError::InvalidCrop { x, y, width, height, image_width, image_height } => write!(
+                f,
+                "Crop region {}x{} at ({}, {}) is outside the {}x{} image",
+                width, height, x, y, image_width, image_height
+            ),
+            ImageError::EncodeFailed(e) => write!(f, "Encoding failed: {}", e),
+            ImageError::UnknownFilter(name) => write!(f, "Unknown filter: {}", name),
+            ImageError::UnknownEffect(name) => write!(f, "Unknown effect: {}", name),
+            ImageError::InvalidParameter(message) => write!(f, "{}", message),
+            ImageError::NotImplemented(message) => write!(f, "{}", message),
+            ImageError::ExceedsMaxBytes { max_bytes, size_bytes } => write!(
+                f,
+                "Smallest encoded output is {} bytes, which exceeds max_bytes of {}",
+                size_bytes, max_bytes
+            ),
+            ImageError::InputTooLarge { max_bytes, size_bytes } => write!(
+                f,
+                "Input is {} bytes, which exceeds max_input_bytes of {}",
+                size_bytes, max_bytes
+            ),
+            ImageError::Panic => write!(f, "Internal error: Rust code panicked during image processing"),
+        }
+    }
+}
+
+impl std::error::Error for ImageError {}
+
+impl Serialize for ImageError {
+    fn serialize<S: serde::Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        use serde::ser::SerializeStruct;
+        let mut state = serializer.serialize_struct("ImageError", 2)?;
+        state.serialize_field("code", self.code())?;
+        state.serialize_field("message", &self.to_string())?;
+        state.end()
+    }
+}
+
+/// Top-level operation requested for an image
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize)]
+#[cfg_attr(feature = "wasm", derive(tsify::Tsify))]
+#[serde(rename_all = "snake_case")]
+pub enum Operation {
+    #[default]
+    Filter,
+    Transform,
+    Adjust,
+    Effect,
+    Tint,
+    SwapChannels,
+    Blend,
+    Trim,
+    CropAspect,
+    ChromaKey,
+    LumaAlpha,
+    Canvas,
+    BlurRegions,
+    Normalize,
+    Identity,
+}
+
+impl Operation {
+    /// Every supported operation, in the order they are advertised to callers
+    pub fn all() -> &'static [Operation] {
+        &[
+            Operation::Filter,
+            Operation::Transform,
+            Operation::Adjust,
+            Operation::Effect,
+            Operation::Tint,
+            Operation::SwapChannels,
+            Operation::Blend,
+            Operation::Trim,
+            Operation::CropAspect,
+            Operation::ChromaKey,
+            Operation::LumaAlpha,
+            Operation::Canvas,
+            Operation::BlurRegions,
+            Operation::Normalize,
+            Operation::Identity,
+        ]
+    }
+
+    /// Wire name of the operation, matching its serde representation
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Operation::Filter => "filter",
+            Operation::Transform => "transform",
+            Operation::Adjust => "adjust",
+            Operation::Effect => "effect",
+            Operation::Tint => "tint",
+            Operation::SwapChannels => "swap_channels",
+            Operation::Blend => "blend",
+            Operation::Trim => "trim",
+            Operation::CropAspect => "crop_aspect",
+            Operation::ChromaKey => "chroma_key",
+            Operation::LumaAlpha => "luma_alpha",
+            Operation::Canvas => "canvas",
+            Operation::BlurRegions => "blur_regions",
+            Operation::Normalize => "normalize",
+            Operation::Identity => "identity",
+        }
+    }
+}
+
+/// Named preset filters available to the `filter` operation
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[cfg_attr(feature = "wasm", derive(tsify::Tsify))]
+#[serde(rename_all = "snake_case")]
+pub enum Filter {
+    Grayscale,
+    Sepia,
+    Invert,
+    Vintage,
+    Noir,
+    Warm,
+    Cool,
+    Dramatic,
+    Firenze,
+    Golden,
+    Lix,
+    Lofi,
+    Neue,
+    Obsidian,
+    PastelPink,
+    Ryo,
+}
+
+impl Filter {
+    /// Every supported filter, in the order they are advertised to callers
+    pub fn all() -> &'static [Filter] {
+        &[
+            Filter::Grayscale,
+            Filter::Sepia,
+            Filter::Invert,
+            Filter::Vintage,
+            Filter::Noir,
+            Filter::Warm,
+            Filter::Cool,
+            Filter::Dramatic,
+            Filter::Firenze,
+            Filter::Golden,
+            Filter::Lix,
+            Filter::Lofi,
+            Filter::Neue,
+            Filter::Obsidian,
+            Filter::PastelPink,
+            Filter::Ryo,
+        ]
+    }
+
+    /// Wire name of the filter, matching its serde representation
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Filter::Grayscale => "grayscale",
+            Filter::Sepia => "sepia",
+            Filter::Invert => "invert",
+            Filter::Vintage => "vintage",
+            Filter::Noir => "noir",
+            Filter::Warm => "warm",
+            Filter::Cool => "cool",
+            Filter::Dramatic => "dramatic",
+            Filter::Firenze => "firenze",
+            Filter::Golden => "golden",
+            Filter::Lix => "lix",
+            Filter::Lofi => "lofi",
+            Filter::Neue => "neue",
+            Filter::Obsidian => "obsidian",
+            Filter::PastelPink => "pastel_pink",
+            Filter::Ryo => "ryo",
+        }
+    }
+
+    /// Whether the filter reads `intensity` itself rather than being blended with the original
+    fn scales_with_intensity(&self) -> bool {
+        matches!(self, Filter::Warm | Filter::Cool | Filter::Vintage)
+    }
+}
+
+impl std::str::FromStr for Filter {
+    type Err = ImageError;
+
+    fn from_str(name: &str) -> Result<Self, Self::Err> {
+        Filter::all()
+            .iter()
+            .find(|filter| filter.as_str() == name)
+            .copied()
+            .ok_or_else(|| ImageError::UnknownFilter(name.to_string()))
+    }
+}
+
+/// Effects available to the `effect` operation, selected through `filter`
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[cfg_attr(feature = "wasm", derive(tsify::Tsify))]
+#[serde(rename_all = "snake_case")]
+pub enum Effect {
+    EdgeDetection,
+    Emboss,
+    Laplace,
+    SobelHorizontal,
+    SobelVertical,
+    Sobel,
+    Blur,
+    Sharpen,
+    Threshold,
+    Solarize,
+    Posterize,
+    Pixelate,
+    Convolve,
+    Noise,
+    Oil,
+    Quantize,
+    Dither,
+    Clahe,
+}
+
+impl Effect {
+    /// Every supported effect, in the order they are advertised to callers
+    pub fn all() -> &'static [Effect] {
+        &[
+            Effect::EdgeDetection,
+            Effect::Emboss,
+            Effect::Laplace,
+            Effect::SobelHorizontal,
+            Effect::SobelVertical,
+            Effect::Sobel,
+            Effect::Blur,
+            Effect::Sharpen,
+            Effect::Threshold,
+            Effect::Solarize,
+            Effect::Posterize,
+            Effect::Pixelate,
+            Effect::Convolve,
+            Effect::Noise,
+            Effect::Oil,
+            Effect::Quantize,
+            Effect::Dither,
+            Effect::Clahe,
+        ]
+    }
+
+    /// Wire name of the effect, matching its serde representation
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Effect::EdgeDetection => "edge_detection",
+            Effect::Emboss => "emboss",
+            Effect::Laplace => "laplace",
+            Effect::SobelHorizontal => "sobel_horizontal",
+            Effect::SobelVertical => "sobel_vertical",
+            Effect::Sobel => "sobel",
+            Effect::Blur => "blur",
+            Effect::Sharpen => "sharpen",
+            Effect::Threshold => "threshold",
+            Effect::Solarize => "solarize",
+            Effect::Posterize => "posterize",
+            Effect::Pixelate => "pixelate",
+            Effect::Convolve => "convolve",
+            Effect::Noise => "noise",
+            Effect::Oil => "oil",
+            Effect::Quantize => "quantize",
+            Effect::Dither => "dither",
+            Effect::Clahe => "clahe",
+        }
+    }
+}
+
+impl std::str::FromStr for Effect {
+    type Err = ImageError;
+
+    fn from_str(name: &str) -> Result<Self, Self::Err> {
+        Effect::all()
+            .iter()
+            .find(|effect| effect.as_str() == name)
+            .copied()
+            .ok_or_else(|| ImageError::UnknownEffect(name.to_string()))
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[cfg_attr(feature = "wasm", derive(tsify::Tsify))]
 pub struct ImageProcessingOptions {
-    pub operation: String,
+    pub operation: Operation,
+    #[serde(default)]
     pub filter: Option<String>,
+    /// Filters applied in order after `filter`, e.g. `["grayscale", "vintage"]`
+    #[serde(default)]
+    pub filters: Option<Vec<String>>,
+    #[serde(default)]
     pub intensity: Option<f32>,
+    #[serde(default)]
     pub brightness: Option<f32>,
+    #[serde(default)]
     pub contrast: Option<f32>,
+    #[serde(default)]
     pub saturation: Option<f32>,
+    #[serde(default)]
     pub hue_rotation: Option<f32>,
+    #[serde(default)]
     pub resize_width: Option<u32>,
+    #[serde(default)]
     pub resize_height: Option<u32>,
+    #[serde(default)]
     pub keep_aspect_ratio: Option<bool>,
+    #[serde(default)]
     pub crop_x: Option<u32>,
+    #[serde(default)]
     pub crop_y: Option<u32>,
+    #[serde(default)]
     pub crop_width: Option<u32>,
+    #[serde(default)]
     pub crop_height: Option<u32>,
+    /// Crop rectangle as percentages (0-100) of the image size, resolved at processing time
+    ///
+    /// Unset origin fields default to 0 and unset size fields to the rest of the image.
+    #[serde(default)]
+    pub crop_x_pct: Option<f32>,
+    #[serde(default)]
+    pub crop_y_pct: Option<f32>,
+    #[serde(default)]
+    pub crop_width_pct: Option<f32>,
+    #[serde(default)]
+    pub crop_height_pct: Option<f32>,
+    #[serde(default)]
     pub rotation_angle: Option<f32>,
+    #[serde(default)]
     pub flip_horizontal: Option<bool>,
+    #[serde(default)]
     pub flip_vertical: Option<bool>,
+    #[serde(default)]
     pub output_format: Option<String>,
+    #[serde(default)]
     pub quality: Option<u8>,
+    #[serde(default)]
     pub output_as_binary: Option<bool>,
+    #[serde(default)]
+    pub block_size: Option<u32>,
+    #[serde(default)]
+    pub kernel: Option<Vec<f32>>,
+    #[serde(default)]
+    pub kernel_divisor: Option<f32>,
+    #[serde(default)]
+    pub noise_amount: Option<f32>,
+    #[serde(default)]
+    pub noise_type: Option<String>,
+    #[serde(default)]
+    pub seed: Option<u64>,
+    #[serde(default)]
+    pub max_pixels: Option<u64>,
+    #[serde(default)]
+    pub oil_radius: Option<u32>,
+    #[serde(default)]
+    pub oil_levels: Option<u32>,
+    #[serde(default)]
+    pub tint_color: Option<String>,
+    #[serde(default)]
+    pub tint_strength: Option<f32>,
+    #[serde(default)]
+    pub channel_order: Option<String>,
+    #[serde(default)]
+    pub blend_base64: Option<String>,
+    #[serde(default)]
+    pub blend_mode: Option<String>,
+    #[serde(default)]
+    pub blend_opacity: Option<f32>,
+    #[serde(default)]
+    pub palette_size: Option<u32>,
+    #[serde(default)]
+    pub opacity: Option<f32>,
+    /// Largest acceptable encoded size; lossy formats lower quality until the output fits
+    #[serde(default)]
+    pub max_bytes: Option<usize>,
+    /// Mirror across the main diagonal, swapping width and height
+    #[serde(default)]
+    pub transpose: Option<bool>,
+    /// Where the encoded output goes: `data_url`, `base64` or `binary`; overrides `output_as_binary`
+    #[serde(default)]
+    pub output_encoding: Option<String>,
+    /// Stretch each color channel so its darkest value maps to 0 and brightest to 255
+    #[serde(default)]
+    pub auto_levels: Option<bool>,
+    /// Percent of pixels ignored at each end of the histogram by `auto_levels` (0.0-50.0)
+    #[serde(default)]
+    pub clip_percent: Option<f32>,
+    /// White balance color temperature, from -1.0 (cooler) to 1.0 (warmer)
+    #[serde(default)]
+    pub temperature: Option<f32>,
+    /// White balance tint, from -1.0 (greener) to 1.0 (more magenta)
+    #[serde(default)]
+    pub tint: Option<f32>,
+    /// Largest per-channel difference from the border color still treated as border by `trim`
+    #[serde(default)]
+    pub trim_tolerance: Option<u8>,
+    /// Decode the input and report its metadata without applying the operation or encoding output
+    #[serde(default)]
+    pub metadata_only: Option<bool>,
+    /// Drop EXIF from the output (default true)
+    ///
+    /// Output is always re-encoded, so stripping is the natural result. When
+    /// false, EXIF from the input is copied forward with GPS removed, but only
+    /// when the output format matches the input: JPEG carries it in an APP1
+    /// segment, PNG in an `eXIf` chunk and WebP in an `EXIF` chunk.
+    #[serde(default)]
+    pub strip_metadata: Option<bool>,
+    /// Channel the `threshold` effect compares against the cutoff: `luma` (default), `r`, `g` or `b`
+    #[serde(default)]
+    pub threshold_channel: Option<String>,
+    /// Make pixels below the `threshold` cutoff white instead of black
+    #[serde(default)]
+    pub threshold_invert: Option<bool>,
+    /// Number of evenly spaced values per channel kept by `posterize` (2-256, default 4)
+    #[serde(default)]
+    pub levels: Option<u32>,
+    /// Raster width for SVG input; height follows the aspect ratio when only one is set (`svg` feature)
+    #[serde(default)]
+    pub svg_width: Option<u32>,
+    /// Raster height for SVG input (`svg` feature)
+    #[serde(default)]
+    pub svg_height: Option<u32>,
+    /// Aspect ratio for the crop_aspect operation, e.g. `"16:9"` or `"1.5"`
+    #[serde(default)]
+    pub target_aspect: Option<String>,
+    /// Conversion used by the grayscale filter: `luminance` (Rec. 709 weights), `average`,
+    /// `lightness` or `single_channel_r`/`_g`/`_b`; defaults to photon's channel average
+    #[serde(default)]
+    pub grayscale_mode: Option<String>,
+    /// Stop a batch at the first failed image, returning the results so far
+    #[serde(default)]
+    pub fail_fast: Option<bool>,
+    /// Skip the resize when it would enlarge the image, returning the original size
+    #[serde(default)]
+    pub no_upscale: Option<bool>,
+    /// Base64 alphabet, `standard` or `url_safe`, for the input and raw base64 output;
+    /// input is auto-detected when unset, and data URLs always use the standard alphabet
+    #[serde(default)]
+    pub base64_variant: Option<String>,
+    /// Encode JPEG output progressively (multi-scan) instead of baseline
+    #[serde(default)]
+    pub jpeg_progressive: Option<bool>,
+    /// JPEG chroma subsampling: "4:4:4" (default), "4:2:2" or "4:2:0"
+    #[serde(default)]
+    pub jpeg_subsampling: Option<String>,
+    /// Resize sampling filter: `nearest`, `triangle`, `catmull_rom`, `gaussian` or `lanczos3`;
+    /// defaults to the configured `default_resize_filter`
+    #[serde(default)]
+    pub resize_filter: Option<String>,
+    /// Color the chroma_key operation makes transparent, as hex or `rgb()`; defaults to pure green
+    #[serde(default)]
+    pub key_color: Option<String>,
+    /// Largest per-channel difference from `key_color` that is still keyed out (default 32)
+    #[serde(default)]
+    pub key_tolerance: Option<u8>,
+    /// Luminance cutoff for the luma_alpha operation (default 240)
+    #[serde(default)]
+    pub luma_threshold: Option<u8>,
+    /// Make pixels at or above `luma_threshold` transparent (default true), or at or below it when false
+    #[serde(default)]
+    pub luma_above: Option<bool>,
+    /// Width of the canvas operation's canvas; defaults to the image width
+    #[serde(default)]
+    pub canvas_width: Option<u32>,
+    /// Height of the canvas operation's canvas; defaults to the image height
+    #[serde(default)]
+    pub canvas_height: Option<u32>,
+    /// Where the canvas operation places the image: `center` (default), `top_left`, `top`, `top_right`, `left`, `right`, `bottom_left`, `bottom` or `bottom_right`
+    #[serde(default)]
+    pub anchor: Option<String>,
+    /// Fill color for uncovered canvas, as hex or `rgb()`/`rgba()`; defaults to transparent
+    #[serde(default)]
+    pub canvas_color: Option<String>,
+    /// Tile edge length in pixels for the clahe effect (default 64)
+    #[serde(default)]
+    pub clahe_tile_size: Option<u32>,
+    /// Histogram clip limit for the clahe effect, as a multiple of the mean bin count (default 2.0)
+    #[serde(default)]
+    pub clahe_clip_limit: Option<f32>,
+    /// Apply brightness as an exposure gain in linear light rather than an offset on sRGB values
+    #[serde(default)]
+    pub linear_light: Option<bool>,
+    /// Rectangles as `[x, y, width, height]` for the blur_regions operation
+    #[serde(default)]
+    pub regions: Option<Vec<[u32; 4]>>,
+    /// Measure `processing_time_ms`; off by default so identical inputs serialize identically
+    #[serde(default)]
+    pub include_timing: Option<bool>,
+    /// Sample depth for the adjust operation, 8 (default) or 16; 16 processes and writes 16 bits per channel (png or tiff)
+    #[serde(default)]
+    pub bit_depth: Option<u8>,
+    /// Reject inputs larger than this many bytes, checked before any decoding
+    #[serde(default)]
+    pub max_input_bytes: Option<usize>,
 }
 
 #[derive(Debug, Serialize, Deserialize)]
+#[cfg_attr(feature = "wasm", derive(tsify::Tsify))]
 pub struct ImageMetadata {
     pub width: u32,
     pub height: u32,
     pub format: String,
     pub size_bytes: usize,
     pub processing_time_ms: u128,
+    /// Colors chosen by palette-reducing effects such as `quantize`, as `#rrggbb`
+    pub palette: Option<Vec<String>>,
+    /// Quality the output was encoded at, for lossy formats
+    pub quality: Option<u8>,
+    /// Width divided by height; above 1.0 is landscape
+    pub aspect_ratio: f32,
+    /// Pixel count in millions
+    pub megapixels: f32,
+    /// Decoded color layout (`grayscale`, `grayscale_alpha`, `rgb` or `rgba`), reported by `get_image_info`
+    #[serde(default)]
+    pub color_type: Option<String>,
+    /// Bits per channel, reported by `get_image_info`
+    #[serde(default)]
+    pub bit_depth: Option<u8>,
+    /// Whether the image carries an alpha channel, reported by `get_image_info`
+    #[serde(default)]
+    pub has_alpha: Option<bool>,
+}
+
+impl ImageMetadata {
+    /// Describe an image of the given size, deriving aspect ratio and megapixels
+    fn new(width: u32, height: u32, format: String, size_bytes: usize) -> Self {
+        Self {
+            width,
+            height,
+            format,
+            size_bytes,
+            processing_time_ms: 0,
+            palette: None,
+            quality: None,
+            aspect_ratio: width as f32 / height as f32,
+            megapixels: (width as u64 * height as u64) as f32 / 1_000_000.0,
+            color_type: None,
+            bit_depth: None,
+            has_alpha: None,
+        }
+    }
 }
 
 #[derive(Debug, Serialize, Deserialize)]
+#[cfg_attr(feature = "wasm", derive(tsify::Tsify))]
 pub struct ImageProcessingResult {
     pub success: bool,
     pub image_data: Option<String>, // base64 encoded (data URL or raw base64)
     pub binary_data: Option<Vec<u8>>, // raw binary data
     pub metadata: Option<ImageMetadata>,
     pub error: Option<String>,
+    /// Stable `ImageError::code` of the failure, such as `decode_base64` or `panic`
+    #[serde(default)]
+    pub error_code: Option<String>,
+    /// Inputs that were ignored or clamped while producing the image
+    #[serde(default)]
+    pub warnings: Vec<String>,
+    /// Position of the input within its batch (0 outside batches), for
+    /// correlating results with inputs regardless of processing order
+    #[serde(default)]
+    pub index: usize,
+}
+
+impl ImageProcessingResult {
+    /// Tag the result with its position in the batch
+    fn with_index(mut self, index: usize) -> Self {
+        self.index = index;
+        self
+    }
+
+    /// Build a failed result carrying the given error
+    pub fn failure(error: ImageError) -> Self {
+        Self {
+            success: false,
+            image_data: None,
+            binary_data: None,
+            metadata: None,
+            error: Some(error.to_string()),
+            error_code: Some(error.code().to_string()),
+            warnings: Vec::new(),
+            index: 0,
+        }
+    }
 }
 
 #[derive(Debug, Serialize, Deserialize)]
+#[cfg_attr(feature = "wasm", derive(tsify::Tsify))]
 pub struct BatchProcessingResult {
     pub processed: usize,
     pub successful: usize,
     pub failed: usize,
     pub results: Vec<ImageProcessingResult>,
     pub total_time_ms: u128,
+    /// The batch was cancelled before every image was processed
+    #[serde(default)]
+    pub cancelled: bool,
+}
+
+/// Check for operation-specific options that the chosen operation ignores,
+/// such as `flip_horizontal` under `filter`; the error names every such field
+pub fn validate_options(options: &ImageProcessingOptions) -> Result<(), String> {
+    let fields: &[(&str, bool, &[Operation])] = &[
+        ("filter", options.filter.is_some(), &[Operation::Filter, Operation::Effect]),
+        ("filters", options.filters.is_some(), &[Operation::Filter]),
+        ("intensity", options.intensity.is_some(), &[Operation::Filter, Operation::Effect]),
+        ("grayscale_mode", options.grayscale_mode.is_some(), &[Operation::Filter]),
+        ("brightness", options.brightness.is_some(), &[Operation::Adjust]),
+        ("linear_light", options.linear_light.is_some(), &[Operation::Adjust]),
+        ("contrast", options.contrast.is_some(), &[Operation::Adjust]),
+        ("saturation", options.saturation.is_some(), &[Operation::Adjust]),
+        ("hue_rotation", options.hue_rotation.is_some(), &[Operation::Adjust]),
+        ("opacity", options.opacity.is_some(), &[Operation::Adjust]),
+        ("auto_levels", options.auto_levels.is_some(), &[Operation::Adjust]),
+        ("clip_percent", options.clip_percent.is_some(), &[Operation::Adjust]),
+        ("temperature", options.temperature.is_some(), &[Operation::Adjust]),
+        ("tint", options.tint.is_some(), &[Operation::Adjust]),
+        ("resize_width", options.resize_width.is_some(), &[Operation::Transform]),
+        ("resize_height", options.resize_height.is_some(), &[Operation::Transform]),
+        ("keep_aspect_ratio", options.keep_aspect_ratio.is_some(), &[Operation::Transform]),
+        ("no_upscale", options.no_upscale.is_some(), &[Operation::Transform]),
+        ("rotation_angle", options.rotation_angle.is_some(), &[Operation::Transform]),
+        ("flip_horizontal", options.flip_horizontal.is_some(), &[Operation::Transform]),
+        ("flip_vertical", options.flip_vertical.is_some(), &[Operation::Transform]),
+        ("transpose", options.transpose.is_some(), &[Operation::Transform]),
+        ("crop_x", options.crop_x.is_some(), &[Operation::Transform, Operation::Effect]),
+        ("crop_y", options.crop_y.is_some(), &[Operation::Transform, Operation::Effect]),
+        ("crop_width", options.crop_width.is_some(), &[Operation::Transform, Operation::Effect]),
+        ("crop_height", options.crop_height.is_some(), &[Operation::Transform, Operation::Effect]),
+        ("crop_x_pct", options.crop_x_pct.is_some(), &[Operation::Transform, Operation::Effect]),
+        ("crop_y_pct", options.crop_y_pct.is_some(), &[Operation::Transform, Operation::Effect]),
+        ("crop_width_pct", options.crop_width_pct.is_some(), &[Operation::Transform, Operation::Effect]),
+        ("crop_height_pct", options.crop_height_pct.is_some(), &[Operation::Transform, Operation::Effect]),
+        ("block_size", options.block_size.is_some(), &[Operation::Effect]),
+        ("kernel", options.kernel.is_some(), &[Operation::Effect]),
+        ("kernel_divisor", options.kernel_divisor.is_some(), &[Operation::Effect]),
+        ("noise_amount", options.noise_amount.is_some(), &[Operation::Effect]),
+        ("noise_type", options.noise_type.is_some(), &[Operation::Effect]),
+        ("seed", options.seed.is_some(), &[Operation::Effect]),
+        ("oil_radius", options.oil_radius.is_some(), &[Operation::Effect]),
+        ("oil_levels", options.oil_levels.is_some(), &[Operation::Effect]),
+        ("clahe_tile_size", options.clahe_tile_size.is_some(), &[Operation::Effect]),
+        ("clahe_clip_limit", options.clahe_clip_limit.is_some(), &[Operation::Effect]),
+        ("palette_size", options.palette_size.is_some(), &[Operation::Effect]),
+        ("levels", options.levels.is_some(), &[Operation::Effect]),
+        ("threshold_channel", options.threshold_channel.is_some(), &[Operation::Effect]),
+        ("threshold_invert", options.threshold_invert.is_some(), &[Operation::Effect]),
+        ("tint_color", options.tint_color.is_some(), &[Operation::Tint]),
+        ("tint_strength", options.tint_strength.is_some(), &[Operation::Tint]),
+        ("channel_order", options.channel_order.is_some(), &[Operation::SwapChannels]),
+        ("blend_base64", options.blend_base64.is_some(), &[Operation::Blend]),
+        ("blend_mode", options.blend_mode.is_some(), &[Operation::Blend]),
+        ("blend_opacity", options.blend_opacity.is_some(), &[Operation::Blend]),
+        ("trim_tolerance", options.trim_tolerance.is_some(), &[Operation::Trim]),
+        ("target_aspect", options.target_aspect.is_some(), &[Operation::CropAspect]),
+        ("key_color", options.key_color.is_some(), &[Operation::ChromaKey]),
+        ("key_tolerance", options.key_tolerance.is_some(), &[Operation::ChromaKey]),
+        ("luma_threshold", options.luma_threshold.is_some(), &[Operation::LumaAlpha]),
+        ("luma_above", options.luma_above.is_some(), &[Operation::LumaAlpha]),
+        ("canvas_width", options.canvas_width.is_some(), &[Operation::Canvas]),
+        ("canvas_height", options.canvas_height.is_some(), &[Operation::Canvas]),
+        ("anchor", options.anchor.is_some(), &[Operation::Canvas]),
+        ("canvas_color", options.canvas_color.is_some(), &[Operation::Canvas]),
+        ("regions", options.regions.is_some(), &[Operation::BlurRegions]),
+        ("bit_depth", options.bit_depth.is_some(), &[Operation::Adjust]),
+    ];
+
+    let unused: Vec<&str> = fields.iter()
+        .filter(|(_, set, operations)| *set && !operations.contains(&options.operation))
+        .map(|(name, _, _)| *name)
+        .collect();
+    if unused.is_empty() {
+        Ok(())
+    } else {
+        Err(format!(
+            "Options ignored by the '{}' operation: {}",
+            options.operation.as_str(),
+            unused.join(", ")
+        ))
+    }
+}
+
+/// JPEG encoder settings beyond quality, set via `jpeg_progressive` / `jpeg_subsampling`
+#[derive(Debug, Clone, Copy, PartialEq)]
+struct JpegSettings {
+    progressive: bool,
+    sampling: jpeg_encoder::SamplingFactor,
+}
+
+impl JpegSettings {
+    /// Settings requested by the options, or `None` to use the default baseline 4:4:4 encoder
+    fn from_options(options: &ImageProcessingOptions) -> Result<Option<Self>, ImageError> {
+        if options.jpeg_progressive.is_none() && options.jpeg_subsampling.is_none() {
+            return Ok(None);
+        }
+        let sampling = match options.jpeg_subsampling.as_deref().unwrap_or("4:4:4") {
+            "4:4:4" => jpeg_encoder::SamplingFactor::R_4_4_4,
+            "4:2:2" => jpeg_encoder::SamplingFactor::R_4_2_2,
+            "4:2:0" => jpeg_encoder::SamplingFactor::R_4_2_0,
+            other => return Err(ImageError::InvalidParameter(format!(
+                "Unknown jpeg_subsampling '{}': expected 4:4:4, 4:2:2 or 4:2:0",
+                other
+            ))),
+        };
+        Ok(Some(JpegSettings { progressive: options.jpeg_progressive.unwrap_or(false), sampling }))
+    }
+}
+
+/// Whether a `base64_variant` names the URL-safe alphabet
+fn parse_base64_variant(variant: &str) -> Result<bool, ImageError> {
+    match variant {
+        "standard" => Ok(false),
+        "url_safe" => Ok(true),
+        other => Err(ImageError::InvalidParameter(format!(
+            "Unknown base64_variant '{}': expected standard or url_safe",
+            other
+        ))),
+    }
+}
+
+/// Parse an aspect ratio written as `"16:9"`, `"16/9"`, `"16x9"` or a bare `"1.78"`
+pub fn parse_aspect_ratio(ratio: &str) -> Result<f64, ImageError> {
+    let invalid = || ImageError::InvalidParameter(format!("Invalid aspect ratio '{}'", ratio));
+    let number = |part: &str| part.trim().parse::<f64>().map_err(|_| invalid());
+    let value = match ratio.trim().split_once([':', '/', 'x', 'X']) {
+        Some((width, height)) => number(width)? / number(height)?,
+        None => number(ratio)?,
+    };
+    if value.is_finite() && value > 0.0 {
+        Ok(value)
+    } else {
+        Err(invalid())
+    }
+}
+
+/// Parse a `#rgb` or `#rrggbb` hex color (the `#` is optional)
+pub fn parse_hex_color(color: &str) -> Result<[u8; 3], ImageError> {
+    let hex = color.trim().trim_start_matches('#');
+    let invalid = || ImageError::InvalidParameter(format!("Invalid hex color '{}'", color));
+    if !hex.chars().all(|c| c.is_ascii_hexdigit()) {
+        return Err(invalid());
+    }
+
+    let channel = |digits: &str| u8::from_str_radix(digits, 16).map_err(|_| invalid());
+    match hex.len() {
+        3 => {
+            let expand = |i: usize| channel(&hex[i..i + 1]).map(|v| v * 17);
+            Ok([expand(0)?, expand(1)?, expand(2)?])
+        }
+        6 => Ok([channel(&hex[0..2])?, channel(&hex[2..4])?, channel(&hex[4..6])?]),
+        _ => Err(invalid()),
+    }
+}
+
+/// Parse a hex color (`#rgb`, `#rrggbb`, `#rrggbbaa`) or a CSS-style
+/// `rgb(r, g, b)` / `rgba(r, g, b, a)` with alpha from 0.0 to 1.0
+pub fn parse_color(color: &str) -> Result<[u8; 4], ImageError> {
+    let trimmed = color.trim();
+    let invalid = || ImageError::InvalidParameter(format!("Invalid color '{}'", color));
+
+    let function_args = trimmed.strip_prefix("rgba(").or_else(|| trimmed.strip_prefix("rgb("));
+    if let Some(args) = function_args {
+        let parts: Vec<&str> = args.strip_suffix(')').ok_or_else(invalid)?.split(',').map(str::trim).collect();
+        if parts.len() != 3 && parts.len() != 4 {
+            return Err(invalid());
+        }
+        let channel = |part: &str| part.parse::<u8>().map_err(|_| invalid());
+        let alpha = match parts.get(3) {
+            Some(part) => match part.parse::<f32>() {
+                Ok(alpha) if (0.0..=1.0).contains(&alpha) => (alpha * 255.0).round() as u8,
+                _ => return Err(invalid()),
+            },
+            None => 255,
+        };
+        return Ok([channel(parts[0])?, channel(parts[1])?, channel(parts[2])?, alpha]);
+    }
+
+    let hex = trimmed.trim_start_matches('#');
+    if hex.len() == 8 && hex.is_ascii() {
+        let [r, g, b] = parse_hex_color(&hex[..6]).map_err(|_| invalid())?;
+        let alpha = u8::from_str_radix(&hex[6..], 16).map_err(|_| invalid())?;
+        return Ok([r, g, b, alpha]);
+    }
+    let [r, g, b] = parse_hex_color(hex).map_err(|_| invalid())?;
+    Ok([r, g, b, 255])
+}
+
+/// Format a color as `#rrggbb`
+pub fn format_hex_color([r, g, b]: [u8; 3]) -> String {
+    format!("#{:02x}{:02x}{:02x}", r, g, b)
+}
+
+/// Linear light (0.0-1.0) for an sRGB-encoded value (0.0-1.0)
+fn decode_srgb(value: f32) -> f32 {
+    if value <= 0.04045 { value / 12.92 } else { ((value + 0.055) / 1.055).powf(2.4) }
+}
+
+/// sRGB-encoded value (0.0-1.0) for linear light (0.0-1.0)
+fn encode_srgb(value: f32) -> f32 {
+    if value <= 0.0031308 { value * 12.92 } else { 1.055 * value.powf(1.0 / 2.4) - 0.055 }
+}
+
+/// Linear-light value (0.0-1.0) of each 8-bit sRGB level
+static SRGB_TO_LINEAR: LazyLock<[f32; 256]> = LazyLock::new(|| {
+    std::array::from_fn(|level| decode_srgb(level as f32 / 255.0))
+});
+
+/// Steps in the linear-to-sRGB table; fine enough that dark tones round to the right level
+const LINEAR_STEPS: usize = 4096;
+
+/// 8-bit sRGB level for linear light quantized to `LINEAR_STEPS`
+static LINEAR_TO_SRGB: LazyLock<[u8; LINEAR_STEPS]> = LazyLock::new(|| {
+    std::array::from_fn(|step| (encode_srgb(step as f32 / (LINEAR_STEPS - 1) as f32) * 255.0).round() as u8)
+});
+
+/// Encode linear light (clamped to 0.0-1.0) as an 8-bit sRGB level
+fn linear_to_srgb(value: f32) -> u8 {
+    LINEAR_TO_SRGB[(value.clamp(0.0, 1.0) * (LINEAR_STEPS - 1) as f32).round() as usize]
+}
+
+/// RGB triples of an RGBA pixel buffer
+fn rgb_colors(pixels: &[u8]) -> Vec<[u8; 3]> {
+    pixels.chunks_exact(4).map(|p| [p[0], p[1], p[2]]).collect()
+}
+
+/// Median-cut palette: repeatedly split the box with the widest channel range
+/// at its median until there are `palette_size` boxes, then average each box
+fn median_cut(colors: Vec<[u8; 3]>, palette_size: usize) -> Vec<[u8; 3]> {
+    fn widest_channel(colors: &[[u8; 3]]) -> (usize, u8) {
+        (0..3)
+            .map(|c| {
+                let (min, max) = colors.iter().fold((u8::MAX, u8::MIN), |(min, max), color| {
+                    (min.min(color[c]), max.max(color[c]))
+                });
+                (c, max.saturating_sub(min))
+            })
+            .max_by_key(|&(_, range)| range)
+            .unwrap_or((0, 0))
+    }
+
+    let mut boxes = vec![colors];
+    while boxes.len() < palette_size {
+        let Some((index, channel)) = boxes.iter()
+            .enumerate()
+            .map(|(index, colors)| (index, widest_channel(colors)))
+            .filter(|&(_, (_, range))| range > 0)
+            .max_by_key(|&(_, (_, range))| range)
+            .map(|(index, (channel, _))| (index, channel))
+        else {
+            break;
+        };
+
+        let mut colors = boxes.swap_remove(index);
+        colors.sort_unstable_by_key(|color| color[channel]);
+        let upper = colors.split_off(colors.len() / 2);
+        boxes.push(colors);
+        boxes.push(upper);
+    }
+
+    boxes.iter()
+        .filter(|colors| !colors.is_empty())
+        .map(|colors| {
+            let mut sums = [0u64; 3];
+            for color in colors {
+                for c in 0..3 {
+                    sums[c] += color[c] as u64;
+                }
+            }
+            let count = colors.len() as u64;
+            [(sums[0] / count) as u8, (sums[1] / count) as u8, (sums[2] / count) as u8]
+        })
+        .collect()
+}
+
+/// Whether the bytes look like SVG markup rather than an encoded raster image
+fn is_svg(bytes: &[u8]) -> bool {
+    let text = bytes.strip_prefix(b"\xEF\xBB\xBF").unwrap_or(bytes);
+    let start = text.iter().position(|byte| !byte.is_ascii_whitespace()).unwrap_or(text.len());
+    let text = &text[start..];
+    text.starts_with(b"<svg")
+        || (text.starts_with(b"<?xml") && text.windows(4).take(1024).any(|window| window == b"<svg"))
+}
+
+/// Output formats this crate knows about; which ones a build can encode
+/// depends on its features, see [`ImageProcessor::supported_output_formats`]
+const KNOWN_OUTPUT_FORMATS: &[&str] = &["png", "jpeg", "webp", "avif"];
+
+/// Default upper bound on decoded pixels (width * height), guarding against decompression bombs
+pub const DEFAULT_MAX_PIXELS: u64 = 100_000_000;
+
+/// Deployment-wide defaults for options a call leaves unset
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct ProcessorConfig {
+    /// Output format when `output_format` is unset
+    pub default_format: String,
+    /// Lossy encoder quality (1-100) when `quality` is unset
+    pub default_quality: u8,
+    /// Resize sampling filter when `resize_filter` is unset
+    pub default_resize_filter: String,
+    /// Decoded pixel limit when `max_pixels` is unset
+    pub max_pixels: u64,
+}
+
+impl Default for ProcessorConfig {
+    fn default() -> Self {
+        Self {
+            default_format: "png".to_string(),
+            default_quality: 85,
+            default_resize_filter: "nearest".to_string(),
+            max_pixels: DEFAULT_MAX_PIXELS,
+        }
+    }
+}
+
+static CONFIG: OnceLock<ProcessorConfig> = OnceLock::new();
+static DEFAULT_CONFIG: LazyLock<ProcessorConfig> = LazyLock::new(ProcessorConfig::default);
+
+impl ProcessorConfig {
+    /// The installed configuration, or the built-in defaults before [`configure`] is called
+    pub fn global() -> &'static ProcessorConfig {
+        CONFIG.get().unwrap_or(&DEFAULT_CONFIG)
+    }
+}
+
+/// Install process-wide defaults; this can only be done once
+pub fn configure(config: ProcessorConfig) -> Result<(), ImageError> {
+    // Only formats this build can encode, since a bad default cannot be replaced later
+    if !ImageProcessor::supported_output_formats().contains(&config.default_format.as_str()) {
+        return Err(ImageError::UnsupportedFormat(config.default_format));
+    }
+    if config.max_pixels == 0 {
+        return Err(ImageError::InvalidParameter("max_pixels must be greater than 0".to_string()));
+    }
+    if !(1..=100).contains(&config.default_quality) {
+        return Err(ImageError::InvalidParameter("default_quality must be between 1 and 100".to_string()));
+    }
+    parse_resize_filter(&config.default_resize_filter)?;
+    CONFIG.set(config)
+        .map_err(|_| ImageError::InvalidParameter("Processor defaults are already configured".to_string()))
 }
 
+/// Parse a `resize_filter` name into photon's sampling filter
+fn parse_resize_filter(name: &str) -> Result<photon_rs::transform::SamplingFilter, ImageError> {
+    use photon_rs::transform::SamplingFilter;
+    match name {
+        "nearest" => Ok(SamplingFilter::Nearest),
+        "triangle" => Ok(SamplingFilter::Triangle),
+        "catmull_rom" => Ok(SamplingFilter::CatmullRom),
+        "gaussian" => Ok(SamplingFilter::Gaussian),
+        "lanczos3" => Ok(SamplingFilter::Lanczos3),
+        other => Err(ImageError::InvalidParameter(format!(
+            "Unknown resize_filter '{}': expected nearest, triangle, catmull_rom, gaussian or lanczos3",
+            other
+        ))),
+    }
+}
+
+/// Pixel budget for `count_unique_colors`; larger images are sampled down to it
+const COLOR_COUNT_MAX_PIXELS: u64 = 1_000_000;
+
+/// Stateless image operations; [`ImageProcessor::from_base64`] starts a
+/// [`LoadedImage`] for applying several operations to one decoded image
 pub struct ImageProcessor;
 
+impl Default for ImageProcessor {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
 impl ImageProcessor {
     pub fn new() -> Self {
         Self
     }
 
+    /// Output formats this build can encode, following the feature gates on
+    /// the arms of `photon_image_to_bytes`
+    pub fn supported_output_formats() -> Vec<&'static str> {
+        KNOWN_OUTPUT_FORMATS.iter()
+            .copied()
+            .filter(|format| *format != "avif" || cfg!(feature = "avif"))
+            .collect()
+    }
+
+    /// Decode a base64 image once so several operations can be chained on it
+    pub fn from_base64(base64_data: &str) -> Result<LoadedImage, ImageError> {
+        Self::from_bytes(&Self::base64_to_bytes(base64_data)?)
+    }
+
+    /// Decode raw encoded bytes once so several operations can be chained on it
+    pub fn from_bytes(image_bytes: &[u8]) -> Result<LoadedImage, ImageError> {
+        let image = Self::decode_input(image_bytes, &ImageProcessingOptions::default())?;
+        Ok(LoadedImage { image, warnings: Vec::new() })
+    }
+
     /// Convert base64 string to PhotonImage
-    pub fn base64_to_photon_image(base64_data: &str) -> Result<PhotonImage, String> {
+    pub fn base64_to_photon_image(base64_data: &str) -> Result<PhotonImage, ImageError> {
+        Self::bytes_to_photon_image(&Self::base64_to_bytes(base64_data)?)
+    }
+
+    /// Size in bytes that a base64 string (optionally a data URL) decodes to,
+    /// computed from its length without decoding
+    fn decoded_len(base64_data: &str) -> usize {
+        let payload = if base64_data.starts_with("data:") {
+            base64_data.split(',').nth(1).unwrap_or(base64_data)
+        } else {
+            base64_data
+        };
+        let symbols = payload.bytes().filter(|b| !b.is_ascii_whitespace() && *b != b'=').count();
+        symbols * 3 / 4
+    }
+
+    /// Enforce `max_input_bytes` on an input of `size_bytes`
+    fn check_input_size(size_bytes: usize, options: &ImageProcessingOptions) -> Result<(), ImageError> {
+        match options.max_input_bytes {
+            Some(max_bytes) if size_bytes > max_bytes => Err(ImageError::InputTooLarge { max_bytes, size_bytes }),
+            _ => Ok(()),
+        }
+    }
+
+    /// Decode a base64 string (optionally a data URL) to raw bytes
+    ///
+    /// Whitespace is ignored so line-wrapped payloads decode as-is, and the
+    /// URL-safe alphabet is detected from `-` or `_` characters.
+    pub fn base64_to_bytes(base64_data: &str) -> Result<Vec<u8>, ImageError> {
+        Self::base64_to_bytes_with_variant(base64_data, None)
+    }
+
+    /// Decode base64 in the given alphabet (`standard` or `url_safe`), auto-detecting it when `None`
+    pub fn base64_to_bytes_with_variant(base64_data: &str, variant: Option<&str>) -> Result<Vec<u8>, ImageError> {
         // Remove data URL prefix if present
         let clean_data = if base64_data.starts_with("data:") {
             base64_data.split(',').nth(1).unwrap_or(base64_data)
@@ -75,13 +1112,50 @@ impl ImageProcessor {
             base64_data
         };
 
+        let clean_data: std::borrow::Cow<str> = if clean_data.bytes().any(|b| b.is_ascii_whitespace()) {
+            clean_data.chars().filter(|c| !c.is_ascii_whitespace()).collect::<String>().into()
+        } else {
+            clean_data.into()
+        };
+        let is_base64_byte = |b: u8| b.is_ascii_alphanumeric() || matches!(b, b'+' | b'/' | b'-' | b'_' | b'=');
+        if !clean_data.bytes().all(is_base64_byte) {
+            return Err(ImageError::DecodeBase64("input does not look like base64 or a data URL".to_string()));
+        }
+
+        let url_safe = match variant {
+            Some(variant) => parse_base64_variant(variant)?,
+            None => clean_data.bytes().any(|b| b == b'-' || b == b'_'),
+        };
+
         use base64::Engine;
-        let engine = base64::engine::general_purpose::STANDARD;
-        let image_bytes = engine.decode(clean_data)
-            .map_err(|e| format!("Failed to decode base64: {}", e))?;
+        let decoded = if url_safe {
+            // URL-safe payloads frequently drop their padding
+            let config = base64::engine::GeneralPurposeConfig::new()
+                .with_decode_padding_mode(base64::engine::DecodePaddingMode::Indifferent);
+            base64::engine::GeneralPurpose::new(&base64::alphabet::URL_SAFE, config).decode(clean_data.as_bytes())
+        } else {
+            base64::engine::general_purpose::STANDARD.decode(clean_data.as_bytes())
+        };
+        decoded.map_err(|e| ImageError::DecodeBase64(e.to_string()))
+    }
+
+    /// Convert encoded image bytes (PNG, JPEG, ...) to PhotonImage
+    pub fn bytes_to_photon_image(image_bytes: &[u8]) -> Result<PhotonImage, ImageError> {
+        Self::bytes_to_photon_image_limited(image_bytes, ProcessorConfig::global().max_pixels)
+    }
+
+    /// Convert encoded image bytes to PhotonImage, rejecting images above `max_pixels`
+    ///
+    /// Dimensions are read from the header first so oversized images are refused
+    /// before the full RGBA buffer is allocated.
+    pub fn bytes_to_photon_image_limited(image_bytes: &[u8], max_pixels: u64) -> Result<PhotonImage, ImageError> {
+        let (width, height) = Self::image_dimensions(image_bytes)?;
+        if width as u64 * height as u64 > max_pixels {
+            return Err(ImageError::TooLarge { width, height, max_pixels });
+        }
 
-        let dynamic_image = image::load_from_memory(&image_bytes)
-            .map_err(|e| format!("Failed to load image: {}", e))?;
+        let dynamic_image = image::load_from_memory(image_bytes)
+            .map_err(|e| ImageError::LoadImage(e.to_string()))?;
 
         let rgba_image = dynamic_image.to_rgba8();
         let width = rgba_image.width();
@@ -91,17 +1165,134 @@ impl ImageProcessor {
         Ok(photon_image)
     }
 
+    /// Read an encoded image's dimensions from its header without decoding pixels
+    pub fn image_dimensions(image_bytes: &[u8]) -> Result<(u32, u32), ImageError> {
+        image::ImageReader::new(Cursor::new(image_bytes))
+            .with_guessed_format()
+            .map_err(|e| ImageError::LoadImage(e.to_string()))?
+            .into_dimensions()
+            .map_err(|e| ImageError::LoadImage(e.to_string()))
+    }
+
+    /// Describe an encoded image (size, format, color type, bit depth, alpha)
+    /// from its header, without decoding pixels or re-encoding
+    ///
+    /// SVG input is rasterized, so it reports as 8-bit RGBA.
+    pub fn get_image_info(base64_input: &str) -> Result<ImageMetadata, ImageError> {
+        let image_bytes = Self::base64_to_bytes(base64_input)?;
+        let format = Self::input_format_name(&image_bytes)?;
+        if is_svg(&image_bytes) {
+            let image = Self::decode_input(&image_bytes, &ImageProcessingOptions::default())?;
+            return Ok(ImageMetadata {
+                color_type: Some("rgba".to_string()),
+                bit_depth: Some(8),
+                has_alpha: Some(true),
+                ..ImageMetadata::new(image.get_width(), image.get_height(), format, image_bytes.len())
+            });
+        }
+
+        let decoder = image::ImageReader::new(Cursor::new(&image_bytes))
+            .with_guessed_format()
+            .map_err(|e| ImageError::LoadImage(e.to_string()))?
+            .into_decoder()
+            .map_err(|e| ImageError::LoadImage(e.to_string()))?;
+        let (width, height) = decoder.dimensions();
+        let color = decoder.color_type();
+        let color_type = match (color.has_color(), color.has_alpha()) {
+            (false, false) => "grayscale",
+            (false, true) => "grayscale_alpha",
+            (true, false) => "rgb",
+            (true, true) => "rgba",
+        };
+        Ok(ImageMetadata {
+            color_type: Some(color_type.to_string()),
+            bit_depth: Some(color.bits_per_pixel() as u8 / color.channel_count()),
+            has_alpha: Some(color.has_alpha()),
+            ..ImageMetadata::new(width, height, format, image_bytes.len())
+        })
+    }
+
+    /// Latitude and longitude in decimal degrees (south and west negative) from
+    /// the image's EXIF, or `None` when it carries no GPS position
+    pub fn extract_gps(base64_input: &str) -> Result<Option<(f64, f64)>, ImageError> {
+        let image_bytes = Self::base64_to_bytes(base64_input)?;
+        if is_svg(&image_bytes) {
+            return Ok(None);
+        }
+        let mut decoder = image::ImageReader::new(Cursor::new(&image_bytes))
+            .with_guessed_format()
+            .map_err(|e| ImageError::LoadImage(e.to_string()))?
+            .into_decoder()
+            .map_err(|e| ImageError::LoadImage(e.to_string()))?;
+        let exif = decoder.exif_metadata().map_err(|e| ImageError::LoadImage(e.to_string()))?;
+        Ok(exif.and_then(|exif| crate::exif::gps_coordinates(&exif)))
+    }
+
+    /// Short format name of encoded input, such as `png` or `svg`
+    fn input_format_name(image_bytes: &[u8]) -> Result<String, ImageError> {
+        if is_svg(image_bytes) {
+            return Ok("svg".to_string());
+        }
+        image::guess_format(image_bytes)
+            .map(|format| format.to_mime_type().trim_start_matches("image/").to_string())
+            .map_err(|e| ImageError::LoadImage(e.to_string()))
+    }
+
+    /// Estimate the memory needed to hold every image of a batch decoded as
+    /// RGBA (`width * height * 4` bytes each), reading only image headers
+    ///
+    /// Images whose headers cannot be read, or that exceed the configured
+    /// `max_pixels`, add nothing: both are rejected before any pixel buffer is allocated.
+    pub fn estimate_batch_memory(images: &[String]) -> u64 {
+        let max_pixels = ProcessorConfig::global().max_pixels;
+        images.iter()
+            .filter_map(|image| Self::base64_to_bytes(image).ok())
+            .filter_map(|bytes| Self::image_dimensions(&bytes).ok())
+            .map(|(width, height)| {
+                let pixels = width as u64 * height as u64;
+                if pixels > max_pixels { 0 } else { pixels * 4 }
+            })
+            .sum()
+    }
+
     /// Convert PhotonImage to binary bytes
-    pub fn photon_image_to_bytes(image: &PhotonImage, format: &str, quality: Option<u8>) -> Result<Vec<u8>, String> {
+    pub fn photon_image_to_bytes(image: &PhotonImage, format: &str, quality: Option<u8>) -> Result<Vec<u8>, ImageError> {
+        Self::encode(image, format, quality, None, None)
+    }
+
+    /// Encode a PhotonImage, embedding the given raw EXIF block if any
+    #[cfg_attr(feature = "tracing", tracing::instrument(
+        skip_all,
+        fields(format = format, width = image.get_width(), height = image.get_height()),
+    ))]
+    fn encode(
+        image: &PhotonImage,
+        format: &str,
+        quality: Option<u8>,
+        exif: Option<&[u8]>,
+        jpeg: Option<JpegSettings>,
+    ) -> Result<Vec<u8>, ImageError> {
+        fn attach_exif(encoder: &mut impl ImageEncoder, exif: Option<&[u8]>) -> Result<(), ImageError> {
+            match exif {
+                Some(exif) => encoder.set_exif_metadata(exif.to_vec())
+                    .map_err(|e| ImageError::EncodeFailed(format!("EXIF: {}", e))),
+                None => Ok(()),
+            }
+        }
+
         let width = image.get_width();
         let height = image.get_height();
         let raw_data = image.get_raw_pixels();
 
         // Convert to DynamicImage
         let rgba_image = image::RgbaImage::from_raw(width, height, raw_data.clone())
-            .ok_or("Failed to create RGBA image")?;
+            .ok_or_else(|| ImageError::EncodeFailed("Failed to create RGBA image".to_string()))?;
         let dynamic_image = DynamicImage::ImageRgba8(rgba_image);
 
+        if let (Some(settings), "jpeg" | "jpg") = (jpeg, format.to_lowercase().as_str()) {
+            return Self::encode_jpeg_with_settings(&dynamic_image, quality.unwrap_or(ProcessorConfig::global().default_quality), exif, settings);
+        }
+
         // Encode to bytes
         let mut buffer = Vec::new();
         let mut cursor = Cursor::new(&mut buffer);
@@ -111,31 +1302,69 @@ impl ImageProcessor {
                 let rgb_image = dynamic_image.to_rgb8();
                 let mut encoder = image::codecs::jpeg::JpegEncoder::new_with_quality(
                     &mut cursor, 
-                    quality.unwrap_or(85)
+                    quality.unwrap_or(ProcessorConfig::global().default_quality)
                 );
+                attach_exif(&mut encoder, exif)?;
                 encoder.encode(
                     rgb_image.as_raw(),
                     rgb_image.width(),
                     rgb_image.height(),
                     image::ExtendedColorType::Rgb8,
-                ).map_err(|e| format!("JPEG encoding failed: {}", e))?;
+                ).map_err(|e| ImageError::EncodeFailed(format!("JPEG: {}", e)))?;
             }
             "png" => {
-                dynamic_image.write_to(&mut cursor, ImageFormat::Png)
-                    .map_err(|e| format!("PNG encoding failed: {}", e))?;
+                let mut encoder = image::codecs::png::PngEncoder::new(&mut cursor);
+                attach_exif(&mut encoder, exif)?;
+                dynamic_image.write_with_encoder(encoder)
+                    .map_err(|e| ImageError::EncodeFailed(format!("PNG: {}", e)))?;
             }
             "webp" => {
-                dynamic_image.write_to(&mut cursor, ImageFormat::WebP)
-                    .map_err(|e| format!("WebP encoding failed: {}", e))?;
+                let mut encoder = image::codecs::webp::WebPEncoder::new_lossless(&mut cursor);
+                attach_exif(&mut encoder, exif)?;
+                dynamic_image.write_with_encoder(encoder)
+                    .map_err(|e| ImageError::EncodeFailed(format!("WebP: {}", e)))?;
+            }
+            // The AVIF encoder cannot embed EXIF, so carried metadata is dropped
+            #[cfg(feature = "avif")]
+            "avif" => {
+                // Speed 8 of 1-10 keeps encoding practical in WASM; quality uses the JPEG scale
+                let encoder = image::codecs::avif::AvifEncoder::new_with_speed_quality(&mut cursor, 8, quality.unwrap_or(ProcessorConfig::global().default_quality));
+                dynamic_image.write_with_encoder(encoder)
+                    .map_err(|e| ImageError::EncodeFailed(format!("AVIF: {}", e)))?;
             }
             _ => {
-                return Err(format!("Unsupported output format: {}", format));
+                return Err(ImageError::UnsupportedFormat(format.to_string()));
             }
         }
 
         Ok(buffer)
     }
 
+    /// Encode JPEG with jpeg-encoder, which unlike `image` supports progressive scans and subsampling
+    fn encode_jpeg_with_settings(
+        image: &DynamicImage,
+        quality: u8,
+        exif: Option<&[u8]>,
+        settings: JpegSettings,
+    ) -> Result<Vec<u8>, ImageError> {
+        let rgb_image = image.to_rgb8();
+        let too_large = || ImageError::EncodeFailed("JPEG: dimensions exceed 65535 pixels".to_string());
+        let width = u16::try_from(rgb_image.width()).map_err(|_| too_large())?;
+        let height = u16::try_from(rgb_image.height()).map_err(|_| too_large())?;
+
+        let mut buffer = Vec::new();
+        let mut encoder = jpeg_encoder::Encoder::new(&mut buffer, quality);
+        encoder.set_progressive(settings.progressive);
+        encoder.set_sampling_factor(settings.sampling);
+        if let Some(exif) = exif {
+            encoder.add_app_segment(1, &[b"Exif\0\0".as_slice(), exif].concat())
+                .map_err(|e| ImageError::EncodeFailed(format!("EXIF: {}", e)))?;
+        }
+        encoder.encode(rgb_image.as_raw(), width, height, jpeg_encoder::ColorType::Rgb)
+            .map_err(|e| ImageError::EncodeFailed(format!("JPEG: {}", e)))?;
+        Ok(buffer)
+    }
+
     /// Convert bytes to base64 data URL
     pub fn bytes_to_base64_data_url(bytes: &[u8], format: &str) -> String {
         use base64::Engine;
@@ -151,69 +1380,96 @@ impl ImageProcessor {
         engine.encode(bytes)
     }
 
+    /// Convert bytes to raw base64 using the URL-safe alphabet
+    pub fn bytes_to_base64_url_safe(bytes: &[u8]) -> String {
+        use base64::Engine;
+        base64::engine::general_purpose::URL_SAFE.encode(bytes)
+    }
+
     /// Process a single image with the given options
+    #[cfg_attr(feature = "tracing", tracing::instrument(skip_all, fields(operation = options.operation.as_str())))]
     pub fn process_image(base64_input: &str, options: &ImageProcessingOptions) -> ImageProcessingResult {
-        // Note: WASM doesn't support std::time::Instant, so we'll use a placeholder for timing
+        if let Err(e) = Self::check_input_size(Self::decoded_len(base64_input), options) {
+            return ImageProcessingResult::failure(e);
+        }
+        match Self::base64_to_bytes_with_variant(base64_input, options.base64_variant.as_deref()) {
+            Ok(image_bytes) => Self::process_image_bytes(&image_bytes, options),
+            Err(e) => ImageProcessingResult::failure(e),
+        }
+    }
 
-        let mut photon_image = match Self::base64_to_photon_image(base64_input) {
-            Ok(img) => img,
-            Err(e) => return ImageProcessingResult {
-                success: false,
-                image_data: None,
-                binary_data: None,
-                metadata: None,
-                error: Some(e),
-            },
-        };
+    /// Process a single image, filling unset options from `config` rather than
+    /// the global [`ProcessorConfig`]
+    pub fn process_image_with_config(
+        base64_input: &str,
+        options: &ImageProcessingOptions,
+        config: &ProcessorConfig,
+    ) -> ImageProcessingResult {
+        Self::process_image(base64_input, &options.with_defaults(config))
+    }
 
-        // Apply the requested operation
-        let operation_result = match options.operation.as_str() {
-            "filter" => Self::apply_filter(&mut photon_image, options),
-            "transform" => Self::apply_transform(&mut photon_image, options),
-            "adjust" => Self::apply_adjustments(&mut photon_image, options),
-            "effect" => Self::apply_effects(&mut photon_image, options),
-            _ => Err(format!("Unknown operation: {}", options.operation)),
-        };
+    /// Process a single image supplied as raw encoded bytes
+    pub fn process_image_bytes(input: &[u8], options: &ImageProcessingOptions) -> ImageProcessingResult {
+        if let Err(e) = Self::check_input_size(input.len(), options) {
+            return ImageProcessingResult::failure(e);
+        }
+        let mut warnings = Vec::new();
+        if let Err(unused) = validate_options(options) {
+            warnings.push(unused);
+        }
+        let output = Self::render_bytes(input, options, &mut warnings);
+        Self::build_result(output, warnings, options)
+    }
 
-        if let Err(e) = operation_result {
+    /// Package rendered bytes into a result according to the output options
+    fn build_result(
+        output: Result<(Vec<u8>, ImageMetadata), ImageError>,
+        warnings: Vec<String>,
+        options: &ImageProcessingOptions,
+    ) -> ImageProcessingResult {
+        let (image_bytes, metadata) = match output {
+            Ok(output) => output,
+            Err(e) => return ImageProcessingResult::failure(e),
+        };
+        if options.metadata_only.unwrap_or(false) {
             return ImageProcessingResult {
-                success: false,
+                success: true,
                 image_data: None,
                 binary_data: None,
-                metadata: None,
-                error: Some(e),
+                metadata: Some(metadata),
+                error: None,
+                error_code: None,
+                warnings,
+                index: 0,
             };
         }
 
-        // Convert to bytes first
-        let output_format = options.output_format.as_deref().unwrap_or("png");
-        let image_bytes = match Self::photon_image_to_bytes(&photon_image, output_format, options.quality) {
-            Ok(bytes) => bytes,
-            Err(e) => return ImageProcessingResult {
-                success: false,
-                image_data: None,
-                binary_data: None,
-                metadata: None,
-                error: Some(e),
-            },
+        // Raw base64 honours base64_variant; data URLs always use the standard alphabet
+        let url_safe = match options.base64_variant.as_deref().map(parse_base64_variant).transpose() {
+            Ok(url_safe) => url_safe.unwrap_or(false),
+            Err(e) => return ImageProcessingResult::failure(e),
         };
-
-        // Determine output format based on options
-        let output_as_binary = options.output_as_binary.unwrap_or(false);
-        let (image_data, binary_data) = if output_as_binary {
-            (Some(Self::bytes_to_base64(&image_bytes)), Some(image_bytes.clone()))
-        } else {
-            (Some(Self::bytes_to_base64_data_url(&image_bytes, output_format)), None)
+        let raw_base64 = |bytes: &[u8]| {
+            if url_safe { Self::bytes_to_base64_url_safe(bytes) } else { Self::bytes_to_base64(bytes) }
         };
 
-        // WASM doesn't support timing, so we'll use a placeholder
-        let processing_time_ms = 0u128;
-        let metadata = ImageMetadata {
-            width: photon_image.get_width(),
-            height: photon_image.get_height(),
-            format: output_format.to_string(),
-            size_bytes: image_bytes.len(),
-            processing_time_ms,
+        // Determine output format based on options
+        let output_format = metadata.format.as_str();
+        let (image_data, binary_data) = match options.output_encoding.as_deref() {
+            Some("data_url") => (Some(Self::bytes_to_base64_data_url(&image_bytes, output_format)), None),
+            Some("base64") => (Some(raw_base64(&image_bytes)), None),
+            Some("binary") => (None, Some(image_bytes)),
+            Some(other) => {
+                return ImageProcessingResult::failure(ImageError::InvalidParameter(format!(
+                    "Unknown output_encoding '{}': expected data_url, base64 or binary",
+                    other
+                )))
+            }
+            // Legacy behaviour: binary output carries both raw base64 and the bytes
+            None if options.output_as_binary.unwrap_or(false) => {
+                (Some(raw_base64(&image_bytes)), Some(image_bytes))
+            }
+            None => (Some(Self::bytes_to_base64_data_url(&image_bytes, output_format)), None),
         };
 
         ImageProcessingResult {
@@ -222,57 +1478,440 @@ impl ImageProcessor {
             binary_data,
             metadata: Some(metadata),
             error: None,
+            error_code: None,
+            warnings,
+            index: 0,
         }
     }
 
-    /// Apply filters to the image
-    fn apply_filter(image: &mut PhotonImage, options: &ImageProcessingOptions) -> Result<(), String> {
-        let filter = options.filter.as_deref().unwrap_or("none");
-        let intensity = options.intensity.unwrap_or(1.0);
+    /// Process a single image and return the encoded bytes with their metadata
+    pub fn process_image_to_bytes(base64_input: &str, options: &ImageProcessingOptions) -> Result<(Vec<u8>, ImageMetadata), ImageError> {
+        Self::check_input_size(Self::decoded_len(base64_input), options)?;
+        let image_bytes = Self::base64_to_bytes_with_variant(base64_input, options.base64_variant.as_deref())?;
+        Self::render_bytes(&image_bytes, options, &mut Vec::new())
+    }
 
-        match filter {
-            "grayscale" => photon_rs::monochrome::grayscale(image),
-            "sepia" => photon_rs::monochrome::sepia(image),
-            "invert" => photon_rs::channels::invert(image),
-            "vintage" => {
-                // Apply a combination of effects for vintage look
-                photon_rs::monochrome::sepia(image);
-                let brightness_adj = if intensity < 0.5 { 20 } else { 0 };
-                if brightness_adj > 0 {
-                    photon_rs::effects::inc_brightness(image, brightness_adj);
-                }
-            }
-            "noir" => {
-                photon_rs::monochrome::grayscale(image);
-                photon_rs::effects::inc_brightness(image, 10);
-            }
-            "warm" => {
-                photon_rs::channels::alter_red_channel(image, (intensity * 20.0) as i16);
-                photon_rs::channels::alter_blue_channel(image, -(intensity * 10.0) as i16);
+    /// Decode, process and re-encode `image_bytes`, collecting warnings about ignored or clamped inputs
+    fn render_bytes(
+        image_bytes: &[u8],
+        options: &ImageProcessingOptions,
+        warnings: &mut Vec<String>,
+    ) -> Result<(Vec<u8>, ImageMetadata), ImageError> {
+        if options.metadata_only.unwrap_or(false) {
+            return Self::inspect(image_bytes, options);
+        }
+        match options.bit_depth {
+            None | Some(8) => {}
+            Some(16) if options.operation == Operation::Adjust => {
+                return Self::render_16bit(image_bytes, options, warnings);
             }
-            "cool" => {
-                photon_rs::channels::alter_blue_channel(image, (intensity * 20.0) as i16);
-                photon_rs::channels::alter_red_channel(image, -(intensity * 10.0) as i16);
+            Some(16) => {}
+            Some(other) => {
+                return Err(ImageError::InvalidParameter(format!("bit_depth must be 8 or 16, not {}", other)));
             }
-            "dramatic" => photon_rs::filters::dramatic(image),
-            "firenze" => photon_rs::filters::firenze(image),
-            "golden" => photon_rs::filters::golden(image),
-            "lix" => photon_rs::filters::lix(image),
-            "lofi" => photon_rs::filters::lofi(image),
-            "neue" => photon_rs::filters::neue(image),
-            "obsidian" => photon_rs::filters::obsidian(image),
-            "pastel_pink" => photon_rs::filters::pastel_pink(image),
-            "ryo" => photon_rs::filters::ryo(image),
-            _ => return Err(format!("Unknown filter: {}", filter)),
         }
+        // std::time::Instant panics on wasm32, so timing is only measured natively
+        #[cfg(not(target_arch = "wasm32"))]
+        let started = options.include_timing.unwrap_or(false).then(std::time::Instant::now);
 
-        Ok(())
-    }
+        let mut photon_image = Self::decode_input(image_bytes, options)?;
+        if options.operation == Operation::Normalize {
+            Self::auto_orient(&mut photon_image, image_bytes);
+        }
+        let output = Self::render(photon_image, Self::carried_exif(image_bytes, options), options, warnings);
+
+        #[cfg(not(target_arch = "wasm32"))]
+        if let Some(started) = started {
+            let processing_time_ms = started.elapsed().as_millis();
+            return output.map(|(bytes, metadata)| (bytes, ImageMetadata { processing_time_ms, ..metadata }));
+        }
+        output
+    }
+
+    /// Adjust `image_bytes` at 16 bits per channel and encode 16-bit png or tiff
+    ///
+    /// Photon images are 8-bit, so this path decodes with the image crate and
+    /// runs the adjustments on `u16` samples. Grayscale input stays grayscale;
+    /// metadata is not carried over.
+    fn render_16bit(
+        image_bytes: &[u8],
+        options: &ImageProcessingOptions,
+        warnings: &mut Vec<String>,
+    ) -> Result<(Vec<u8>, ImageMetadata), ImageError> {
+        let output_format = options.output_format_or_default().to_lowercase();
+        let format = match output_format.as_str() {
+            "png" => ImageFormat::Png,
+            "tiff" | "tif" => ImageFormat::Tiff,
+            other => return Err(ImageError::InvalidParameter(format!(
+                "bit_depth 16 needs png or tiff output, not {}",
+                other
+            ))),
+        };
+
+        let decoder = image::ImageReader::new(Cursor::new(image_bytes))
+            .with_guessed_format()
+            .map_err(|e| ImageError::LoadImage(e.to_string()))?
+            .into_decoder()
+            .map_err(|e| ImageError::LoadImage(e.to_string()))?;
+        let (width, height) = decoder.dimensions();
+        let max_pixels = options.max_pixels_or_default();
+        if width as u64 * height as u64 > max_pixels {
+            return Err(ImageError::TooLarge { width, height, max_pixels });
+        }
+        let color = decoder.color_type();
+        let decoded = DynamicImage::from_decoder(decoder).map_err(|e| ImageError::LoadImage(e.to_string()))?;
+
+        let mut rgba = decoded.to_rgba16();
+        Self::apply_adjustments_16bit(&mut rgba, options, warnings)?;
+        let output = match (color.has_color(), color.has_alpha()) {
+            (false, false) => DynamicImage::ImageLuma16(DynamicImage::ImageRgba16(rgba).to_luma16()),
+            (false, true) => DynamicImage::ImageLumaA16(DynamicImage::ImageRgba16(rgba).to_luma_alpha16()),
+            (true, false) => DynamicImage::ImageRgb16(DynamicImage::ImageRgba16(rgba).to_rgb16()),
+            (true, true) => DynamicImage::ImageRgba16(rgba),
+        };
+
+        let mut bytes = Vec::new();
+        output.write_to(&mut Cursor::new(&mut bytes), format)
+            .map_err(|e| ImageError::EncodeFailed(e.to_string()))?;
+        let metadata = ImageMetadata {
+            bit_depth: Some(16),
+            ..ImageMetadata::new(width, height, output_format, bytes.len())
+        };
+        Ok((bytes, metadata))
+    }
+
+    /// Decode the input image, rasterizing SVG markup when the `svg` feature is enabled
+    fn decode_input(image_bytes: &[u8], options: &ImageProcessingOptions) -> Result<PhotonImage, ImageError> {
+        let max_pixels = options.max_pixels_or_default();
+        if is_svg(image_bytes) {
+            #[cfg(feature = "svg")]
+            return Self::svg_to_photon_image(image_bytes, options.svg_width, options.svg_height, max_pixels);
+            #[cfg(not(feature = "svg"))]
+            return Err(ImageError::LoadImage("SVG input requires the `svg` feature".to_string()));
+        }
+        Self::bytes_to_photon_image_limited(image_bytes, max_pixels)
+    }
+
+    /// Rasterize SVG markup at the requested size (defaulting to its intrinsic size)
+    #[cfg(feature = "svg")]
+    pub fn svg_to_photon_image(
+        svg: &[u8],
+        width: Option<u32>,
+        height: Option<u32>,
+        max_pixels: u64,
+    ) -> Result<PhotonImage, ImageError> {
+        use resvg::{tiny_skia, usvg};
+
+        let tree = usvg::Tree::from_data(svg, &usvg::Options::default())
+            .map_err(|e| ImageError::LoadImage(format!("SVG: {}", e)))?;
+        let size = tree.size();
+        let (width, height) = match (width, height) {
+            (Some(width), Some(height)) => (width, height),
+            (Some(width), None) => (width, (width as f32 * size.height() / size.width()).round() as u32),
+            (None, Some(height)) => ((height as f32 * size.width() / size.height()).round() as u32, height),
+            (None, None) => (size.width().ceil() as u32, size.height().ceil() as u32),
+        };
+        if width as u64 * height as u64 > max_pixels {
+            return Err(ImageError::TooLarge { width, height, max_pixels });
+        }
+        let mut pixmap = tiny_skia::Pixmap::new(width, height)
+            .ok_or_else(|| ImageError::InvalidParameter("SVG raster size must be greater than 0".to_string()))?;
+
+        let transform = tiny_skia::Transform::from_scale(width as f32 / size.width(), height as f32 / size.height());
+        resvg::render(&tree, transform, &mut pixmap.as_mut());
+
+        // tiny-skia stores premultiplied alpha; photon expects straight RGBA
+        let pixels = pixmap.pixels()
+            .iter()
+            .flat_map(|pixel| {
+                let color = pixel.demultiply();
+                [color.red(), color.green(), color.blue(), color.alpha()]
+            })
+            .collect();
+        Ok(PhotonImage::new(pixels, width, height))
+    }
+
+    /// EXIF to copy onto the output: only when `strip_metadata` is false and the
+    /// output keeps the input's format, and never with GPS data
+    fn carried_exif(image_bytes: &[u8], options: &ImageProcessingOptions) -> Option<Vec<u8>> {
+        if options.strip_metadata.unwrap_or(true) || options.operation == Operation::Normalize {
+            return None;
+        }
+        let output_format = ImageFormat::from_extension(options.output_format_or_default())?;
+        if image::guess_format(image_bytes).ok()? != output_format {
+            return None;
+        }
+        let mut decoder = image::ImageReader::new(Cursor::new(image_bytes))
+            .with_guessed_format()
+            .ok()?
+            .into_decoder()
+            .ok()?;
+        let exif = decoder.exif_metadata().ok()??;
+        crate::exif::strip_gps(&exif)
+    }
+
+    /// Decode the input and describe it as-is, without applying the operation or encoding
+    fn inspect(image_bytes: &[u8], options: &ImageProcessingOptions) -> Result<(Vec<u8>, ImageMetadata), ImageError> {
+        let format = Self::input_format_name(image_bytes)?;
+        let image = Self::decode_input(image_bytes, options)?;
+        let metadata = ImageMetadata::new(image.get_width(), image.get_height(), format, image_bytes.len());
+        Ok((Vec::new(), metadata))
+    }
+
+    /// Apply the requested operation to a decoded image and encode the output
+    ///
+    /// With the `tracing` feature this runs in a span carrying the operation and
+    /// input dimensions; subscribers measure its duration when the span closes.
+    #[cfg_attr(feature = "tracing", tracing::instrument(
+        skip_all,
+        fields(
+            operation = options.operation.as_str(),
+            width = photon_image.get_width(),
+            height = photon_image.get_height(),
+        ),
+    ))]
+    fn render(
+        mut photon_image: PhotonImage,
+        exif: Option<Vec<u8>>,
+        options: &ImageProcessingOptions,
+        warnings: &mut Vec<String>,
+    ) -> Result<(Vec<u8>, ImageMetadata), ImageError> {
+        let palette = Self::apply_operation(&mut photon_image, options, warnings)?;
+
+        let mut output_format = options.output_format_or_default();
+        let keys_alpha = matches!(options.operation, Operation::ChromaKey | Operation::LumaAlpha);
+        if keys_alpha && matches!(output_format.to_lowercase().as_str(), "jpeg" | "jpg") {
+            warnings.push(format!(
+                "{} output needs transparency, so png was used instead of {}",
+                options.operation.as_str(),
+                output_format
+            ));
+            output_format = "png";
+        }
+        let (image_bytes, quality) = Self::encode_output(&photon_image, output_format, exif.as_deref(), options)?;
+
+        let metadata = ImageMetadata {
+            palette: palette.map(|colors| colors.into_iter().map(format_hex_color).collect()),
+            quality,
+            ..ImageMetadata::new(
+                photon_image.get_width(),
+                photon_image.get_height(),
+                output_format.to_string(),
+                image_bytes.len(),
+            )
+        };
+        #[cfg(feature = "tracing")]
+        tracing::debug!(
+            format = metadata.format,
+            width = metadata.width,
+            height = metadata.height,
+            size_bytes = metadata.size_bytes,
+            "rendered image",
+        );
+
+        Ok((image_bytes, metadata))
+    }
+
+    /// Apply `options.operation` in place, returning the palette when the effect extracts one
+    fn apply_operation(
+        image: &mut PhotonImage,
+        options: &ImageProcessingOptions,
+        warnings: &mut Vec<String>,
+    ) -> Result<Option<Vec<[u8; 3]>>, ImageError> {
+        let mut palette = None;
+        match options.operation {
+            Operation::Filter => Self::apply_filter(image, options, warnings)?,
+            Operation::Transform => Self::apply_transform(image, options, warnings)?,
+            Operation::Adjust => Self::apply_adjustments(image, options, warnings)?,
+            Operation::Effect => palette = Self::apply_effects(image, options)?,
+            Operation::Tint => Self::apply_tint(image, options)?,
+            Operation::SwapChannels => Self::swap_channels(image, options)?,
+            Operation::Blend => Self::apply_blend(image, options)?,
+            Operation::Trim => Self::trim(image, options)?,
+            Operation::CropAspect => Self::crop_aspect(image, options)?,
+            Operation::ChromaKey => Self::chroma_key(image, options)?,
+            Operation::LumaAlpha => Self::luma_alpha(image, options)?,
+            Operation::Canvas => Self::canvas(image, options)?,
+            Operation::BlurRegions => Self::blur_regions(image, options)?,
+            // Oriented right after decoding; the encoder then writes no metadata
+            Operation::Normalize => {}
+            // Pixels pass through untouched; only the output encoding changes
+            Operation::Identity => {}
+        }
+        Ok(palette)
+    }
+
+    /// Encode the output, lowering lossy quality as needed to honour `max_bytes`
+    fn encode_output(
+        image: &PhotonImage,
+        format: &str,
+        exif: Option<&[u8]>,
+        options: &ImageProcessingOptions,
+    ) -> Result<(Vec<u8>, Option<u8>), ImageError> {
+        let lossy = matches!(format.to_lowercase().as_str(), "jpeg" | "jpg" | "avif");
+        let quality = options.quality_or_default();
+        let jpeg = JpegSettings::from_options(options)?;
+        let bytes = Self::encode(image, format, Some(quality), exif, jpeg)?;
+        let max_bytes = match options.max_bytes {
+            Some(max_bytes) if bytes.len() > max_bytes => max_bytes,
+            _ => return Ok((bytes, lossy.then_some(quality))),
+        };
+        if !lossy {
+            return Err(ImageError::ExceedsMaxBytes { max_bytes, size_bytes: bytes.len() });
+        }
+
+        // Binary search for the highest quality in 10..quality that fits the budget
+        let (mut low, mut high) = (10u8, quality.saturating_sub(1));
+        let mut smallest = bytes.len();
+        let mut best = None;
+        while low <= high {
+            let mid = low + (high - low) / 2;
+            let candidate = Self::encode(image, format, Some(mid), exif, jpeg)?;
+            if candidate.len() <= max_bytes {
+                best = Some((candidate, Some(mid)));
+                low = mid + 1;
+            } else {
+                smallest = smallest.min(candidate.len());
+                high = mid - 1;
+            }
+        }
+
+        best.ok_or(ImageError::ExceedsMaxBytes { max_bytes, size_bytes: smallest })
+    }
+
+    /// Apply filters to the image
+    fn apply_filter(
+        image: &mut PhotonImage,
+        options: &ImageProcessingOptions,
+        warnings: &mut Vec<String>,
+    ) -> Result<(), ImageError> {
+        // Parse every name up front so a typo late in the list fails before any work
+        let filters = options.filter.iter()
+            .chain(options.filters.iter().flatten())
+            .map(|name| name.parse())
+            .collect::<Result<Vec<Filter>, _>>()?;
+        if filters.is_empty() {
+            return Err(ImageError::UnknownFilter("none".to_string()));
+        }
+        let requested = options.intensity.unwrap_or(1.0);
+        let intensity = if requested.is_nan() { 1.0 } else { requested.clamp(0.0, 2.0) };
+        if intensity != requested {
+            warnings.push(format!("Filter intensity {} is outside 0.0-2.0 and was clamped to {}", requested, intensity));
+        }
+
+        for filter in filters {
+            // Warm, cool and vintage scale with intensity themselves; other filters
+            // render at full strength and partial intensity mixes the original back in
+            let original = (intensity < 1.0 && !filter.scales_with_intensity()).then(|| image.get_raw_pixels());
+            if let (Filter::Grayscale, Some(mode)) = (filter, options.grayscale_mode.as_deref()) {
+                Self::grayscale(image, mode)?;
+            } else {
+                Self::apply_named_filter(image, filter, intensity);
+            }
+            if let Some(original) = original {
+                let mut pixels = image.get_raw_pixels();
+                for (filtered, original) in pixels.iter_mut().zip(original) {
+                    *filtered = (*filtered as f32 * intensity + original as f32 * (1.0 - intensity)).round() as u8;
+                }
+                *image = PhotonImage::new(pixels, image.get_width(), image.get_height());
+            }
+        }
+        Ok(())
+    }
+
+    /// Convert to gray with an explicit conversion, preserving alpha
+    fn grayscale(image: &mut PhotonImage, mode: &str) -> Result<(), ImageError> {
+        let gray: fn(&[u8]) -> f32 = match mode {
+            "luminance" => |p| 0.2126 * p[0] as f32 + 0.7152 * p[1] as f32 + 0.0722 * p[2] as f32,
+            "average" => |p| (p[0] as f32 + p[1] as f32 + p[2] as f32) / 3.0,
+            "lightness" => |p| (p[0].max(p[1]).max(p[2]) as f32 + p[0].min(p[1]).min(p[2]) as f32) / 2.0,
+            "single_channel_r" => |p| p[0] as f32,
+            "single_channel_g" => |p| p[1] as f32,
+            "single_channel_b" => |p| p[2] as f32,
+            other => {
+                return Err(ImageError::InvalidParameter(format!(
+                    "Unknown grayscale mode '{}': expected luminance, average, lightness or single_channel_r/g/b",
+                    other
+                )))
+            }
+        };
+
+        let mut pixels = image.get_raw_pixels();
+        for pixel in pixels.chunks_exact_mut(4) {
+            let value = gray(pixel).round().min(255.0) as u8;
+            pixel[..3].fill(value);
+        }
+        *image = PhotonImage::new(pixels, image.get_width(), image.get_height());
+        Ok(())
+    }
+
+    /// Apply a single named filter; only warm, cool and vintage use `intensity`
+    fn apply_named_filter(image: &mut PhotonImage, filter: Filter, intensity: f32) {
+        match filter {
+            Filter::Grayscale => photon_rs::monochrome::grayscale(image),
+            Filter::Sepia => photon_rs::monochrome::sepia(image),
+            Filter::Invert => photon_rs::channels::invert(image),
+            Filter::Vintage => {
+                // Apply a combination of effects for vintage look
+                photon_rs::monochrome::sepia(image);
+                let brightness_adj = if intensity < 0.5 { 20 } else { 0 };
+                if brightness_adj > 0 {
+                    photon_rs::effects::inc_brightness(image, brightness_adj);
+                }
+            }
+            Filter::Noir => {
+                photon_rs::monochrome::grayscale(image);
+                photon_rs::effects::inc_brightness(image, 10);
+            }
+            Filter::Warm => {
+                photon_rs::channels::alter_red_channel(image, (intensity * 20.0) as i16);
+                photon_rs::channels::alter_blue_channel(image, -(intensity * 10.0) as i16);
+            }
+            Filter::Cool => {
+                photon_rs::channels::alter_blue_channel(image, (intensity * 20.0) as i16);
+                photon_rs::channels::alter_red_channel(image, -(intensity * 10.0) as i16);
+            }
+            Filter::Dramatic => photon_rs::filters::dramatic(image),
+            Filter::Firenze => photon_rs::filters::firenze(image),
+            Filter::Golden => photon_rs::filters::golden(image),
+            Filter::Lix => photon_rs::filters::lix(image),
+            Filter::Lofi => photon_rs::filters::lofi(image),
+            Filter::Neue => photon_rs::filters::neue(image),
+            Filter::Obsidian => photon_rs::filters::obsidian(image),
+            Filter::PastelPink => photon_rs::filters::pastel_pink(image),
+            Filter::Ryo => photon_rs::filters::ryo(image),
+        }
+    }
 
     /// Apply transformations to the image
-    fn apply_transform(image: &mut PhotonImage, options: &ImageProcessingOptions) -> Result<(), String> {
+    fn apply_transform(
+        image: &mut PhotonImage,
+        options: &ImageProcessingOptions,
+        warnings: &mut Vec<String>,
+    ) -> Result<(), ImageError> {
+        // A resize is an enlargement when every requested dimension exceeds the source
+        let (source_width, source_height) = (image.get_width(), image.get_height());
+        if source_width == 0 || source_height == 0 {
+            return Err(ImageError::InvalidParameter("Cannot process a zero-dimension image".to_string()));
+        }
+        if options.resize_width == Some(0) || options.resize_height == Some(0) {
+            return Err(ImageError::InvalidParameter("Resize dimensions must be greater than 0".to_string()));
+        }
+        let upscale = match (options.resize_width, options.resize_height) {
+            (Some(width), Some(height)) => width > source_width && height > source_height,
+            (Some(width), None) => width > source_width,
+            (None, Some(height)) => height > source_height,
+            (None, None) => false,
+        };
+
         // Handle resize
-        if let (Some(width), Some(height)) = (options.resize_width, options.resize_height) {
+        let filter = options.resize_filter_or_default()?;
+        if upscale && options.no_upscale.unwrap_or(false) {
+            warnings.push(format!(
+                "Resize skipped: the requested size is larger than the {}x{} source and no_upscale is set",
+                source_width, source_height
+            ));
+        } else if let (Some(width), Some(height)) = (options.resize_width, options.resize_height) {
             let keep_aspect = options.keep_aspect_ratio.unwrap_or(true);
             if keep_aspect {
                 // Calculate aspect ratio preserving dimensions
@@ -286,22 +1925,45 @@ impl ImageProcessor {
                     (width as f32, width as f32 / aspect_ratio)
                 };
                 
-                *image = photon_rs::transform::resize(image, new_width as u32, new_height as u32, photon_rs::transform::SamplingFilter::Nearest);
+                // Very wide or tall sources can round a side down to nothing
+                let (new_width, new_height) = ((new_width as u32).max(1), (new_height as u32).max(1));
+                *image = photon_rs::transform::resize(image, new_width, new_height, filter);
             } else {
-                *image = photon_rs::transform::resize(image, width, height, photon_rs::transform::SamplingFilter::Nearest);
+                *image = photon_rs::transform::resize(image, width, height, filter);
             }
+        } else if options.resize_width.is_some() || options.resize_height.is_some() {
+            // A single dimension scales the other to keep the aspect ratio
+            let aspect_ratio = image.get_width() as f32 / image.get_height() as f32;
+            let (new_width, new_height) = match (options.resize_width, options.resize_height) {
+                (Some(width), _) => (width, (width as f32 / aspect_ratio).round() as u32),
+                (_, Some(height)) => ((height as f32 * aspect_ratio).round() as u32, height),
+                (None, None) => unreachable!(),
+            };
+            *image = photon_rs::transform::resize(
+                image,
+                new_width.max(1),
+                new_height.max(1),
+                filter,
+            );
         }
 
         // Handle crop
-        if let (Some(x), Some(y), Some(w), Some(h)) = (options.crop_x, options.crop_y, options.crop_width, options.crop_height) {
-            *image = photon_rs::transform::crop(image, x, y, w, h);
+        if let Some((x, y, w, h)) = options.crop_rect(image.get_width(), image.get_height())? {
+            let (image_width, image_height) = (image.get_width(), image.get_height());
+            let fits = w > 0 && h > 0
+                && x.checked_add(w).is_some_and(|right| right <= image_width)
+                && y.checked_add(h).is_some_and(|bottom| bottom <= image_height);
+            if !fits {
+                return Err(ImageError::InvalidCrop { x, y, width: w, height: h, image_width, image_height });
+            }
+            *image = photon_rs::transform::crop(image, x, y, x + w, y + h);
         }
 
         // Handle rotation - simplified for now
         if let Some(_angle) = options.rotation_angle {
             // Rotation feature will be implemented in future version
             // The photon-rs API for rotation varies between versions
-            return Err("Rotation feature not yet implemented".to_string());
+            return Err(ImageError::NotImplemented("Rotation feature not yet implemented".to_string()));
         }
 
         // Handle flips
@@ -311,25 +1973,104 @@ impl ImageProcessor {
         if options.flip_vertical.unwrap_or(false) {
             photon_rs::transform::flipv(image);
         }
+        if options.transpose.unwrap_or(false) {
+            Self::transpose(image);
+        }
 
         Ok(())
     }
 
+    /// Rotate and flip the decoded image upright according to the input's EXIF orientation
+    fn auto_orient(image: &mut PhotonImage, image_bytes: &[u8]) {
+        use image::metadata::Orientation;
+        let orientation = image::ImageReader::new(Cursor::new(image_bytes))
+            .with_guessed_format()
+            .ok()
+            .and_then(|reader| reader.into_decoder().ok())
+            .and_then(|mut decoder| decoder.orientation().ok())
+            .unwrap_or(Orientation::NoTransforms);
+
+        // Quarter turns are a transpose followed by a flip
+        let (transpose, flip_horizontal, flip_vertical) = match orientation {
+            Orientation::NoTransforms => (false, false, false),
+            Orientation::FlipHorizontal => (false, true, false),
+            Orientation::Rotate180 => (false, true, true),
+            Orientation::FlipVertical => (false, false, true),
+            Orientation::Rotate90FlipH => (true, false, false),
+            Orientation::Rotate90 => (true, true, false),
+            Orientation::Rotate270FlipH => (true, true, true),
+            Orientation::Rotate270 => (true, false, true),
+        };
+        if transpose {
+            Self::transpose(image);
+        }
+        if flip_horizontal {
+            photon_rs::transform::fliph(image);
+        }
+        if flip_vertical {
+            photon_rs::transform::flipv(image);
+        }
+    }
+
+    /// Mirror the image across its main diagonal so pixel (x, y) moves to (y, x)
+    fn transpose(image: &mut PhotonImage) {
+        let (width, height) = (image.get_width() as usize, image.get_height() as usize);
+        let pixels = image.get_raw_pixels();
+        let mut transposed = vec![0u8; pixels.len()];
+        for y in 0..height {
+            for x in 0..width {
+                let source = (y * width + x) * 4;
+                let target = (x * height + y) * 4;
+                transposed[target..target + 4].copy_from_slice(&pixels[source..source + 4]);
+            }
+        }
+        *image = PhotonImage::new(transposed, height as u32, width as u32);
+    }
+
     /// Apply color adjustments using available photon-rs functions
-    fn apply_adjustments(image: &mut PhotonImage, options: &ImageProcessingOptions) -> Result<(), String> {
-        if let Some(brightness) = options.brightness {
+    fn apply_adjustments(
+        image: &mut PhotonImage,
+        options: &ImageProcessingOptions,
+        warnings: &mut Vec<String>,
+    ) -> Result<(), ImageError> {
+        if options.auto_levels.unwrap_or(false) {
+            let clip_percent = options.clip_percent.unwrap_or(0.0);
+            if !(0.0..50.0).contains(&clip_percent) {
+                return Err(ImageError::InvalidParameter("Clip percent must be between 0.0 and 50.0".to_string()));
+            }
+            Self::auto_levels(image, clip_percent);
+        }
+
+        if options.temperature.is_some() || options.tint.is_some() {
+            Self::white_balance(image, options.temperature.unwrap_or(0.0), options.tint.unwrap_or(0.0))?;
+        }
+
+        if let (Some(brightness), true) = (options.brightness, options.linear_light.unwrap_or(false)) {
+            // Scale the light itself, as an exposure change would
+            let gain = brightness.max(0.0);
+            let mut pixels = image.get_raw_pixels();
+            for pixel in pixels.chunks_exact_mut(4) {
+                for channel in &mut pixel[..3] {
+                    *channel = linear_to_srgb(SRGB_TO_LINEAR[*channel as usize] * gain);
+                }
+            }
+            *image = PhotonImage::new(pixels, image.get_width(), image.get_height());
+        } else if let Some(brightness) = options.brightness {
+            let adjustment = (brightness - 1.0) * 50.0;
+            if adjustment.abs() > 255.0 {
+                warnings.push(format!("Brightness {} exceeds the supported range and was clamped", brightness));
+            }
+            let amount = adjustment.abs().min(255.0) as u8;
             if brightness > 1.0 {
-                let adjustment = ((brightness - 1.0) * 50.0).clamp(0.0, 255.0) as u8;
-                photon_rs::effects::inc_brightness(image, adjustment);
+                photon_rs::effects::inc_brightness(image, amount);
             } else if brightness < 1.0 {
-                let adjustment = ((1.0 - brightness) * 50.0).clamp(0.0, 255.0) as u8;
-                photon_rs::effects::dec_brightness(image, adjustment);
+                photon_rs::effects::dec_brightness(image, amount);
             }
         }
 
-        if let Some(_contrast) = options.contrast {
+        if options.contrast.is_some() {
             // Contrast adjustment not directly available in photon-rs
-            // Could be implemented with histogram manipulation in the future
+            warnings.push("Contrast adjustment is not supported and was ignored".to_string());
         }
 
         if let Some(saturation) = options.saturation {
@@ -351,192 +2092,3675 @@ impl ImageProcessor {
 
         // Hue rotation not available in basic photon-rs
 
+        if let Some(opacity) = options.opacity {
+            if !(0.0..=1.0).contains(&opacity) {
+                return Err(ImageError::InvalidParameter("Opacity must be between 0.0 and 1.0".to_string()));
+            }
+            let format = options.output_format_or_default().to_lowercase();
+            if format == "jpeg" || format == "jpg" {
+                return Err(ImageError::InvalidParameter(
+                    "Opacity requires an output format with alpha (png or webp)".to_string()
+                ));
+            }
+
+            let mut pixels = image.get_raw_pixels();
+            for pixel in pixels.chunks_exact_mut(4) {
+                pixel[3] = (pixel[3] as f32 * opacity).round() as u8;
+            }
+            *image = PhotonImage::new(pixels, image.get_width(), image.get_height());
+        }
+
         Ok(())
     }
 
-    /// Apply special effects
-    fn apply_effects(image: &mut PhotonImage, options: &ImageProcessingOptions) -> Result<(), String> {
-        let effect = options.filter.as_deref().unwrap_or("none");
+    /// 16-bit counterpart of [`Self::apply_adjustments`] covering brightness,
+    /// saturation and opacity, with 8-bit amounts scaled up by 257
+    fn apply_adjustments_16bit(
+        image: &mut image::ImageBuffer<image::Rgba<u16>, Vec<u16>>,
+        options: &ImageProcessingOptions,
+        warnings: &mut Vec<String>,
+    ) -> Result<(), ImageError> {
+        let unsupported: Vec<&str> = [
+            ("auto_levels", options.auto_levels.is_some()),
+            ("temperature", options.temperature.is_some()),
+            ("tint", options.tint.is_some()),
+        ]
+        .iter()
+        .filter(|(_, set)| *set)
+        .map(|(name, _)| *name)
+        .collect();
+        if !unsupported.is_empty() {
+            return Err(ImageError::InvalidParameter(format!(
+                "Not supported with bit_depth 16: {}",
+                unsupported.join(", ")
+            )));
+        }
 
-        match effect {
-            "edge_detection" => photon_rs::conv::edge_detection(image),
-            "emboss" => photon_rs::conv::emboss(image),
-            "laplace" => photon_rs::conv::laplace(image),
-            "sobel_horizontal" => photon_rs::conv::sobel_horizontal(image),
-            "sobel_vertical" => photon_rs::conv::sobel_vertical(image),
-            "blur" => photon_rs::conv::gaussian_blur(image, 2),
-            "sharpen" => photon_rs::conv::sharpen(image),
-            "threshold" => {
-                let threshold = (options.intensity.unwrap_or(0.5) * 255.0) as u32;
-                photon_rs::monochrome::threshold(image, threshold);
-            }
-            "solarize" => photon_rs::effects::solarize(image),
-            "posterize" => photon_rs::effects::inc_brightness(image, 20),
-            _ => return Err(format!("Unknown effect: {}", effect)),
+        let shift = |value: u16, amount: i32| (value as i32 + amount).clamp(0, u16::MAX as i32) as u16;
+        if let (Some(brightness), true) = (options.brightness, options.linear_light.unwrap_or(false)) {
+            let gain = brightness.max(0.0);
+            for pixel in image.pixels_mut() {
+                for channel in &mut pixel.0[..3] {
+                    let linear = (decode_srgb(*channel as f32 / 65535.0) * gain).min(1.0);
+                    *channel = (encode_srgb(linear) * 65535.0).round() as u16;
+                }
+            }
+        } else if let Some(brightness) = options.brightness {
+            let adjustment = (brightness - 1.0) * 50.0;
+            if adjustment.abs() > 255.0 {
+                warnings.push(format!("Brightness {} exceeds the supported range and was clamped", brightness));
+            }
+            let amount = (adjustment.clamp(-255.0, 255.0) as i32) * 257;
+            for pixel in image.pixels_mut() {
+                for channel in &mut pixel.0[..3] {
+                    *channel = shift(*channel, amount);
+                }
+            }
+        }
+
+        if options.contrast.is_some() {
+            warnings.push("Contrast adjustment is not supported and was ignored".to_string());
+        }
+
+        if let Some(saturation) = options.saturation {
+            if saturation < 0.5 {
+                for pixel in image.pixels_mut() {
+                    let [r, g, b, _] = pixel.0;
+                    let average = ((r as u32 + g as u32 + b as u32) / 3) as u16;
+                    pixel.0[..3].fill(average);
+                }
+            } else if saturation != 1.0 {
+                let amount = ((saturation - 1.0) * 30.0) as i32 * 257;
+                for pixel in image.pixels_mut() {
+                    pixel.0[0] = shift(pixel.0[0], amount);
+                    pixel.0[2] = shift(pixel.0[2], amount);
+                }
+            }
+        }
+
+        if let Some(opacity) = options.opacity {
+            if !(0.0..=1.0).contains(&opacity) {
+                return Err(ImageError::InvalidParameter("Opacity must be between 0.0 and 1.0".to_string()));
+            }
+            for pixel in image.pixels_mut() {
+                pixel.0[3] = (pixel.0[3] as f32 * opacity).round() as u16;
+            }
         }
 
         Ok(())
     }
 
-    /// Process multiple images in batch
-    pub fn process_batch(images: Vec<String>, options: &ImageProcessingOptions) -> BatchProcessingResult {
-        // Note: WASM doesn't support std::time::Instant, so we'll use a placeholder for timing
-        let mut results = Vec::new();
-        let mut successful = 0;
-        let mut failed = 0;
-
-        for image_data in images {
-            let result = Self::process_image(&image_data, options);
-            if result.success {
-                successful += 1;
-            } else {
-                failed += 1;
+    /// Stretch each RGB channel so the range left after clipping `clip_percent` of pixels
+    /// at either end spans 0-255; channels already at full range are left untouched
+    fn auto_levels(image: &mut PhotonImage, clip_percent: f32) {
+        let mut pixels = image.get_raw_pixels();
+        let pixel_count = pixels.len() / 4;
+        let clip = (pixel_count as f32 * clip_percent / 100.0) as usize;
+
+        for channel in 0..3 {
+            let mut histogram = [0usize; 256];
+            for pixel in pixels.chunks_exact(4) {
+                histogram[pixel[channel] as usize] += 1;
+            }
+            let bound = |mut levels: Box<dyn Iterator<Item = usize>>| {
+                let mut seen = 0;
+                levels.find(|&level| {
+                    seen += histogram[level];
+                    seen > clip
+                })
+            };
+            let (Some(low), Some(high)) = (bound(Box::new(0..256)), bound(Box::new((0..256).rev()))) else {
+                continue;
+            };
+            if high <= low || (low == 0 && high == 255) {
+                continue;
+            }
+
+            let scale = 255.0 / (high - low) as f32;
+            for pixel in pixels.chunks_exact_mut(4) {
+                let value = (pixel[channel] as f32 - low as f32) * scale;
+                pixel[channel] = value.round().clamp(0.0, 255.0) as u8;
             }
-            results.push(result);
         }
 
-        // WASM doesn't support timing, so we'll use a placeholder
-        let total_time_ms = 0u128;
+        *image = PhotonImage::new(pixels, image.get_width(), image.get_height());
+    }
 
-        BatchProcessingResult {
-            processed: results.len(),
-            successful,
-            failed,
-            results,
-            total_time_ms,
+    /// Apply per-channel gains: temperature trades red against blue, tint trades green against magenta
+    fn white_balance(image: &mut PhotonImage, temperature: f32, tint: f32) -> Result<(), ImageError> {
+        if !(-1.0..=1.0).contains(&temperature) || !(-1.0..=1.0).contains(&tint) {
+            return Err(ImageError::InvalidParameter(
+                "Temperature and tint must be between -1.0 and 1.0".to_string()
+            ));
+        }
+
+        let gains = [
+            (1.0 + 0.3 * temperature) * (1.0 + 0.15 * tint),
+            1.0 - 0.3 * tint,
+            (1.0 - 0.3 * temperature) * (1.0 + 0.15 * tint),
+        ];
+        let mut pixels = image.get_raw_pixels();
+        for pixel in pixels.chunks_exact_mut(4) {
+            for (channel, gain) in pixel.iter_mut().zip(gains) {
+                *channel = (*channel as f32 * gain).round().clamp(0.0, 255.0) as u8;
+            }
         }
+
+        *image = PhotonImage::new(pixels, image.get_width(), image.get_height());
+        Ok(())
     }
-}
 
-#[cfg(test)]
-mod tests {
-    use super::*;
+    /// Crop away uniform borders matching the top-left pixel within `trim_tolerance`
+    fn trim(image: &mut PhotonImage, options: &ImageProcessingOptions) -> Result<(), ImageError> {
+        let tolerance = options.trim_tolerance.unwrap_or(0);
+        let (width, height) = (image.get_width(), image.get_height());
+        let pixels = image.get_raw_pixels();
+        let border = [pixels[0], pixels[1], pixels[2], pixels[3]];
 
-    // Create a simple test image as base64
-    fn create_test_image_base64() -> String {
-        // Create a simple 2x2 red image
-        let red_pixel = [255u8, 0, 0, 255]; // RGBA red
-        let mut image_data = Vec::new();
-        
-        // 2x2 image with all red pixels
-        for _ in 0..4 {
-            image_data.extend_from_slice(&red_pixel);
+        let mut bounds: Option<(u32, u32, u32, u32)> = None;
+        for (index, pixel) in pixels.chunks_exact(4).enumerate() {
+            let is_border = pixel.iter().zip(border).all(|(&value, edge)| value.abs_diff(edge) <= tolerance);
+            if is_border {
+                continue;
+            }
+            let (x, y) = (index as u32 % width, index as u32 / width);
+            bounds = Some(match bounds {
+                Some((left, top, right, bottom)) => (left.min(x), top.min(y), right.max(x), bottom.max(y)),
+                None => (x, y, x, y),
+            });
         }
-        
-        let photon_image = PhotonImage::new(image_data, 2, 2);
-        
-        // Convert to base64
-        let bytes = ImageProcessor::photon_image_to_bytes(&photon_image, "png", None)
-            .unwrap_or_else(|_| vec![137, 80, 78, 71, 13, 10, 26, 10]); // PNG header
-        ImageProcessor::bytes_to_base64_data_url(&bytes, "png")
-    }
 
-    #[test]
-    fn test_base64_conversion() {
-        let test_image = create_test_image_base64();
-        
-        // Test conversion from base64 to PhotonImage
-        let photon_result = ImageProcessor::base64_to_photon_image(&test_image);
-        assert!(photon_result.is_ok());
-        
-        let photon_image = photon_result.unwrap();
-        assert_eq!(photon_image.get_width(), 2);
-        assert_eq!(photon_image.get_height(), 2);
+        // A uniform image has no content to keep, so leave it untouched
+        if let Some((left, top, right, bottom)) = bounds {
+            if (left, top, right + 1, bottom + 1) != (0, 0, width, height) {
+                *image = photon_rs::transform::crop(image, left, top, right + 1, bottom + 1);
+            }
+        }
+        Ok(())
     }
 
-    #[test]
-    fn test_filter_processing() {
-        let test_image = create_test_image_base64();
-        let options = ImageProcessingOptions {
-            operation: "filter".to_string(),
-            filter: Some("grayscale".to_string()),
-            intensity: Some(1.0),
-            output_format: Some("png".to_string()),
-            ..Default::default()
+    /// Center-crop to `target_aspect` without resizing, keeping as many pixels as possible
+    fn crop_aspect(image: &mut PhotonImage, options: &ImageProcessingOptions) -> Result<(), ImageError> {
+        let ratio = options.target_aspect.as_deref()
+            .ok_or_else(|| ImageError::InvalidParameter("crop_aspect requires a target_aspect".to_string()))?;
+        let target = parse_aspect_ratio(ratio)?;
+        let (width, height) = (image.get_width(), image.get_height());
+
+        let (crop_width, crop_height) = if width as f64 / height as f64 > target {
+            (((height as f64 * target).round() as u32).clamp(1, width), height)
+        } else {
+            (width, ((width as f64 / target).round() as u32).clamp(1, height))
         };
+        if (crop_width, crop_height) != (width, height) {
+            let (left, top) = ((width - crop_width) / 2, (height - crop_height) / 2);
+            *image = photon_rs::transform::crop(image, left, top, left + crop_width, top + crop_height);
+        }
+        Ok(())
+    }
 
-        let result = ImageProcessor::process_image(&test_image, &options);
-        assert!(result.success);
-        assert!(result.image_data.is_some());
-        assert!(result.metadata.is_some());
+    /// Make pixels within `key_tolerance` of `key_color` (per channel) fully transparent
+    fn chroma_key(image: &mut PhotonImage, options: &ImageProcessingOptions) -> Result<(), ImageError> {
+        let [r, g, b, _] = parse_color(options.key_color.as_deref().unwrap_or("#00ff00"))?;
+        let tolerance = options.key_tolerance.unwrap_or(32);
+
+        let mut pixels = image.get_raw_pixels();
+        for pixel in pixels.chunks_exact_mut(4) {
+            let keyed = pixel[..3].iter().zip([r, g, b]).all(|(&channel, key)| channel.abs_diff(key) <= tolerance);
+            if keyed {
+                pixel[3] = 0;
+            }
+        }
+        *image = PhotonImage::new(pixels, image.get_width(), image.get_height());
+        Ok(())
     }
 
-    #[test]
-    fn test_invalid_image_data() {
-        let invalid_data = "invalid_base64_data";
-        let options = ImageProcessingOptions {
-            operation: "filter".to_string(),
-            filter: Some("grayscale".to_string()),
-            ..Default::default()
-        };
+    /// Make pixels whose Rec. 709 luminance is past `luma_threshold` fully transparent
+    fn luma_alpha(image: &mut PhotonImage, options: &ImageProcessingOptions) -> Result<(), ImageError> {
+        let threshold = options.luma_threshold.unwrap_or(240) as f32;
+        let above = options.luma_above.unwrap_or(true);
 
-        let result = ImageProcessor::process_image(invalid_data, &options);
-        assert!(!result.success);
-        assert!(result.error.is_some());
+        let mut pixels = image.get_raw_pixels();
+        for pixel in pixels.chunks_exact_mut(4) {
+            let luma = 0.2126 * pixel[0] as f32 + 0.7152 * pixel[1] as f32 + 0.0722 * pixel[2] as f32;
+            if (above && luma >= threshold) || (!above && luma <= threshold) {
+                pixel[3] = 0;
+            }
+        }
+        *image = PhotonImage::new(pixels, image.get_width(), image.get_height());
+        Ok(())
     }
 
-    #[test]
-    fn test_batch_processing() {
-        let test_image = create_test_image_base64();
-        let images = vec![test_image.clone(), test_image];
-        let options = ImageProcessingOptions {
-            operation: "filter".to_string(),
-            filter: Some("sepia".to_string()),
-            ..Default::default()
+    /// Place the image, unscaled, on a `canvas_width` x `canvas_height` canvas
+    /// filled with `canvas_color` at the given `anchor`; any part of the image
+    /// falling outside a smaller canvas is clipped
+    fn canvas(image: &mut PhotonImage, options: &ImageProcessingOptions) -> Result<(), ImageError> {
+        let (width, height) = (image.get_width(), image.get_height());
+        let canvas_width = options.canvas_width.unwrap_or(width);
+        let canvas_height = options.canvas_height.unwrap_or(height);
+        if canvas_width == 0 || canvas_height == 0 {
+            return Err(ImageError::InvalidParameter("Canvas dimensions must be greater than 0".to_string()));
+        }
+        let max_pixels = options.max_pixels_or_default();
+        if canvas_width as u64 * canvas_height as u64 > max_pixels {
+            return Err(ImageError::TooLarge { width: canvas_width, height: canvas_height, max_pixels });
+        }
+        let fill = parse_color(options.canvas_color.as_deref().unwrap_or("#00000000"))?;
+
+        // Position along each axis in halves: 0 = start, 1 = middle, 2 = end
+        let (horizontal, vertical) = match options.anchor.as_deref().unwrap_or("center") {
+            "top_left" => (0, 0),
+            "top" => (1, 0),
+            "top_right" => (2, 0),
+            "left" => (0, 1),
+            "center" => (1, 1),
+            "right" => (2, 1),
+            "bottom_left" => (0, 2),
+            "bottom" => (1, 2),
+            "bottom_right" => (2, 2),
+            other => return Err(ImageError::InvalidParameter(format!(
+                "Unknown anchor '{}': expected center, top_left, top, top_right, left, right, bottom_left, bottom or bottom_right",
+                other
+            ))),
         };
+        let offset_x = (canvas_width as i64 - width as i64) * horizontal / 2;
+        let offset_y = (canvas_height as i64 - height as i64) * vertical / 2;
 
-        let result = ImageProcessor::process_batch(images, &options);
-        assert_eq!(result.processed, 2);
-        assert_eq!(result.successful, 2);
-        assert_eq!(result.failed, 0);
+        let source = image.get_raw_pixels();
+        let mut pixels = fill.repeat(canvas_width as usize * canvas_height as usize);
+        let first_x = (-offset_x).max(0);
+        let last_x = (width as i64).min(canvas_width as i64 - offset_x);
+        for y in 0..height as i64 {
+            let target_y = y + offset_y;
+            if first_x >= last_x || !(0..canvas_height as i64).contains(&target_y) {
+                continue;
+            }
+            let from = ((y * width as i64 + first_x) * 4) as usize;
+            let to = ((target_y * canvas_width as i64 + first_x + offset_x) * 4) as usize;
+            let len = ((last_x - first_x) * 4) as usize;
+            pixels[to..to + len].copy_from_slice(&source[from..from + len]);
+        }
+        *image = PhotonImage::new(pixels, canvas_width, canvas_height);
+        Ok(())
     }
 
-    #[test]
-    fn test_transform_operations() {
-        let test_image = create_test_image_base64();
+    /// Gaussian-blur only the given `regions`, e.g. faces or IDs found by an external detector
+    ///
+    /// The radius scales with the largest region so bigger areas are still obscured.
+    fn blur_regions(image: &mut PhotonImage, options: &ImageProcessingOptions) -> Result<(), ImageError> {
+        let (width, height) = (image.get_width(), image.get_height());
+        let regions = options.regions.as_deref().unwrap_or_default();
+        if regions.is_empty() {
+            return Err(ImageError::InvalidParameter("blur_regions requires at least one region".to_string()));
+        }
+        for &[x, y, region_width, region_height] in regions {
+            let inside = x as u64 + region_width as u64 <= width as u64
+                && y as u64 + region_height as u64 <= height as u64;
+            if region_width == 0 || region_height == 0 || !inside {
+                return Err(ImageError::InvalidParameter(format!(
+                    "Blur region {}x{} at ({}, {}) is outside the {}x{} image",
+                    region_width, region_height, x, y, width, height
+                )));
+            }
+        }
+
+        let radius = regions.iter().map(|region| region[2].max(region[3]) / 8).max().unwrap_or(0).max(2);
+        let mut blurred = image.clone();
+        photon_rs::conv::gaussian_blur(&mut blurred, radius as i32);
+        let blurred = blurred.get_raw_pixels();
+
+        let mut pixels = image.get_raw_pixels();
+        for &[x, y, region_width, region_height] in regions {
+            let len = region_width as usize * 4;
+            for row in y..y + region_height {
+                let start = (row as usize * width as usize + x as usize) * 4;
+                pixels[start..start + len].copy_from_slice(&blurred[start..start + len]);
+            }
+        }
+        *image = PhotonImage::new(pixels, width, height);
+        Ok(())
+    }
+
+    /// Blend every pixel toward `tint_color` by `tint_strength` (0.0-1.0, default 0.5)
+    fn apply_tint(image: &mut PhotonImage, options: &ImageProcessingOptions) -> Result<(), ImageError> {
+        let color = options.tint_color.as_deref()
+            .ok_or_else(|| ImageError::InvalidParameter("Tint requires a tint_color".to_string()))?;
+        let [r, g, b] = parse_hex_color(color)?;
+        let strength = options.tint_strength.unwrap_or(0.5);
+        if !(0.0..=1.0).contains(&strength) {
+            return Err(ImageError::InvalidParameter("Tint strength must be between 0.0 and 1.0".to_string()));
+        }
+
+        let mut pixels = image.get_raw_pixels();
+        for pixel in pixels.chunks_exact_mut(4) {
+            for (channel, target) in pixel.iter_mut().zip([r, g, b]) {
+                let value = *channel as f32 + (target as f32 - *channel as f32) * strength;
+                *channel = value.round().clamp(0.0, 255.0) as u8;
+            }
+        }
+
+        *image = PhotonImage::new(pixels, image.get_width(), image.get_height());
+        Ok(())
+    }
+
+    /// Permute the R/G/B channels of every pixel according to `channel_order`
+    fn swap_channels(image: &mut PhotonImage, options: &ImageProcessingOptions) -> Result<(), ImageError> {
+        let order = options.channel_order.as_deref()
+            .ok_or_else(|| ImageError::InvalidParameter("Swap channels requires a channel_order".to_string()))?;
+
+        // For each output channel, the index of the input channel it is read from
+        let invalid = || ImageError::InvalidParameter(format!(
+            "Channel order '{}' must be a permutation of 'rgb'",
+            order
+        ));
+        let sources: Vec<usize> = order.chars()
+            .map(|c| "rgb".find(c.to_ascii_lowercase()))
+            .collect::<Option<_>>()
+            .ok_or_else(invalid)?;
+        if sources.len() != 3 || !(0..3).all(|channel| sources.contains(&channel)) {
+            return Err(invalid());
+        }
+
+        let mut pixels = image.get_raw_pixels();
+        for pixel in pixels.chunks_exact_mut(4) {
+            let rgb = [pixel[0], pixel[1], pixel[2]];
+            for (slot, source) in sources.iter().enumerate() {
+                pixel[slot] = rgb[*source];
+            }
+        }
+
+        *image = PhotonImage::new(pixels, image.get_width(), image.get_height());
+        Ok(())
+    }
+
+    /// Composite `blend_base64` over the image with `blend_mode` at `blend_opacity`
+    ///
+    /// The top image is resized to the input's dimensions when they differ.
+    /// The top image's alpha scales its contribution; the input's alpha is kept.
+    fn apply_blend(image: &mut PhotonImage, options: &ImageProcessingOptions) -> Result<(), ImageError> {
+        let top_data = options.blend_base64.as_deref()
+            .ok_or_else(|| ImageError::InvalidParameter("Blend requires a blend_base64 image".to_string()))?;
+        let mode = options.blend_mode.as_deref().unwrap_or("normal");
+        let blend: fn(f32, f32) -> f32 = match mode {
+            "normal" => |_, top| top,
+            "multiply" => |base, top| base * top,
+            "screen" => |base, top| 1.0 - (1.0 - base) * (1.0 - top),
+            "overlay" => |base, top| {
+                if base < 0.5 {
+                    2.0 * base * top
+                } else {
+                    1.0 - 2.0 * (1.0 - base) * (1.0 - top)
+                }
+            },
+            _ => return Err(ImageError::InvalidParameter(format!("Unknown blend mode '{}'", mode))),
+        };
+        let opacity = options.blend_opacity.unwrap_or(1.0);
+        if !(0.0..=1.0).contains(&opacity) {
+            return Err(ImageError::InvalidParameter("Blend opacity must be between 0.0 and 1.0".to_string()));
+        }
+
+        let (width, height) = (image.get_width(), image.get_height());
+        let max_pixels = options.max_pixels_or_default();
+        Self::check_input_size(Self::decoded_len(top_data), options)?;
+        let mut top = Self::bytes_to_photon_image_limited(&Self::base64_to_bytes(top_data)?, max_pixels)?;
+        if (top.get_width(), top.get_height()) != (width, height) {
+            let filter = photon_rs::transform::SamplingFilter::Triangle;
+            top = photon_rs::transform::resize(&top, width, height, filter);
+        }
+
+        let mut pixels = image.get_raw_pixels();
+        for (base, top) in pixels.chunks_exact_mut(4).zip(top.get_raw_pixels().chunks_exact(4)) {
+            let weight = opacity * top[3] as f32 / 255.0;
+            for channel in 0..3 {
+                let b = base[channel] as f32 / 255.0;
+                let mixed = blend(b, top[channel] as f32 / 255.0);
+                base[channel] = ((b + (mixed - b) * weight) * 255.0).round().clamp(0.0, 255.0) as u8;
+            }
+        }
+
+        *image = PhotonImage::new(pixels, width, height);
+        Ok(())
+    }
+
+    /// Apply special effects
+    ///
+    /// Returns the palette chosen by palette-reducing effects.
+    fn apply_effects(image: &mut PhotonImage, options: &ImageProcessingOptions) -> Result<Option<Vec<[u8; 3]>>, ImageError> {
+        let effect: Effect = options.filter.as_deref().unwrap_or("none").parse()?;
+        let mut palette = None;
+
+        match effect {
+            Effect::EdgeDetection => photon_rs::conv::edge_detection(image),
+            Effect::Emboss => photon_rs::conv::emboss(image),
+            Effect::Laplace => photon_rs::conv::laplace(image),
+            Effect::SobelHorizontal => photon_rs::conv::sobel_horizontal(image),
+            Effect::SobelVertical => photon_rs::conv::sobel_vertical(image),
+            Effect::Sobel => Self::sobel_magnitude(image),
+            Effect::Blur => photon_rs::conv::gaussian_blur(image, 2),
+            Effect::Sharpen => photon_rs::conv::sharpen(image),
+            Effect::Threshold => Self::threshold(image, options)?,
+            Effect::Solarize => photon_rs::effects::solarize(image),
+            Effect::Posterize => {
+                let levels = options.levels.unwrap_or(4);
+                if !(2..=256).contains(&levels) {
+                    return Err(ImageError::InvalidParameter("Posterize levels must be between 2 and 256".to_string()));
+                }
+                Self::posterize(image, levels);
+            }
+            Effect::Pixelate => {
+                let block_size = options.block_size.unwrap_or(8);
+                if block_size == 0 {
+                    return Err(ImageError::InvalidParameter("Block size must be greater than 0".to_string()));
+                }
+                // Restrict to the crop rectangle when one is given, so a region can be censored
+                let region = options.crop_rect(image.get_width(), image.get_height())?
+                    .unwrap_or((0, 0, image.get_width(), image.get_height()));
+                Self::pixelate_region(image, region, block_size)?;
+            }
+            Effect::Convolve => {
+                let kernel = options.kernel.as_deref()
+                    .ok_or_else(|| ImageError::InvalidParameter("Convolve effect requires a kernel".to_string()))?;
+                Self::convolve(image, kernel, options.kernel_divisor)?;
+            }
+            Effect::Noise => {
+                let mut rng = match options.seed {
+                    Some(seed) => StdRng::seed_from_u64(seed),
+                    None => StdRng::from_entropy(),
+                };
+                let noise_type = options.noise_type.as_deref().unwrap_or("gaussian");
+                Self::add_noise(image, noise_type, options.noise_amount, &mut rng)?;
+            }
+            Effect::Oil => {
+                let radius = options.oil_radius.unwrap_or(4);
+                let levels = options.oil_levels.unwrap_or(20);
+                if !(1..=10).contains(&radius) {
+                    return Err(ImageError::InvalidParameter("Oil radius must be between 1 and 10".to_string()));
+                }
+                if !(2..=256).contains(&levels) {
+                    return Err(ImageError::InvalidParameter("Oil levels must be between 2 and 256".to_string()));
+                }
+                Self::oil_paint(image, radius, levels as usize);
+            }
+            Effect::Quantize => {
+                let palette_size = options.palette_size.unwrap_or(16);
+                if !(2..=256).contains(&palette_size) {
+                    return Err(ImageError::InvalidParameter("Palette size must be between 2 and 256".to_string()));
+                }
+                palette = Some(Self::quantize(image, palette_size as usize));
+            }
+            Effect::Dither => {
+                // Black and white by default; a median-cut palette when palette_size is given
+                let target = match options.palette_size {
+                    Some(size) if (2..=256).contains(&size) => {
+                        median_cut(rgb_colors(&image.get_raw_pixels()), size as usize)
+                    }
+                    Some(_) => {
+                        return Err(ImageError::InvalidParameter("Palette size must be between 2 and 256".to_string()));
+                    }
+                    None => {
+                        photon_rs::monochrome::grayscale(image);
+                        vec![[0, 0, 0], [255, 255, 255]]
+                    }
+                };
+                Self::dither(image, &target);
+                palette = Some(target);
+            }
+            Effect::Clahe => {
+                let tile_size = options.clahe_tile_size.unwrap_or(64);
+                let clip_limit = options.clahe_clip_limit.unwrap_or(2.0);
+                if tile_size == 0 {
+                    return Err(ImageError::InvalidParameter("CLAHE tile size must be greater than 0".to_string()));
+                }
+                if clip_limit.is_nan() || clip_limit < 1.0 {
+                    return Err(ImageError::InvalidParameter("CLAHE clip limit must be at least 1.0".to_string()));
+                }
+                Self::clahe(image, tile_size as usize, clip_limit);
+            }
+        }
+
+        Ok(palette)
+    }
+
+    /// Floyd-Steinberg dithering: map each pixel to its nearest palette color
+    /// and diffuse the quantization error onto unvisited neighbours
+    fn dither(image: &mut PhotonImage, palette: &[[u8; 3]]) {
+        let width = image.get_width() as usize;
+        let height = image.get_height() as usize;
+        let mut pixels = image.get_raw_pixels();
+        let mut values: Vec<[f32; 3]> = pixels.chunks_exact(4)
+            .map(|p| [p[0] as f32, p[1] as f32, p[2] as f32])
+            .collect();
+
+        for y in 0..height {
+            for x in 0..width {
+                let index = y * width + x;
+                let old = values[index].map(|v| v.clamp(0.0, 255.0));
+                let new = palette.iter()
+                    .min_by(|a, b| {
+                        let distance = |color: &[u8; 3]| {
+                            (0..3).map(|c| (color[c] as f32 - old[c]).powi(2)).sum::<f32>()
+                        };
+                        distance(a).total_cmp(&distance(b))
+                    })
+                    .copied()
+                    .unwrap_or([0, 0, 0]);
+                pixels[index * 4..index * 4 + 3].copy_from_slice(&new);
+
+                let error = [0, 1, 2].map(|c| old[c] - new[c] as f32);
+                let mut spread = |dx: isize, dy: usize, weight: f32| {
+                    let nx = x as isize + dx;
+                    if nx >= 0 && (nx as usize) < width && y + dy < height {
+                        let neighbour = &mut values[(y + dy) * width + nx as usize];
+                        for c in 0..3 {
+                            neighbour[c] += error[c] * weight;
+                        }
+                    }
+                };
+                spread(1, 0, 7.0 / 16.0);
+                spread(-1, 1, 3.0 / 16.0);
+                spread(0, 1, 5.0 / 16.0);
+                spread(1, 1, 1.0 / 16.0);
+            }
+        }
+
+        *image = PhotonImage::new(pixels, width as u32, height as u32);
+    }
+
+    /// Reduce the image to at most `palette_size` colors using median cut,
+    /// returning the palette. Alpha is left untouched.
+    fn quantize(image: &mut PhotonImage, palette_size: usize) -> Vec<[u8; 3]> {
+        let mut pixels = image.get_raw_pixels();
+        let palette = median_cut(rgb_colors(&pixels), palette_size);
+
+        for pixel in pixels.chunks_exact_mut(4) {
+            let nearest = palette.iter()
+                .min_by_key(|color| {
+                    (0..3).map(|c| (color[c] as i32 - pixel[c] as i32).pow(2)).sum::<i32>()
+                })
+                .copied()
+                .unwrap_or([0, 0, 0]);
+            pixel[..3].copy_from_slice(&nearest);
+        }
+
+        *image = PhotonImage::new(pixels, image.get_width(), image.get_height());
+        palette
+    }
+
+    /// Reduce each color channel to `levels` evenly spaced values spanning 0-255
+    fn posterize(image: &mut PhotonImage, levels: u32) {
+        let mut pixels = image.get_raw_pixels();
+        for pixel in pixels.chunks_exact_mut(4) {
+            for channel in &mut pixel[..3] {
+                let step = *channel as u32 * levels / 256;
+                *channel = (step * 255 / (levels - 1)) as u8;
+            }
+        }
+        *image = PhotonImage::new(pixels, image.get_width(), image.get_height());
+    }
+
+    /// Binarize on luma or a single channel against `intensity * 255`
+    fn threshold(image: &mut PhotonImage, options: &ImageProcessingOptions) -> Result<(), ImageError> {
+        let cutoff = (options.intensity.unwrap_or(0.5) * 255.0) as u32 as f32;
+        let channel = match options.threshold_channel.as_deref().unwrap_or("luma") {
+            "luma" => None,
+            "r" => Some(0),
+            "g" => Some(1),
+            "b" => Some(2),
+            other => {
+                return Err(ImageError::InvalidParameter(format!(
+                    "Unknown threshold channel '{}': expected luma, r, g or b",
+                    other
+                )))
+            }
+        };
+        let (above, below) = if options.threshold_invert.unwrap_or(false) { (0, 255) } else { (255, 0) };
+
+        let mut pixels = image.get_raw_pixels();
+        for pixel in pixels.chunks_exact_mut(4) {
+            let value = match channel {
+                // Same weights as photon's threshold so existing output is unchanged
+                None => 0.2126 * pixel[0] as f32 + 0.7152 * pixel[1] as f32 + 0.072 * pixel[2] as f32,
+                Some(index) => pixel[index] as f32,
+            };
+            pixel[..3].fill(if value >= cutoff { above } else { below });
+        }
+        *image = PhotonImage::new(pixels, image.get_width(), image.get_height());
+        Ok(())
+    }
+
+    /// Gradient magnitude `sqrt(gx² + gy²)` of the luminance, scaled so the
+    /// strongest edge is 255; alpha is preserved
+    fn sobel_magnitude(image: &mut PhotonImage) {
+        let width = image.get_width() as usize;
+        let height = image.get_height() as usize;
+        let mut pixels = image.get_raw_pixels();
+        let luma: Vec<f32> = pixels.chunks_exact(4)
+            .map(|p| 0.299 * p[0] as f32 + 0.587 * p[1] as f32 + 0.114 * p[2] as f32)
+            .collect();
+        // Clamp to the border so edges of the image are not reported as gradients
+        let at = |x: usize, y: usize, dx: isize, dy: isize| {
+            let x = (x as isize + dx).clamp(0, width as isize - 1) as usize;
+            let y = (y as isize + dy).clamp(0, height as isize - 1) as usize;
+            luma[y * width + x]
+        };
+
+        let mut magnitudes = Vec::with_capacity(luma.len());
+        for y in 0..height {
+            for x in 0..width {
+                let gx = at(x, y, 1, -1) + 2.0 * at(x, y, 1, 0) + at(x, y, 1, 1)
+                    - at(x, y, -1, -1) - 2.0 * at(x, y, -1, 0) - at(x, y, -1, 1);
+                let gy = at(x, y, -1, 1) + 2.0 * at(x, y, 0, 1) + at(x, y, 1, 1)
+                    - at(x, y, -1, -1) - 2.0 * at(x, y, 0, -1) - at(x, y, 1, -1);
+                magnitudes.push((gx * gx + gy * gy).sqrt());
+            }
+        }
+
+        let max = magnitudes.iter().copied().fold(0.0f32, f32::max);
+        let scale = if max > 0.0 { 255.0 / max } else { 0.0 };
+        for (pixel, magnitude) in pixels.chunks_exact_mut(4).zip(magnitudes) {
+            let value = (magnitude * scale).round() as u8;
+            pixel[..3].fill(value);
+        }
+        *image = PhotonImage::new(pixels, width as u32, height as u32);
+    }
+
+    /// Contrast Limited Adaptive Histogram Equalization on luma
+    ///
+    /// Each tile gets its own equalization curve from a histogram clipped at
+    /// `clip_limit` times the mean bin count, with the clipped excess spread
+    /// over all bins so noise in flat areas is not amplified. Pixels blend the
+    /// curves of the four nearest tile centres to avoid seams, and the luma
+    /// change is added to every channel so chroma is preserved.
+    fn clahe(image: &mut PhotonImage, tile_size: usize, clip_limit: f32) {
+        let width = image.get_width() as usize;
+        let height = image.get_height() as usize;
+        let mut pixels = image.get_raw_pixels();
+        let luma: Vec<u8> = pixels.chunks_exact(4)
+            .map(|p| (0.299 * p[0] as f32 + 0.587 * p[1] as f32 + 0.114 * p[2] as f32).round() as u8)
+            .collect();
+
+        let (tiles_x, tiles_y) = (width.div_ceil(tile_size), height.div_ceil(tile_size));
+        let mut curves = vec![[0u8; 256]; tiles_x * tiles_y];
+        for tile_y in 0..tiles_y {
+            for tile_x in 0..tiles_x {
+                let (x0, y0) = (tile_x * tile_size, tile_y * tile_size);
+                let (x1, y1) = ((x0 + tile_size).min(width), (y0 + tile_size).min(height));
+                let mut histogram = [0u32; 256];
+                for y in y0..y1 {
+                    for &value in &luma[y * width + x0..y * width + x1] {
+                        histogram[value as usize] += 1;
+                    }
+                }
+
+                let total = ((x1 - x0) * (y1 - y0)) as u32;
+                let limit = ((clip_limit * total as f32 / 256.0) as u32).max(1);
+                let excess: u32 = histogram.iter().map(|&count| count.saturating_sub(limit)).sum();
+                for (level, count) in histogram.iter_mut().enumerate() {
+                    *count = (*count).min(limit) + excess / 256 + u32::from((level as u32) < excess % 256);
+                }
+
+                let mut cumulative = 0u32;
+                for (level, count) in histogram.iter().enumerate() {
+                    cumulative += count;
+                    curves[tile_y * tiles_x + tile_x][level] = (cumulative as u64 * 255 / total as u64) as u8;
+                }
+            }
+        }
+
+        // Neighbouring tile indices and the weight of the second, for a pixel coordinate
+        let neighbours = |position: usize, tiles: usize| {
+            let centre = (position as f32 + 0.5) / tile_size as f32 - 0.5;
+            let first = (centre.floor().max(0.0) as usize).min(tiles - 1);
+            let second = (first + 1).min(tiles - 1);
+            (first, second, (centre - first as f32).clamp(0.0, 1.0))
+        };
+        for y in 0..height {
+            let (top, bottom, wy) = neighbours(y, tiles_y);
+            for x in 0..width {
+                let (left, right, wx) = neighbours(x, tiles_x);
+                let value = luma[y * width + x] as usize;
+                let curve = |tile_x: usize, tile_y: usize| curves[tile_y * tiles_x + tile_x][value] as f32;
+                let upper = curve(left, top) * (1.0 - wx) + curve(right, top) * wx;
+                let lower = curve(left, bottom) * (1.0 - wx) + curve(right, bottom) * wx;
+                let delta = upper * (1.0 - wy) + lower * wy - value as f32;
+
+                let offset = (y * width + x) * 4;
+                for channel in &mut pixels[offset..offset + 3] {
+                    *channel = (*channel as f32 + delta).round().clamp(0.0, 255.0) as u8;
+                }
+            }
+        }
+        *image = PhotonImage::new(pixels, width as u32, height as u32);
+    }
+
+    /// Oil-painting effect: bucket each pixel's neighbourhood by intensity and
+    /// paint it with the average color of the most common bucket
+    fn oil_paint(image: &mut PhotonImage, radius: u32, levels: usize) {
+        let width = image.get_width() as usize;
+        let height = image.get_height() as usize;
+        let source = image.get_raw_pixels();
+        let mut pixels = source.clone();
+
+        let buckets: Vec<usize> = source.chunks_exact(4)
+            .map(|p| (p[0] as usize + p[1] as usize + p[2] as usize) * levels / (3 * 256))
+            .collect();
+
+        let radius = radius as usize;
+        let mut counts = vec![0u32; levels];
+        let mut sums = vec![[0u32; 3]; levels];
+        for y in 0..height {
+            for x in 0..width {
+                counts.fill(0);
+                sums.fill([0; 3]);
+                for ny in y.saturating_sub(radius)..(y + radius + 1).min(height) {
+                    for nx in x.saturating_sub(radius)..(x + radius + 1).min(width) {
+                        let index = ny * width + nx;
+                        let bucket = buckets[index];
+                        counts[bucket] += 1;
+                        for channel in 0..3 {
+                            sums[bucket][channel] += source[index * 4 + channel] as u32;
+                        }
+                    }
+                }
+
+                let (bucket, &count) = counts.iter().enumerate()
+                    .max_by_key(|&(_, count)| count)
+                    .unwrap_or((0, &1));
+                let offset = (y * width + x) * 4;
+                for channel in 0..3 {
+                    pixels[offset + channel] = (sums[bucket][channel] / count.max(1)) as u8;
+                }
+            }
+        }
+
+        *image = PhotonImage::new(pixels, width as u32, height as u32);
+    }
+
+    /// Replace each block inside the region with the block's average color
+    fn pixelate_region(image: &mut PhotonImage, region: (u32, u32, u32, u32), block_size: u32) -> Result<(), ImageError> {
+        let width = image.get_width();
+        let height = image.get_height();
+        let (x0, y0, region_width, region_height) = region;
+
+        if x0 >= width || y0 >= height {
+            return Err(ImageError::InvalidParameter(format!(
+                "Pixelate region origin ({}, {}) is outside the {}x{} image",
+                x0, y0, width, height
+            )));
+        }
+        let x1 = x0.saturating_add(region_width).min(width);
+        let y1 = y0.saturating_add(region_height).min(height);
+
+        let mut pixels = image.get_raw_pixels();
+        for block_y in (y0..y1).step_by(block_size as usize) {
+            for block_x in (x0..x1).step_by(block_size as usize) {
+                let block_x1 = (block_x + block_size).min(x1);
+                let block_y1 = (block_y + block_size).min(y1);
+
+                let mut sums = [0u64; 4];
+                for y in block_y..block_y1 {
+                    for x in block_x..block_x1 {
+                        let idx = ((y * width + x) * 4) as usize;
+                        for (channel, sum) in sums.iter_mut().enumerate() {
+                            *sum += pixels[idx + channel] as u64;
+                        }
+                    }
+                }
+
+                let count = ((block_x1 - block_x) * (block_y1 - block_y)) as u64;
+                let average = sums.map(|sum| (sum / count) as u8);
+                for y in block_y..block_y1 {
+                    for x in block_x..block_x1 {
+                        let idx = ((y * width + x) * 4) as usize;
+                        pixels[idx..idx + 4].copy_from_slice(&average);
+                    }
+                }
+            }
+        }
+
+        *image = PhotonImage::new(pixels, width, height);
+        Ok(())
+    }
+
+    /// Apply a flattened 3x3 or 5x5 kernel to the RGB channels, clamping at the edges
+    fn convolve(image: &mut PhotonImage, kernel: &[f32], divisor: Option<f32>) -> Result<(), ImageError> {
+        let size: i64 = match kernel.len() {
+            9 => 3,
+            25 => 5,
+            len => return Err(ImageError::InvalidParameter(format!(
+                "Kernel must contain 9 (3x3) or 25 (5x5) values, got {}",
+                len
+            ))),
+        };
+        let divisor = divisor.unwrap_or(1.0);
+        if divisor == 0.0 {
+            return Err(ImageError::InvalidParameter("Kernel divisor must not be zero".to_string()));
+        }
+
+        let width = image.get_width() as i64;
+        let height = image.get_height() as i64;
+        let source = image.get_raw_pixels();
+        let mut output = source.clone();
+        let radius = size / 2;
+
+        for y in 0..height {
+            for x in 0..width {
+                let mut sums = [0f32; 3];
+                for ky in 0..size {
+                    for kx in 0..size {
+                        let sx = (x + kx - radius).clamp(0, width - 1);
+                        let sy = (y + ky - radius).clamp(0, height - 1);
+                        let idx = ((sy * width + sx) * 4) as usize;
+                        let weight = kernel[(ky * size + kx) as usize];
+                        for (channel, sum) in sums.iter_mut().enumerate() {
+                            *sum += source[idx + channel] as f32 * weight;
+                        }
+                    }
+                }
+
+                let idx = ((y * width + x) * 4) as usize;
+                for (channel, sum) in sums.iter().enumerate() {
+                    output[idx + channel] = (sum / divisor).round().clamp(0.0, 255.0) as u8;
+                }
+            }
+        }
+
+        *image = PhotonImage::new(output, width as u32, height as u32);
+        Ok(())
+    }
+
+    /// Add gaussian grain or salt-and-pepper speckles to the RGB channels
+    fn add_noise(image: &mut PhotonImage, noise_type: &str, amount: Option<f32>, rng: &mut StdRng) -> Result<(), ImageError> {
+        // clamp keeps NaN, which would panic in gen_bool
+        if amount.is_some_and(|amount| !amount.is_finite()) {
+            return Err(ImageError::InvalidParameter("Noise amount must be a finite number".to_string()));
+        }
+        let mut pixels = image.get_raw_pixels();
+
+        match noise_type {
+            "gaussian" => {
+                // amount is the standard deviation as a fraction of the full 0-255 range
+                let std_dev = amount.unwrap_or(0.1).clamp(0.0, 1.0) * 255.0;
+                for pixel in pixels.chunks_exact_mut(4) {
+                    for value in pixel.iter_mut().take(3) {
+                        // Box-Muller transform
+                        let u1: f32 = rng.gen_range(f32::EPSILON..1.0);
+                        let u2: f32 = rng.gen();
+                        let normal = (-2.0 * u1.ln()).sqrt() * (2.0 * std::f32::consts::PI * u2).cos();
+                        *value = (*value as f32 + normal * std_dev).round().clamp(0.0, 255.0) as u8;
+                    }
+                }
+            }
+            "salt_pepper" => {
+                // amount is the fraction of pixels replaced with pure black or white
+                let probability = amount.unwrap_or(0.05).clamp(0.0, 1.0) as f64;
+                for pixel in pixels.chunks_exact_mut(4) {
+                    if rng.gen_bool(probability) {
+                        let value = if rng.gen_bool(0.5) { 255 } else { 0 };
+                        pixel[..3].fill(value);
+                    }
+                }
+            }
+            _ => return Err(ImageError::InvalidParameter(format!("Unknown noise type: {}", noise_type))),
+        }
+
+        *image = PhotonImage::new(pixels, image.get_width(), image.get_height());
+        Ok(())
+    }
+
+    /// Create a `width`x`height` image filled with `color`, encoded as `format`
+    pub fn create_solid_image(width: u32, height: u32, color: &str, format: &str) -> ImageProcessingResult {
+        let output = Self::solid_image(width, height, color).and_then(|image| {
+            let image_bytes = Self::photon_image_to_bytes(&image, format, None)?;
+            let metadata = ImageMetadata::new(width, height, format.to_string(), image_bytes.len());
+            Ok((image_bytes, metadata))
+        });
+        Self::build_result(output, Vec::new(), &ImageProcessingOptions::default())
+    }
+
+    /// Apply the requested operation to every frame of an animated GIF,
+    /// re-encoding it as a looping GIF with the original frame delays
+    ///
+    /// `max_pixels` bounds the pixels of all frames combined. Output is always
+    /// GIF, so an `output_format` other than `gif` is rejected, and encoder
+    /// settings that do not apply to GIF are reported as warnings.
+    pub fn process_gif_frames(base64_input: &str, options: &ImageProcessingOptions) -> ImageProcessingResult {
+        let mut warnings = Vec::new();
+        if let Err(unused) = validate_options(options) {
+            warnings.push(unused);
+        }
+        let output = Self::check_input_size(Self::decoded_len(base64_input), options)
+            .and_then(|_| Self::base64_to_bytes_with_variant(base64_input, options.base64_variant.as_deref()))
+            .and_then(|gif_bytes| Self::render_gif_frames(&gif_bytes, options, &mut warnings));
+        Self::build_result(output, warnings, options)
+    }
+
+    fn render_gif_frames(
+        gif_bytes: &[u8],
+        options: &ImageProcessingOptions,
+        warnings: &mut Vec<String>,
+    ) -> Result<(Vec<u8>, ImageMetadata), ImageError> {
+        use image::codecs::gif::{GifDecoder, GifEncoder, Repeat};
+        use image::{AnimationDecoder, Frame};
+
+        if let Some(format) = options.output_format.as_deref().filter(|format| *format != "gif") {
+            return Err(ImageError::UnsupportedFormat(format!("{} (animated GIF output is always gif)", format)));
+        }
+        let ignored: Vec<&str> = [
+            ("quality", options.quality.is_some()),
+            ("max_bytes", options.max_bytes.is_some()),
+            ("metadata_only", options.metadata_only.is_some()),
+        ]
+        .iter()
+        .filter(|(_, set)| *set)
+        .map(|(name, _)| *name)
+        .collect();
+        if !ignored.is_empty() {
+            warnings.push(format!("Options ignored for animated GIF output: {}", ignored.join(", ")));
+        }
+
+        let decoder = GifDecoder::new(Cursor::new(gif_bytes)).map_err(|e| ImageError::LoadImage(e.to_string()))?;
+        let (width, height) = decoder.dimensions();
+        let max_pixels = options.max_pixels_or_default();
+
+        // Frames arrive composited onto the full canvas, so each is width x height
+        let mut frames = Vec::new();
+        let mut total_pixels = 0u64;
+        for frame in decoder.into_frames() {
+            let frame = frame.map_err(|e| ImageError::LoadImage(e.to_string()))?;
+            total_pixels += width as u64 * height as u64;
+            if total_pixels > max_pixels {
+                return Err(ImageError::TooLarge { width, height, max_pixels });
+            }
+
+            let delay = frame.delay();
+            let buffer = frame.into_buffer();
+            let (frame_width, frame_height) = buffer.dimensions();
+            let mut image = PhotonImage::new(buffer.into_raw(), frame_width, frame_height);
+            // Every frame hits the same clamps, so report each warning once
+            let mut frame_warnings = Vec::new();
+            Self::apply_operation(&mut image, options, &mut frame_warnings)?;
+            for warning in frame_warnings {
+                if !warnings.contains(&warning) {
+                    warnings.push(warning);
+                }
+            }
+
+            let buffer = image::RgbaImage::from_raw(image.get_width(), image.get_height(), image.get_raw_pixels())
+                .ok_or_else(|| ImageError::EncodeFailed("Failed to create GIF frame".to_string()))?;
+            frames.push(Frame::from_parts(buffer, 0, 0, delay));
+        }
+        let (width, height) = frames.first()
+            .map(|frame| frame.buffer().dimensions())
+            .ok_or_else(|| ImageError::LoadImage("GIF has no frames".to_string()))?;
+
+        let mut gif = Vec::new();
+        {
+            let mut encoder = GifEncoder::new(&mut gif);
+            encoder.set_repeat(Repeat::Infinite)
+                .and_then(|_| encoder.encode_frames(frames))
+                .map_err(|e| ImageError::EncodeFailed(format!("GIF: {}", e)))?;
+        }
+
+        let metadata = ImageMetadata::new(width, height, "gif".to_string(), gif.len());
+        Ok((gif, metadata))
+    }
+
+    /// Lay images out left-to-right, top-to-bottom in a grid of `cols` columns
+    ///
+    /// Each image is scaled into its `cell_width`x`cell_height` cell, keeping
+    /// its aspect ratio and centering it unless `keep_aspect_ratio` is false.
+    /// Uncovered space, including the empty cells of a final partial row, is
+    /// transparent, and fewer images than `cols` narrow the grid to fit them.
+    /// The output is encoded according to `options`.
+    pub fn compose_grid(
+        images: Vec<String>,
+        cols: u32,
+        cell_width: u32,
+        cell_height: u32,
+        options: &ImageProcessingOptions,
+    ) -> ImageProcessingResult {
+        let output = Self::render_grid(&images, cols, cell_width, cell_height, options);
+        Self::build_result(output, Vec::new(), options)
+    }
+
+    fn render_grid(
+        images: &[String],
+        cols: u32,
+        cell_width: u32,
+        cell_height: u32,
+        options: &ImageProcessingOptions,
+    ) -> Result<(Vec<u8>, ImageMetadata), ImageError> {
+        if images.is_empty() || cols == 0 || cell_width == 0 || cell_height == 0 {
+            return Err(ImageError::InvalidParameter(
+                "compose_grid needs at least one image and non-zero cols and cell size".to_string(),
+            ));
+        }
+        let cols = cols.min(images.len() as u32);
+        let rows = (images.len() as u32).div_ceil(cols);
+        let (width, height) = (cols.saturating_mul(cell_width), rows.saturating_mul(cell_height));
+        let max_pixels = options.max_pixels_or_default();
+        if width as u64 * height as u64 > max_pixels {
+            return Err(ImageError::TooLarge { width, height, max_pixels });
+        }
+
+        let keep_aspect = options.keep_aspect_ratio.unwrap_or(true);
+        let mut canvas = vec![0u8; width as usize * height as usize * 4];
+        for (index, image_data) in images.iter().enumerate() {
+            Self::check_input_size(Self::decoded_len(image_data), options)?;
+            let image_bytes = Self::base64_to_bytes_with_variant(image_data, options.base64_variant.as_deref())?;
+            let image = Self::decode_input(&image_bytes, options)?;
+
+            let (fit_width, fit_height) = if keep_aspect {
+                let scale = (cell_width as f32 / image.get_width() as f32)
+                    .min(cell_height as f32 / image.get_height() as f32);
+                (
+                    ((image.get_width() as f32 * scale).round() as u32).clamp(1, cell_width),
+                    ((image.get_height() as f32 * scale).round() as u32).clamp(1, cell_height),
+                )
+            } else {
+                (cell_width, cell_height)
+            };
+            let cell = photon_rs::transform::resize(&image, fit_width, fit_height, options.resize_filter_or_default()?);
+
+            let (col, row) = (index as u32 % cols, index as u32 / cols);
+            let left = (col * cell_width + (cell_width - fit_width) / 2) as usize;
+            let top = (row * cell_height + (cell_height - fit_height) / 2) as usize;
+            let pixels = cell.get_raw_pixels();
+            for (y, line) in pixels.chunks_exact(fit_width as usize * 4).enumerate() {
+                let start = ((top + y) * width as usize + left) * 4;
+                canvas[start..start + line.len()].copy_from_slice(line);
+            }
+        }
+
+        let grid = PhotonImage::new(canvas, width, height);
+        let output_format = options.output_format_or_default();
+        let (image_bytes, quality) = Self::encode_output(&grid, output_format, None, options)?;
+        let metadata = ImageMetadata {
+            quality,
+            ..ImageMetadata::new(width, height, output_format.to_string(), image_bytes.len())
+        };
+        Ok((image_bytes, metadata))
+    }
+
+    fn solid_image(width: u32, height: u32, color: &str) -> Result<PhotonImage, ImageError> {
+        if width == 0 || height == 0 {
+            return Err(ImageError::InvalidParameter("Image dimensions must be greater than 0".to_string()));
+        }
+        let max_pixels = ProcessorConfig::global().max_pixels;
+        if width as u64 * height as u64 > max_pixels {
+            return Err(ImageError::TooLarge { width, height, max_pixels });
+        }
+        let rgba = parse_color(color)?;
+        Ok(PhotonImage::new(rgba.repeat(width as usize * height as usize), width, height))
+    }
+
+    /// Compute a 64-bit difference hash (dHash) of an image as 16 hex digits
+    ///
+    /// The image is reduced to a 9x8 grayscale thumbnail and each bit records
+    /// whether a pixel is brighter than its right-hand neighbour, so resized or
+    /// re-encoded copies of an image hash to nearby values.
+    pub fn perceptual_hash(base64_input: &str) -> Result<String, ImageError> {
+        let image = Self::base64_to_photon_image(base64_input)?;
+        let (width, height) = (image.get_width(), image.get_height());
+        let rgba = image::RgbaImage::from_raw(width, height, image.get_raw_pixels())
+            .ok_or_else(|| ImageError::LoadImage("Invalid pixel buffer".to_string()))?;
+        let thumbnail = image::imageops::resize(
+            &DynamicImage::ImageRgba8(rgba).to_luma8(),
+            9,
+            8,
+            image::imageops::FilterType::Triangle,
+        );
+
+        let mut hash = 0u64;
+        for y in 0..8 {
+            for x in 0..8 {
+                let left = thumbnail.get_pixel(x, y)[0];
+                let right = thumbnail.get_pixel(x + 1, y)[0];
+                hash = (hash << 1) | u64::from(left > right);
+            }
+        }
+        Ok(format!("{:016x}", hash))
+    }
+
+    /// Number of differing bits between two hashes from `perceptual_hash`
+    pub fn hamming_distance(a: &str, b: &str) -> Result<u32, ImageError> {
+        let parse = |hash: &str| {
+            u64::from_str_radix(hash, 16)
+                .map_err(|_| ImageError::InvalidParameter(format!("Invalid perceptual hash '{}'", hash)))
+        };
+        Ok((parse(a)? ^ parse(b)?).count_ones())
+    }
+
+    /// Number of distinct RGBA colors in an image
+    ///
+    /// Images above a megapixel are first downsampled (nearest-neighbour, so
+    /// every counted color really occurs), which makes the count for large
+    /// images an approximation that can miss rare colors.
+    pub fn count_unique_colors(base64_input: &str) -> Result<u64, ImageError> {
+        let mut image = Self::base64_to_photon_image(base64_input)?;
+        let pixels = image.get_width() as u64 * image.get_height() as u64;
+        if pixels > COLOR_COUNT_MAX_PIXELS {
+            let scale = (COLOR_COUNT_MAX_PIXELS as f64 / pixels as f64).sqrt();
+            let width = ((image.get_width() as f64 * scale) as u32).max(1);
+            let height = ((image.get_height() as f64 * scale) as u32).max(1);
+            image = photon_rs::transform::resize(&image, width, height, photon_rs::transform::SamplingFilter::Nearest);
+        }
+
+        let colors: std::collections::HashSet<[u8; 4]> = image.get_raw_pixels()
+            .chunks_exact(4)
+            .map(|pixel| [pixel[0], pixel[1], pixel[2], pixel[3]])
+            .collect();
+        Ok(colors.len() as u64)
+    }
+
+    /// Process multiple images in batch
+    #[cfg_attr(feature = "tracing", tracing::instrument(
+        skip_all,
+        fields(operation = options.operation.as_str(), images = images.len()),
+    ))]
+    pub fn process_batch(images: Vec<String>, options: &ImageProcessingOptions) -> BatchProcessingResult {
+        let fail_fast = options.fail_fast.unwrap_or(false);
+        let results = Self::map_batch(&images, fail_fast, |image_data| Self::process_image(image_data, options));
+        Self::summarize_batch(results)
+    }
+
+    /// Process multiple images sequentially, reporting `(index, total, success)` after each one
+    pub fn process_batch_with_progress<F>(images: Vec<String>, options: &ImageProcessingOptions, on_progress: F) -> BatchProcessingResult
+    where
+        F: FnMut(usize, usize, bool),
+    {
+        Self::process_batch_cancelable(images, options, &AtomicBool::new(false), on_progress)
+    }
+
+    /// Process multiple images sequentially, stopping once `cancel` is set
+    ///
+    /// Cancellation is checked at image boundaries, not mid-image: an image that
+    /// is already being processed runs to completion. A cancelled batch returns
+    /// the results processed so far with `cancelled` set.
+    pub fn process_batch_cancelable<F>(
+        images: Vec<String>,
+        options: &ImageProcessingOptions,
+        cancel: &AtomicBool,
+        mut on_progress: F,
+    ) -> BatchProcessingResult
+    where
+        F: FnMut(usize, usize, bool),
+    {
+        let total = images.len();
+        let fail_fast = options.fail_fast.unwrap_or(false);
+        let mut results = Vec::with_capacity(total);
+        for (index, image_data) in images.iter().enumerate() {
+            if cancel.load(Ordering::Relaxed) {
+                return BatchProcessingResult { cancelled: true, ..Self::summarize_batch(results) };
+            }
+            let result = Self::catch_panic(|| Self::process_image(image_data, options)).with_index(index);
+            on_progress(index, total, result.success);
+            let stop = fail_fast && !result.success;
+            results.push(result);
+            if stop {
+                break;
+            }
+        }
+        Self::summarize_batch(results)
+    }
+
+    /// Process multiple images in batch, each with its own options
+    ///
+    /// The batch stops early if any option set enables `fail_fast`.
+    pub fn process_batch_with_options(images: Vec<String>, options: Vec<ImageProcessingOptions>) -> Result<BatchProcessingResult, ImageError> {
+        if images.len() != options.len() {
+            return Err(ImageError::InvalidParameter(format!(
+                "Got {} images but {} option sets",
+                images.len(),
+                options.len()
+            )));
+        }
+
+        let fail_fast = options.iter().any(|options| options.fail_fast.unwrap_or(false));
+        let items: Vec<(&String, &ImageProcessingOptions)> = images.iter().zip(options.iter()).collect();
+        let results = Self::map_batch(&items, fail_fast, |(image_data, options)| Self::process_image(image_data, options));
+        Ok(Self::summarize_batch(results))
+    }
+
+    /// Run `process` over every item, in parallel on native builds with the rayon feature
+    ///
+    /// A panic while processing one item is turned into a failed result for that
+    /// item so the rest of the batch still completes. With `fail_fast` the items
+    /// run sequentially and the first failure ends the batch.
+    fn map_batch<T, F>(items: &[T], fail_fast: bool, process: F) -> Vec<ImageProcessingResult>
+    where
+        T: Sync,
+        F: Fn(&T) -> ImageProcessingResult + Sync + Send,
+    {
+        let process = |(index, item): (usize, &T)| Self::catch_panic(|| process(item)).with_index(index);
+
+        if fail_fast {
+            let mut results = Vec::with_capacity(items.len());
+            for item in items.iter().enumerate() {
+                let result = process(item);
+                let failed = !result.success;
+                results.push(result);
+                if failed {
+                    break;
+                }
+            }
+            return results;
+        }
+
+        // collect() keeps input order on both paths
+        #[cfg(all(feature = "rayon", not(target_arch = "wasm32")))]
+        {
+            use rayon::prelude::*;
+            items.par_iter().enumerate().map(process).collect()
+        }
+
+        #[cfg(not(all(feature = "rayon", not(target_arch = "wasm32"))))]
+        {
+            items.iter().enumerate().map(process).collect()
+        }
+    }
+
+    /// Turn a panic inside `process` into a failed result
+    fn catch_panic<F: FnOnce() -> ImageProcessingResult>(process: F) -> ImageProcessingResult {
+        std::panic::catch_unwind(std::panic::AssertUnwindSafe(process))
+            .unwrap_or_else(|_| ImageProcessingResult::failure(ImageError::Panic))
+    }
+
+    /// Tally per-image results into a batch result
+    fn summarize_batch(results: Vec<ImageProcessingResult>) -> BatchProcessingResult {
+        let successful = results.iter().filter(|result| result.success).count();
+        let failed = results.len() - successful;
+
+        // Zero unless the images were processed with include_timing
+        let total_time_ms = results.iter()
+            .filter_map(|result| result.metadata.as_ref())
+            .map(|metadata| metadata.processing_time_ms)
+            .sum();
+
+        BatchProcessingResult {
+            processed: results.len(),
+            successful,
+            failed,
+            results,
+            total_time_ms,
+            cancelled: false,
+        }
+    }
+}
+
+impl Default for ImageProcessingOptions {
+    fn default() -> Self {
+        Self {
+            operation: Operation::Filter,
+            filter: None,
+            filters: None,
+            intensity: None,
+            brightness: None,
+            contrast: None,
+            saturation: None,
+            hue_rotation: None,
+            resize_width: None,
+            resize_height: None,
+            keep_aspect_ratio: None,
+            crop_x: None,
+            crop_y: None,
+            crop_width: None,
+            crop_height: None,
+            crop_x_pct: None,
+            crop_y_pct: None,
+            crop_width_pct: None,
+            crop_height_pct: None,
+            rotation_angle: None,
+            flip_horizontal: None,
+            flip_vertical: None,
+            output_format: None,
+            quality: None,
+            output_as_binary: None,
+            block_size: None,
+            kernel: None,
+            kernel_divisor: None,
+            noise_amount: None,
+            noise_type: None,
+            seed: None,
+            max_pixels: None,
+            oil_radius: None,
+            oil_levels: None,
+            tint_color: None,
+            tint_strength: None,
+            channel_order: None,
+            blend_base64: None,
+            blend_mode: None,
+            blend_opacity: None,
+            palette_size: None,
+            opacity: None,
+            max_bytes: None,
+            transpose: None,
+            output_encoding: None,
+            auto_levels: None,
+            clip_percent: None,
+            temperature: None,
+            tint: None,
+            trim_tolerance: None,
+            metadata_only: None,
+            strip_metadata: None,
+            threshold_channel: None,
+            threshold_invert: None,
+            levels: None,
+            svg_width: None,
+            svg_height: None,
+            target_aspect: None,
+            grayscale_mode: None,
+            fail_fast: None,
+            no_upscale: None,
+            base64_variant: None,
+            jpeg_progressive: None,
+            jpeg_subsampling: None,
+            resize_filter: None,
+            key_color: None,
+            key_tolerance: None,
+            luma_threshold: None,
+            luma_above: None,
+            canvas_width: None,
+            canvas_height: None,
+            anchor: None,
+            canvas_color: None,
+            clahe_tile_size: None,
+            clahe_clip_limit: None,
+            linear_light: None,
+            regions: None,
+            include_timing: None,
+            bit_depth: None,
+            max_input_bytes: None,
+        }
+    }
+}
+
+/// Generates fluent setters that wrap the value in `Some`
+macro_rules! builder_setters {
+    ($($field:ident: $ty:ty),* $(,)?) => {
+        $(
+            pub fn $field(mut self, value: $ty) -> Self {
+                self.options.$field = Some(value.into());
+                self
+            }
+        )*
+    };
+}
+
+/// Fluent builder for [`ImageProcessingOptions`]
+///
+/// ```
+/// use n8n_rust_core::{ImageProcessingOptions, Operation};
+///
+/// let options = ImageProcessingOptions::builder()
+///     .operation(Operation::Transform)
+///     .resize(800, 600)
+///     .filter("grayscale")
+///     .output_format("webp")
+///     .quality(80)
+///     .build();
+///
+/// assert_eq!(options.resize_width, Some(800));
+/// assert_eq!(options.resize_height, Some(600));
+/// assert_eq!(options.filter.as_deref(), Some("grayscale"));
+/// assert_eq!(options.output_format.as_deref(), Some("webp"));
+/// ```
+#[derive(Debug, Default)]
+pub struct ImageProcessingOptionsBuilder {
+    options: ImageProcessingOptions,
+}
+
+impl ImageProcessingOptions {
+    /// Start building options from the defaults
+    pub fn builder() -> ImageProcessingOptionsBuilder {
+        ImageProcessingOptionsBuilder::default()
+    }
+
+    /// Copy of these options with unset defaults filled from `config`
+    pub fn with_defaults(&self, config: &ProcessorConfig) -> Self {
+        Self {
+            output_format: self.output_format.clone().or_else(|| Some(config.default_format.clone())),
+            quality: self.quality.or(Some(config.default_quality)),
+            resize_filter: self.resize_filter.clone().or_else(|| Some(config.default_resize_filter.clone())),
+            max_pixels: self.max_pixels.or(Some(config.max_pixels)),
+            ..self.clone()
+        }
+    }
+
+    fn output_format_or_default(&self) -> &str {
+        self.output_format.as_deref().unwrap_or(&ProcessorConfig::global().default_format)
+    }
+
+    fn quality_or_default(&self) -> u8 {
+        self.quality.unwrap_or(ProcessorConfig::global().default_quality)
+    }
+
+    fn max_pixels_or_default(&self) -> u64 {
+        self.max_pixels.unwrap_or(ProcessorConfig::global().max_pixels)
+    }
+
+    fn resize_filter_or_default(&self) -> Result<photon_rs::transform::SamplingFilter, ImageError> {
+        parse_resize_filter(self.resize_filter.as_deref().unwrap_or(&ProcessorConfig::global().default_resize_filter))
+    }
+
+    /// Crop rectangle in pixels from either the absolute or the percentage fields
+    fn crop_rect(&self, width: u32, height: u32) -> Result<Option<(u32, u32, u32, u32)>, ImageError> {
+        let percentages = [self.crop_x_pct, self.crop_y_pct, self.crop_width_pct, self.crop_height_pct];
+        if percentages.iter().all(Option::is_none) {
+            return Ok(match (self.crop_x, self.crop_y, self.crop_width, self.crop_height) {
+                (Some(x), Some(y), Some(w), Some(h)) => Some((x, y, w, h)),
+                _ => None,
+            });
+        }
+        if [self.crop_x, self.crop_y, self.crop_width, self.crop_height].iter().any(Option::is_some) {
+            return Err(ImageError::InvalidParameter(
+                "Crop can be given in pixels or percentages, not both".to_string(),
+            ));
+        }
+        if percentages.iter().flatten().any(|pct| !(0.0..=100.0).contains(pct)) {
+            return Err(ImageError::InvalidParameter("Crop percentages must be between 0 and 100".to_string()));
+        }
+
+        let x_pct = self.crop_x_pct.unwrap_or(0.0);
+        let y_pct = self.crop_y_pct.unwrap_or(0.0);
+        let width_pct = self.crop_width_pct.unwrap_or(100.0 - x_pct);
+        let height_pct = self.crop_height_pct.unwrap_or(100.0 - y_pct);
+        let scale = |pct: f32, size: u32| (pct / 100.0 * size as f32).round() as u32;
+        // A nonzero percentage of a small image still keeps at least one pixel
+        let scale_size = |pct: f32, size: u32| if pct > 0.0 { scale(pct, size).max(1) } else { 0 };
+        Ok(Some((
+            scale(x_pct, width),
+            scale(y_pct, height),
+            scale_size(width_pct, width),
+            scale_size(height_pct, height),
+        )))
+    }
+}
+
+impl ImageProcessingOptionsBuilder {
+    pub fn operation(mut self, operation: Operation) -> Self {
+        self.options.operation = operation;
+        self
+    }
+
+    /// Set both resize dimensions at once
+    pub fn resize(mut self, width: u32, height: u32) -> Self {
+        self.options.resize_width = Some(width);
+        self.options.resize_height = Some(height);
+        self
+    }
+
+    /// Set the crop rectangle at once
+    pub fn crop(mut self, x: u32, y: u32, width: u32, height: u32) -> Self {
+        self.options.crop_x = Some(x);
+        self.options.crop_y = Some(y);
+        self.options.crop_width = Some(width);
+        self.options.crop_height = Some(height);
+        self
+    }
+
+    /// Set the crop rectangle at once as percentages of the image size
+    pub fn crop_pct(mut self, x: f32, y: f32, width: f32, height: f32) -> Self {
+        self.options.crop_x_pct = Some(x);
+        self.options.crop_y_pct = Some(y);
+        self.options.crop_width_pct = Some(width);
+        self.options.crop_height_pct = Some(height);
+        self
+    }
+
+    builder_setters! {
+        filter: impl Into<String>,
+        filters: Vec<String>,
+        intensity: f32,
+        brightness: f32,
+        contrast: f32,
+        saturation: f32,
+        hue_rotation: f32,
+        resize_width: u32,
+        resize_height: u32,
+        keep_aspect_ratio: bool,
+        crop_x: u32,
+        crop_y: u32,
+        crop_width: u32,
+        crop_height: u32,
+        crop_x_pct: f32,
+        crop_y_pct: f32,
+        crop_width_pct: f32,
+        crop_height_pct: f32,
+        rotation_angle: f32,
+        flip_horizontal: bool,
+        flip_vertical: bool,
+        output_format: impl Into<String>,
+        quality: u8,
+        output_as_binary: bool,
+        block_size: u32,
+        kernel: Vec<f32>,
+        kernel_divisor: f32,
+        noise_amount: f32,
+        noise_type: impl Into<String>,
+        seed: u64,
+        max_pixels: u64,
+        oil_radius: u32,
+        oil_levels: u32,
+        tint_color: impl Into<String>,
+        tint_strength: f32,
+        channel_order: impl Into<String>,
+        blend_base64: impl Into<String>,
+        blend_mode: impl Into<String>,
+        blend_opacity: f32,
+        palette_size: u32,
+        opacity: f32,
+        max_bytes: usize,
+        transpose: bool,
+        output_encoding: impl Into<String>,
+        auto_levels: bool,
+        clip_percent: f32,
+        temperature: f32,
+        tint: f32,
+        trim_tolerance: u8,
+        metadata_only: bool,
+        strip_metadata: bool,
+        threshold_channel: impl Into<String>,
+        threshold_invert: bool,
+        levels: u32,
+        svg_width: u32,
+        svg_height: u32,
+        target_aspect: impl Into<String>,
+        grayscale_mode: impl Into<String>,
+        fail_fast: bool,
+        no_upscale: bool,
+        base64_variant: impl Into<String>,
+        jpeg_progressive: bool,
+        jpeg_subsampling: impl Into<String>,
+        resize_filter: impl Into<String>,
+        key_color: impl Into<String>,
+        key_tolerance: u8,
+        luma_threshold: u8,
+        luma_above: bool,
+        canvas_width: u32,
+        canvas_height: u32,
+        anchor: impl Into<String>,
+        canvas_color: impl Into<String>,
+        clahe_tile_size: u32,
+        clahe_clip_limit: f32,
+        linear_light: bool,
+        regions: Vec<[u32; 4]>,
+        include_timing: bool,
+        bit_depth: u8,
+        max_input_bytes: usize,
+    }
+
+    pub fn build(self) -> ImageProcessingOptions {
+        self.options
+    }
+}
+
+/// A decoded image that operations are applied to in place, created by
+/// [`ImageProcessor::from_base64`]
+///
+/// ```
+/// use n8n_rust_core::ImageProcessor;
+/// # let png = ImageProcessor::photon_image_to_bytes(&photon_rs::PhotonImage::new(vec![200; 64], 4, 4), "png", None).unwrap();
+/// # let base64 = ImageProcessor::bytes_to_base64(&png);
+/// let jpeg = ImageProcessor::from_base64(&base64)?
+///     .filter("sepia")?
+///     .resize(2, 2)?
+///     .adjust(1.2, 1.0)?
+///     .encode("jpeg", Some(80))?;
+/// # Ok::<(), n8n_rust_core::image_processor::ImageError>(())
+/// ```
+pub struct LoadedImage {
+    image: PhotonImage,
+    warnings: Vec<String>,
+}
+
+impl LoadedImage {
+    /// Apply the operation described by `options`; output settings are ignored
+    pub fn apply(mut self, options: &ImageProcessingOptions) -> Result<Self, ImageError> {
+        ImageProcessor::apply_operation(&mut self.image, options, &mut self.warnings)?;
+        Ok(self)
+    }
+
+    /// Apply a named filter at full intensity
+    pub fn filter(self, name: &str) -> Result<Self, ImageError> {
+        self.apply(&ImageProcessingOptions::builder().filter(name).build())
+    }
+
+    /// Resize to exactly `width`x`height`
+    pub fn resize(self, width: u32, height: u32) -> Result<Self, ImageError> {
+        self.apply(
+            &ImageProcessingOptions::builder()
+                .operation(Operation::Transform)
+                .resize_width(width)
+                .resize_height(height)
+                .keep_aspect_ratio(false)
+                .build(),
+        )
+    }
+
+    /// Adjust brightness and saturation, where 1.0 leaves a value unchanged
+    pub fn adjust(self, brightness: f32, saturation: f32) -> Result<Self, ImageError> {
+        self.apply(
+            &ImageProcessingOptions::builder()
+                .operation(Operation::Adjust)
+                .brightness(brightness)
+                .saturation(saturation)
+                .build(),
+        )
+    }
+
+    /// Encode the current image as `format`
+    pub fn encode(&self, format: &str, quality: Option<u8>) -> Result<Vec<u8>, ImageError> {
+        ImageProcessor::photon_image_to_bytes(&self.image, format, quality)
+    }
+
+    /// The current decoded image
+    pub fn image(&self) -> &PhotonImage {
+        &self.image
+    }
+
+    /// Warnings collected from every operation applied so far
+    pub fn warnings(&self) -> &[String] {
+        &self.warnings
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    // Create a simple test image as base64
+    fn create_test_image_base64() -> String {
+        // Create a simple 2x2 red image
+        let red_pixel = [255u8, 0, 0, 255]; // RGBA red
+        let mut image_data = Vec::new();
+        
+        // 2x2 image with all red pixels
+        for _ in 0..4 {
+            image_data.extend_from_slice(&red_pixel);
+        }
+        
+        let photon_image = PhotonImage::new(image_data, 2, 2);
+        
+        // Convert to base64
+        let bytes = ImageProcessor::photon_image_to_bytes(&photon_image, "png", None)
+            .unwrap_or_else(|_| vec![137, 80, 78, 71, 13, 10, 26, 10]); // PNG header
+        ImageProcessor::bytes_to_base64_data_url(&bytes, "png")
+    }
+
+    #[test]
+    fn test_base64_conversion() {
+        let test_image = create_test_image_base64();
+        
+        // Test conversion from base64 to PhotonImage
+        let photon_result = ImageProcessor::base64_to_photon_image(&test_image);
+        assert!(photon_result.is_ok());
+        
+        let photon_image = photon_result.unwrap();
+        assert_eq!(photon_image.get_width(), 2);
+        assert_eq!(photon_image.get_height(), 2);
+    }
+
+    #[test]
+    fn test_filter_processing() {
+        let test_image = create_test_image_base64();
+        let options = ImageProcessingOptions {
+            operation: Operation::Filter,
+            filter: Some("grayscale".to_string()),
+            intensity: Some(1.0),
+            output_format: Some("png".to_string()),
+            ..Default::default()
+        };
+
+        let result = ImageProcessor::process_image(&test_image, &options);
+        assert!(result.success);
+        assert!(result.image_data.is_some());
+        assert!(result.metadata.is_some());
+    }
+
+    #[test]
+    fn test_filter_intensity_blends_with_original() {
+        let input = encode_pattern(8, 8, |x, y| (x * 200.0 + y * 40.0) as u8);
+        let original = ImageProcessor::base64_to_photon_image(&input).unwrap().get_raw_pixels();
+        let filtered = |intensity: f32| {
+            let options = ImageProcessingOptions::builder().filter("golden").intensity(intensity).build();
+            let output = ImageProcessor::process_image_to_bytes(&input, &options).map(|(bytes, _)| bytes)?;
+            Ok::<_, ImageError>(ImageProcessor::bytes_to_photon_image(&output).unwrap().get_raw_pixels())
+        };
+        let full = filtered(1.0).unwrap();
+        assert_ne!(full, original);
+        assert_eq!(filtered(0.0).unwrap(), original);
+
+        // Halfway lands on the average of the two, up to rounding
+        let half = filtered(0.5).unwrap();
+        for ((half, full), original) in half.iter().zip(&full).zip(&original) {
+            assert!((*half as i16 - (*full as i16 + *original as i16) / 2).abs() <= 1);
+        }
+        // Above 1.0 blend-only filters stay at full strength
+        assert_eq!(filtered(1.5).unwrap(), full);
+    }
+
+    #[test]
+    fn test_filter_intensity_keeps_legacy_scaling() {
+        let input = encode_pattern(4, 4, |x, y| (x * 200.0 + y * 40.0) as u8);
+        let warm = |intensity: Option<f32>| {
+            let options = ImageProcessingOptions { intensity, ..ImageProcessingOptions::builder().filter("warm").build() };
+            let result = ImageProcessor::process_image(&input, &options);
+            let pixels = ImageProcessor::base64_to_photon_image(result.image_data.as_deref().unwrap()).unwrap().get_raw_pixels();
+            (pixels, result.warnings)
+        };
+
+        // Default intensity shifts red up 20 and blue down 10, as it always has
+        let mut expected = ImageProcessor::base64_to_photon_image(&input).unwrap();
+        photon_rs::channels::alter_red_channel(&mut expected, 20);
+        photon_rs::channels::alter_blue_channel(&mut expected, -10);
+        assert_eq!(warm(None).0, expected.get_raw_pixels());
+        assert_eq!(warm(Some(1.0)).0, expected.get_raw_pixels());
+
+        // Out-of-range intensity is clamped with a warning instead of failing
+        let (clamped, warnings) = warm(Some(5.0));
+        assert_eq!(clamped, warm(Some(2.0)).0);
+        assert!(warnings.iter().any(|warning| warning.contains("clamped")), "{:?}", warnings);
+    }
+
+    #[test]
+    fn test_filters_apply_in_sequence() {
+        let input = encode_pattern(4, 4, |x, y| (x * 200.0 + y * 40.0) as u8);
+        let render = |input: &str, options: ImageProcessingOptions| {
+            let output = ImageProcessor::process_image_to_bytes(input, &options).unwrap().0;
+            ImageProcessor::bytes_to_base64_data_url(&output, "png")
+        };
+        let stacked = render(&input, ImageProcessingOptions::builder()
+            .filters(vec!["grayscale".to_string(), "invert".to_string()])
+            .build());
+        let grayscale = render(&input, ImageProcessingOptions::builder().filter("grayscale").build());
+        let one_at_a_time = render(&grayscale, ImageProcessingOptions::builder().filter("invert").build());
+        assert_eq!(stacked, one_at_a_time);
+
+        let typo = ImageProcessingOptions::builder()
+            .filters(vec!["grayscale".to_string(), "invrt".to_string()])
+            .build();
+        assert_eq!(ImageProcessor::process_image_to_bytes(&input, &typo).unwrap_err().code(), "unknown_filter");
+    }
+
+    #[test]
+    fn test_invalid_image_data() {
+        let invalid_data = "invalid_base64_data";
+        let options = ImageProcessingOptions {
+            operation: Operation::Filter,
+            filter: Some("grayscale".to_string()),
+            ..Default::default()
+        };
+
+        let result = ImageProcessor::process_image(invalid_data, &options);
+        assert!(!result.success);
+        assert!(result.error.is_some());
+    }
+
+    #[test]
+    fn test_batch_processing() {
+        let test_image = create_test_image_base64();
+        let images = vec![test_image.clone(), test_image];
+        let options = ImageProcessingOptions {
+            operation: Operation::Filter,
+            filter: Some("sepia".to_string()),
+            ..Default::default()
+        };
+
+        let result = ImageProcessor::process_batch(images, &options);
+        assert_eq!(result.processed, 2);
+        assert_eq!(result.successful, 2);
+        assert_eq!(result.failed, 0);
+    }
+
+    #[test]
+    fn test_transform_operations() {
+        let test_image = create_test_image_base64();
+        let options = ImageProcessingOptions {
+            operation: Operation::Transform,
+            resize_width: Some(4),
+            resize_height: Some(4),
+            keep_aspect_ratio: Some(false),
+            output_format: Some("png".to_string()),
+            ..Default::default()
+        };
+
+        let result = ImageProcessor::process_image(&test_image, &options);
+        assert!(result.success);
+        assert!(result.metadata.is_some());
+        
+        if let Some(metadata) = result.metadata {
+            assert_eq!(metadata.width, 4);
+            assert_eq!(metadata.height, 4);
+        }
+    }
+
+    #[test]
+    fn test_pixelate_effect() {
+        // 8x8 horizontal gradient
+        let mut image_data = Vec::new();
+        for _y in 0..8 {
+            for x in 0..8u8 {
+                image_data.extend_from_slice(&[x * 30, x * 30, x * 30, 255]);
+            }
+        }
+        let gradient = PhotonImage::new(image_data, 8, 8);
+        let bytes = ImageProcessor::photon_image_to_bytes(&gradient, "png", None).unwrap();
+        let input = ImageProcessor::bytes_to_base64(&bytes);
+
+        let options = ImageProcessingOptions {
+            operation: Operation::Effect,
+            filter: Some("pixelate".to_string()),
+            block_size: Some(4),
+            output_as_binary: Some(true),
+            ..Default::default()
+        };
+
+        let result = ImageProcessor::process_image(&input, &options);
+        assert!(result.success);
+
+        let output = image::load_from_memory(&result.binary_data.unwrap()).unwrap().to_rgba8();
+        for block_y in (0..8).step_by(4) {
+            for block_x in (0..8).step_by(4) {
+                let expected = output.get_pixel(block_x, block_y);
+                for y in block_y..block_y + 4 {
+                    for x in block_x..block_x + 4 {
+                        assert_eq!(output.get_pixel(x, y), expected);
+                    }
+                }
+            }
+        }
+        assert_ne!(output.get_pixel(0, 0), output.get_pixel(4, 0));
+    }
+
+    #[test]
+    fn test_pixelate_region_leaves_rest_intact() {
+        let mut image_data = Vec::new();
+        for _y in 0..8 {
+            for x in 0..8u8 {
+                image_data.extend_from_slice(&[x * 30, 0, 0, 255]);
+            }
+        }
+        let mut image = PhotonImage::new(image_data.clone(), 8, 8);
+
+        ImageProcessor::pixelate_region(&mut image, (0, 0, 4, 4), 4).unwrap();
+
+        let pixels = image.get_raw_pixels();
+        // Inside the region every pixel shares the block average
+        assert_eq!(pixels[0], pixels[3 * 4]);
+        // Outside the region the gradient is untouched
+        let idx = (5 * 8 + 6) * 4;
+        assert_eq!(pixels[idx..idx + 4], image_data[idx..idx + 4]);
+    }
+
+    #[test]
+    fn test_convolve_identity_kernel() {
+        let mut image_data = Vec::new();
+        for i in 0..16u8 {
+            image_data.extend_from_slice(&[i * 15, 255 - i * 15, i * 7, 255]);
+        }
+        let mut image = PhotonImage::new(image_data.clone(), 4, 4);
+        let identity = [0.0, 0.0, 0.0, 0.0, 1.0, 0.0, 0.0, 0.0, 0.0];
+
+        ImageProcessor::convolve(&mut image, &identity, None).unwrap();
+        assert_eq!(image.get_raw_pixels(), image_data);
+    }
+
+    #[test]
+    fn test_convolve_rejects_invalid_kernel() {
+        let test_image = create_test_image_base64();
+        let options = ImageProcessingOptions {
+            operation: Operation::Effect,
+            filter: Some("convolve".to_string()),
+            kernel: Some(vec![1.0; 8]),
+            ..Default::default()
+        };
+
+        let result = ImageProcessor::process_image(&test_image, &options);
+        assert!(!result.success);
+        assert!(result.error.unwrap().contains("9 (3x3) or 25 (5x5)"));
+    }
+
+    #[test]
+    fn test_noise_is_reproducible_with_seed() {
+        let test_image = create_test_image_base64();
+        for noise_type in ["gaussian", "salt_pepper"] {
+            let options = ImageProcessingOptions {
+                operation: Operation::Effect,
+                filter: Some("noise".to_string()),
+                noise_type: Some(noise_type.to_string()),
+                noise_amount: Some(0.5),
+                seed: Some(42),
+                output_as_binary: Some(true),
+                ..Default::default()
+            };
+
+            let first = ImageProcessor::process_image(&test_image, &options);
+            let second = ImageProcessor::process_image(&test_image, &options);
+            assert!(first.success);
+            assert!(second.success);
+            assert_eq!(first.binary_data, second.binary_data);
+
+            for amount in [f32::NAN, f32::INFINITY] {
+                let invalid = ImageProcessingOptions { noise_amount: Some(amount), ..options.clone() };
+                let result = ImageProcessor::process_image(&test_image, &invalid);
+                assert_eq!(result.error_code.as_deref(), Some("invalid_parameter"));
+            }
+        }
+    }
+
+    #[test]
+    fn test_unknown_operation_rejected_at_parse_time() {
+        let parsed = serde_json::from_str::<ImageProcessingOptions>(r#"{"operation":"bogus"}"#);
+        let error = parsed.unwrap_err().to_string();
+        assert!(error.contains("unknown variant `bogus`"));
+
+        let parsed = serde_json::from_str::<ImageProcessingOptions>(r#"{"operation":"transform"}"#).unwrap();
+        assert_eq!(parsed.operation, Operation::Transform);
+
+        let parsed = serde_json::from_str::<ImageProcessingOptions>(r#"{"filter":"sepia"}"#);
+        assert!(parsed.unwrap_err().to_string().contains("missing field `operation`"));
+    }
+
+    #[test]
+    fn test_every_filter_is_handled() {
+        let test_image = create_test_image_base64();
+        for filter in Filter::all() {
+            let options = ImageProcessingOptions {
+                operation: Operation::Filter,
+                filter: Some(filter.as_str().to_string()),
+                ..Default::default()
+            };
+
+            let result = ImageProcessor::process_image(&test_image, &options);
+            assert!(result.success, "filter {} failed: {:?}", filter.as_str(), result.error);
+
+            // The wire name must round-trip through serde as well
+            let json = serde_json::to_string(filter).unwrap();
+            assert_eq!(json, format!("\"{}\"", filter.as_str()));
+        }
+    }
+
+    #[test]
+    fn test_unsupported_format_error_code() {
+        let image = PhotonImage::new(vec![255, 0, 0, 255], 1, 1);
+        let error = ImageProcessor::photon_image_to_bytes(&image, "tga", None).unwrap_err();
+        assert_eq!(error, ImageError::UnsupportedFormat("tga".to_string()));
+        assert_eq!(error.code(), "unsupported_format");
+
+        let json = serde_json::to_value(&error).unwrap();
+        assert_eq!(json["code"], "unsupported_format");
+        assert_eq!(json["message"], "Unsupported output format: tga");
+    }
+
+    #[test]
+    fn test_out_of_bounds_crop_is_rejected() {
+        let test_image = create_test_image_base64();
         let options = ImageProcessingOptions {
-            operation: "transform".to_string(),
-            resize_width: Some(4),
-            resize_height: Some(4),
-            keep_aspect_ratio: Some(false),
-            output_format: Some("png".to_string()),
+            operation: Operation::Transform,
+            crop_x: Some(1),
+            crop_y: Some(0),
+            crop_width: Some(2),
+            crop_height: Some(2),
+            ..Default::default()
+        };
+
+        let result = ImageProcessor::process_image(&test_image, &options);
+        assert!(!result.success);
+        assert!(result.error.unwrap().contains("outside the 2x2 image"));
+    }
+
+    #[test]
+    fn test_process_raw_png_bytes() {
+        let image = PhotonImage::new([0u8, 0, 255, 255].repeat(6), 3, 2);
+        let png_bytes = ImageProcessor::photon_image_to_bytes(&image, "png", None).unwrap();
+        let options = ImageProcessingOptions {
+            operation: Operation::Filter,
+            filter: Some("invert".to_string()),
+            output_as_binary: Some(true),
+            ..Default::default()
+        };
+
+        let result = ImageProcessor::process_image_bytes(&png_bytes, &options);
+        assert!(result.success);
+        let metadata = result.metadata.unwrap();
+        assert_eq!((metadata.width, metadata.height), (3, 2));
+
+        let output = image::load_from_memory(&result.binary_data.unwrap()).unwrap().to_rgba8();
+        assert_eq!(output.get_pixel(0, 0).0, [255, 255, 0, 255]);
+    }
+
+    // Minimal CRC-32 (IEEE) for hand-built PNG chunks
+    fn crc32(data: &[u8]) -> u32 {
+        let mut crc = 0xFFFF_FFFFu32;
+        for &byte in data {
+            crc ^= byte as u32;
+            for _ in 0..8 {
+                crc = if crc & 1 != 0 { (crc >> 1) ^ 0xEDB8_8320 } else { crc >> 1 };
+            }
+        }
+        !crc
+    }
+
+    #[test]
+    fn test_oversized_header_is_rejected() {
+        // PNG signature plus an IHDR claiming 50000x50000 RGBA and an empty IDAT
+        let mut png = vec![0x89, b'P', b'N', b'G', 0x0D, 0x0A, 0x1A, 0x0A];
+        let mut ihdr = b"IHDR".to_vec();
+        ihdr.extend_from_slice(&50_000u32.to_be_bytes());
+        ihdr.extend_from_slice(&50_000u32.to_be_bytes());
+        ihdr.extend_from_slice(&[8, 6, 0, 0, 0]);
+        png.extend_from_slice(&13u32.to_be_bytes());
+        png.extend_from_slice(&ihdr);
+        png.extend_from_slice(&crc32(&ihdr).to_be_bytes());
+        png.extend_from_slice(&0u32.to_be_bytes());
+        png.extend_from_slice(b"IDAT");
+        png.extend_from_slice(&crc32(b"IDAT").to_be_bytes());
+
+        let error = ImageProcessor::bytes_to_photon_image(&png).err().unwrap();
+        assert_eq!(error.code(), "image_too_large");
+
+        let input = ImageProcessor::bytes_to_base64(&png);
+        let options = ImageProcessingOptions {
+            max_pixels: Some(1_000),
+            ..Default::default()
+        };
+        let result = ImageProcessor::process_image(&input, &options);
+        assert!(!result.success);
+        assert!(result.error.unwrap().contains("exceed the limit of 1000 pixels"));
+    }
+
+    #[test]
+    fn test_batch_preserves_input_order() {
+        // Give each image a distinct width so results can be matched to inputs
+        let images: Vec<String> = (1..=50u32)
+            .map(|width| {
+                let image = PhotonImage::new([10u8, 20, 30, 255].repeat(width as usize), width, 1);
+                let bytes = ImageProcessor::photon_image_to_bytes(&image, "png", None).unwrap();
+                ImageProcessor::bytes_to_base64(&bytes)
+            })
+            .collect();
+        let options = ImageProcessingOptions {
+            filter: Some("grayscale".to_string()),
+            ..Default::default()
+        };
+
+        let result = ImageProcessor::process_batch(images, &options);
+        assert_eq!(result.processed, 50);
+        assert_eq!(result.successful, 50);
+        for (i, image_result) in result.results.iter().enumerate() {
+            assert_eq!(image_result.metadata.as_ref().unwrap().width, i as u32 + 1);
+        }
+    }
+
+    #[test]
+    fn test_batch_with_per_image_options() {
+        let image = PhotonImage::new([200u8, 80, 40, 255].repeat(4), 2, 2);
+        let bytes = ImageProcessor::photon_image_to_bytes(&image, "png", None).unwrap();
+        let input = ImageProcessor::bytes_to_base64(&bytes);
+        let images = vec![input.clone(), input];
+        let options = vec![
+            ImageProcessingOptions::builder().filter("grayscale").build(),
+            ImageProcessingOptions::builder().filter("sepia").build(),
+        ];
+
+        let result = ImageProcessor::process_batch_with_options(images, options).unwrap();
+        assert_eq!(result.successful, 2);
+        assert_ne!(result.results[0].image_data, result.results[1].image_data);
+    }
+
+    #[test]
+    fn test_batch_with_mismatched_options_fails() {
+        let images = vec![create_test_image_base64(), create_test_image_base64()];
+        let options = vec![ImageProcessingOptions::default()];
+
+        let error = ImageProcessor::process_batch_with_options(images, options).unwrap_err();
+        assert_eq!(error.code(), "invalid_parameter");
+    }
+
+    #[test]
+    fn test_batch_survives_panicking_image() {
+        // photon-rs panics when blurring a 1x1 image
+        let tiny = PhotonImage::new(vec![10, 20, 30, 255], 1, 1);
+        let tiny = ImageProcessor::bytes_to_base64(&ImageProcessor::photon_image_to_bytes(&tiny, "png", None).unwrap());
+        let larger = PhotonImage::new([10u8, 20, 30, 255].repeat(64), 8, 8);
+        let larger = ImageProcessor::bytes_to_base64(&ImageProcessor::photon_image_to_bytes(&larger, "png", None).unwrap());
+        let options = ImageProcessingOptions {
+            operation: Operation::Effect,
+            filter: Some("blur".to_string()),
+            ..Default::default()
+        };
+
+        let result = ImageProcessor::process_batch(vec![tiny, larger], &options);
+        assert_eq!(result.processed, 2);
+        assert_eq!(result.successful, 1);
+        assert_eq!(result.failed, 1);
+        assert!(result.results[0].error.as_ref().unwrap().contains("panicked"));
+        assert_eq!(result.results[0].error_code.as_deref(), Some("panic"));
+        assert!(result.results[1].success);
+        assert_eq!(result.results[1].error_code, None);
+    }
+
+    #[test]
+    fn test_failed_results_carry_error_code() {
+        let error_code = |input: &str, options: &ImageProcessingOptions| {
+            ImageProcessor::process_image(input, options).error_code
+        };
+        let sepia = ImageProcessingOptions::builder().filter("sepia").build();
+        assert_eq!(error_code("not an image!", &sepia).as_deref(), Some("decode_base64"));
+        assert_eq!(error_code("aGVsbG8=", &sepia).as_deref(), Some("load_image"));
+
+        let tga = ImageProcessingOptions::builder().filter("sepia").output_format("tga").build();
+        assert_eq!(error_code(&create_test_image_base64(), &tga).as_deref(), Some("unsupported_format"));
+
+        let json = serde_json::to_value(ImageProcessor::process_image("not an image!", &sepia)).unwrap();
+        assert_eq!(json["error_code"], "decode_base64");
+    }
+
+    #[test]
+    fn test_cancelled_batch_stops_at_image_boundary() {
+        let images = vec![create_test_image_base64(); 3];
+        let options = ImageProcessingOptions::builder().filter("sepia").build();
+        let cancel = AtomicBool::new(false);
+
+        let result = ImageProcessor::process_batch_cancelable(images.clone(), &options, &cancel, |_, _, _| {
+            cancel.store(true, Ordering::Relaxed);
+        });
+        assert!(result.cancelled);
+        assert_eq!(result.processed, 1);
+        assert_eq!(result.successful, 1);
+
+        let result = ImageProcessor::process_batch_cancelable(images, &options, &AtomicBool::new(false), |_, _, _| {});
+        assert!(!result.cancelled);
+        assert_eq!(result.processed, 3);
+    }
+
+    #[test]
+    fn test_batch_progress_reports_each_image() {
+        let images = vec![create_test_image_base64(), "not an image".to_string(), create_test_image_base64()];
+        let options = ImageProcessingOptions::builder().filter("sepia").build();
+        let mut calls = Vec::new();
+
+        let result = ImageProcessor::process_batch_with_progress(images, &options, |index, total, success| {
+            calls.push((index, total, success));
+        });
+
+        assert_eq!(result.processed, 3);
+        assert_eq!(calls, vec![(0, 3, true), (1, 3, false), (2, 3, true)]);
+    }
+
+    fn encode_pattern(width: u32, height: u32, shade: impl Fn(f32, f32) -> u8) -> String {
+        let mut pixels = Vec::with_capacity((width * height * 4) as usize);
+        for y in 0..height {
+            for x in 0..width {
+                let value = shade(x as f32 / width as f32, y as f32 / height as f32);
+                pixels.extend_from_slice(&[value, value, value, 255]);
+            }
+        }
+        let image = PhotonImage::new(pixels, width, height);
+        let bytes = ImageProcessor::photon_image_to_bytes(&image, "png", None).unwrap();
+        ImageProcessor::bytes_to_base64_data_url(&bytes, "png")
+    }
+
+    #[test]
+    fn test_perceptual_hash_near_duplicates() {
+        let waves = |x: f32, y: f32| ((x * 9.0).sin() * (y * 7.0).cos() * 120.0 + 128.0) as u8;
+        let stripes = |x: f32, y: f32| if (((x + y) * 6.0) as u32).is_multiple_of(2) { 30 } else { 220 };
+
+        let original = ImageProcessor::perceptual_hash(&encode_pattern(64, 64, waves)).unwrap();
+        let resized = ImageProcessor::perceptual_hash(&encode_pattern(57, 61, waves)).unwrap();
+        let unrelated = ImageProcessor::perceptual_hash(&encode_pattern(64, 64, stripes)).unwrap();
+
+        assert_eq!(original.len(), 16);
+        assert!(ImageProcessor::hamming_distance(&original, &resized).unwrap() <= 5);
+        assert!(ImageProcessor::hamming_distance(&original, &unrelated).unwrap() >= 20);
+    }
+
+    #[test]
+    fn test_hamming_distance() {
+        assert_eq!(ImageProcessor::hamming_distance("00000000000000ff", "0000000000000000").unwrap(), 8);
+        assert_eq!(ImageProcessor::hamming_distance("ffffffffffffffff", "ffffffffffffffff").unwrap(), 0);
+        assert_eq!(ImageProcessor::hamming_distance("xyz", "0").unwrap_err().code(), "invalid_parameter");
+    }
+
+    #[test]
+    fn test_count_unique_colors() {
+        let two_tone = encode_pattern(16, 16, |x, _| if x < 0.5 { 0 } else { 255 });
+        assert_eq!(ImageProcessor::count_unique_colors(&two_tone).unwrap(), 2);
+
+        let gradient = encode_pattern(256, 4, |x, _| (x * 256.0) as u8);
+        assert_eq!(ImageProcessor::count_unique_colors(&gradient).unwrap(), 256);
+
+        // Sampled down to the pixel budget, but both colors still appear
+        let large = encode_pattern(1200, 1000, |x, _| if x < 0.5 { 0 } else { 255 });
+        assert_eq!(ImageProcessor::count_unique_colors(&large).unwrap(), 2);
+    }
+
+    #[test]
+    fn test_clahe_increases_local_contrast() {
+        // Dim, low-contrast texture: values stay within 90..110
+        let input = encode_pattern(64, 64, |x, y| (100.0 + (x * 40.0).sin() * (y * 30.0).cos() * 10.0) as u8);
+        let spread = |pixels: &[u8]| {
+            let values: Vec<u8> = pixels.chunks_exact(4).map(|pixel| pixel[0]).collect();
+            values.iter().max().unwrap() - values.iter().min().unwrap()
+        };
+        let before = ImageProcessor::base64_to_photon_image(&input).unwrap().get_raw_pixels();
+
+        let options = ImageProcessingOptions::builder()
+            .operation(Operation::Effect)
+            .filter("clahe")
+            .clahe_tile_size(16)
+            .clahe_clip_limit(4.0)
+            .build();
+        let output = ImageProcessor::process_image_to_bytes(&input, &options).unwrap().0;
+        let after = ImageProcessor::bytes_to_photon_image(&output).unwrap().get_raw_pixels();
+        assert!(spread(&after) > spread(&before) * 2, "{} vs {}", spread(&after), spread(&before));
+        // Gray input stays gray
+        assert!(after.chunks_exact(4).all(|pixel| pixel[0] == pixel[1] && pixel[1] == pixel[2]));
+
+        let invalid = ImageProcessingOptions { clahe_clip_limit: Some(0.5), ..options };
+        assert_eq!(ImageProcessor::process_image_to_bytes(&input, &invalid).unwrap_err().code(), "invalid_parameter");
+    }
+
+    #[test]
+    fn test_linear_light_brightness() {
+        let brighten = |level: u8, linear_light: bool| {
+            // Photon's offset skips the final pixel, so read the first of two
+            let mut image = PhotonImage::new([level, level, level, 255].repeat(2), 2, 1);
+            let options = ImageProcessingOptions {
+                operation: Operation::Adjust,
+                brightness: Some(1.5),
+                linear_light: Some(linear_light),
+                ..Default::default()
+            };
+            ImageProcessor::apply_adjustments(&mut image, &options, &mut Vec::new()).unwrap();
+            image.get_raw_pixels()[0]
+        };
+
+        // sRGB mid-gray holds about 21.6% of full light; 1.5x is about 32.4%, sRGB level 154
+        assert_eq!(brighten(128, true), 154);
+        assert_eq!(brighten(128, false), 153);
+        // Dark tones differ most: an sRGB offset lifts them far more than a gain does
+        assert!(brighten(20, true) < brighten(20, false) - 15);
+        assert_eq!(brighten(0, true), 0);
+
+        assert!((0..=255).all(|level| linear_to_srgb(SRGB_TO_LINEAR[level as usize]) == level));
+    }
+
+    #[test]
+    fn test_16bit_gradient_keeps_precision() {
+        // 1024 distinct gray levels, four times what 8 bits can hold
+        let gradient = image::ImageBuffer::from_fn(1024, 1, |x, _| image::Luma([x as u16 * 64]));
+        let mut png = Vec::new();
+        DynamicImage::ImageLuma16(gradient.clone()).write_to(&mut Cursor::new(&mut png), ImageFormat::Png).unwrap();
+        let input = ImageProcessor::bytes_to_base64(&png);
+
+        let levels = |options: &ImageProcessingOptions| {
+            let (output, metadata) = ImageProcessor::process_image_to_bytes(&input, options).unwrap();
+            let decoded = image::load_from_memory(&output).unwrap();
+            (decoded.to_luma16().into_raw(), metadata)
+        };
+        let deep = ImageProcessingOptions::builder().operation(Operation::Adjust).bit_depth(16).build();
+        let (round_trip, metadata) = levels(&deep);
+        assert_eq!(round_trip, gradient.into_raw());
+        assert_eq!(metadata.bit_depth, Some(16));
+
+        let distinct = |samples: &[u16]| samples.iter().collect::<std::collections::HashSet<_>>().len();
+        let brightened = levels(&ImageProcessingOptions { brightness: Some(1.1), ..deep.clone() }).0;
+        assert!(distinct(&brightened) > 900);
+        let eight_bit = levels(&ImageProcessingOptions { bit_depth: None, brightness: Some(1.1), ..deep.clone() }).0;
+        assert!(distinct(&eight_bit) <= 256);
+
+        let eight = ImageProcessingOptions { bit_depth: Some(8), brightness: Some(1.1), ..deep.clone() };
+        assert_eq!(levels(&eight).0, eight_bit);
+        let twelve = ImageProcessingOptions { bit_depth: Some(12), ..deep.clone() };
+        assert_eq!(ImageProcessor::process_image_to_bytes(&input, &twelve).unwrap_err().code(), "invalid_parameter");
+
+        let jpeg = ImageProcessingOptions { output_format: Some("jpeg".to_string()), ..deep };
+        assert_eq!(ImageProcessor::process_image_to_bytes(&input, &jpeg).unwrap_err().code(), "invalid_parameter");
+    }
+
+    #[test]
+    fn test_oil_effect() {
+        let waves = |x: f32, y: f32| ((x * 23.0).sin() * (y * 17.0).cos() * 120.0 + 128.0) as u8;
+        let input = encode_pattern(48, 48, waves);
+        let options = ImageProcessingOptions::builder()
+            .operation(Operation::Effect)
+            .filter("oil")
+            .oil_radius(3)
+            .oil_levels(8)
+            .build();
+
+        let started = std::time::Instant::now();
+        let result = ImageProcessor::process_image(&input, &options);
+        assert!(result.success, "{:?}", result.error);
+        assert!(started.elapsed() < std::time::Duration::from_secs(5));
+
+        let before = ImageProcessor::base64_to_photon_image(&input).unwrap().get_raw_pixels();
+        let after = ImageProcessor::base64_to_photon_image(&result.image_data.unwrap()).unwrap();
+        assert_eq!((after.get_width(), after.get_height()), (48, 48));
+        assert_ne!(after.get_raw_pixels(), before);
+
+        let options = ImageProcessingOptions { oil_radius: Some(50), ..options };
+        let result = ImageProcessor::process_image(&input, &options);
+        assert!(result.error.unwrap().contains("Oil radius"));
+    }
+
+    #[test]
+    fn test_parse_hex_color() {
+        assert_eq!(parse_hex_color("#ff0000").unwrap(), [255, 0, 0]);
+        assert_eq!(parse_hex_color("0A8").unwrap(), [0, 170, 136]);
+        assert_eq!(parse_hex_color(" #FfA500 ").unwrap(), [255, 165, 0]);
+        assert!(parse_hex_color("#ff00").is_err());
+        assert!(parse_hex_color("#gg0000").is_err());
+    }
+
+    #[test]
+    fn test_tint_gray_to_red() {
+        let gray = encode_pattern(4, 4, |_, _| 128);
+        let options = ImageProcessingOptions::builder()
+            .operation(Operation::Tint)
+            .tint_color("#ff0000")
+            .tint_strength(1.0)
+            .build();
+
+        let result = ImageProcessor::process_image(&gray, &options);
+        assert!(result.success, "{:?}", result.error);
+        let output = ImageProcessor::base64_to_photon_image(&result.image_data.unwrap()).unwrap();
+        assert!(output.get_raw_pixels().chunks_exact(4).all(|p| p == [255, 0, 0, 255]));
+    }
+
+    #[test]
+    fn test_swap_channels_red_to_blue() {
+        let options = ImageProcessingOptions::builder()
+            .operation(Operation::SwapChannels)
+            .channel_order("bgr")
+            .build();
+
+        let result = ImageProcessor::process_image(&create_test_image_base64(), &options);
+        assert!(result.success, "{:?}", result.error);
+        let output = ImageProcessor::base64_to_photon_image(&result.image_data.unwrap()).unwrap();
+        assert!(output.get_raw_pixels().chunks_exact(4).all(|p| p == [0, 0, 255, 255]));
+    }
+
+    #[test]
+    fn test_swap_channels_rejects_invalid_order() {
+        for order in ["rg", "rgbb", "rrg", "rgx"] {
+            let options = ImageProcessingOptions::builder()
+                .operation(Operation::SwapChannels)
+                .channel_order(order)
+                .build();
+            let result = ImageProcessor::process_image(&create_test_image_base64(), &options);
+            assert!(result.error.unwrap().contains("permutation"), "{}", order);
+        }
+    }
+
+    fn solid_image_base64(width: u32, height: u32, rgba: [u8; 4]) -> String {
+        let pixels = rgba.repeat((width * height) as usize);
+        let image = PhotonImage::new(pixels, width, height);
+        let bytes = ImageProcessor::photon_image_to_bytes(&image, "png", None).unwrap();
+        ImageProcessor::bytes_to_base64_data_url(&bytes, "png")
+    }
+
+    fn blend_options(top: String, mode: &str) -> ImageProcessingOptions {
+        ImageProcessingOptions::builder()
+            .operation(Operation::Blend)
+            .blend_base64(top)
+            .blend_mode(mode)
+            .build()
+    }
+
+    fn first_pixel(result: ImageProcessingResult) -> Vec<u8> {
+        assert!(result.success, "{:?}", result.error);
+        let image = ImageProcessor::base64_to_photon_image(&result.image_data.unwrap()).unwrap();
+        image.get_raw_pixels()[..4].to_vec()
+    }
+
+    #[test]
+    fn test_blend_multiply_white_base_keeps_top() {
+        let base = solid_image_base64(4, 4, [255, 255, 255, 255]);
+        let top = solid_image_base64(4, 4, [200, 100, 50, 255]);
+        let result = ImageProcessor::process_image(&base, &blend_options(top, "multiply"));
+        assert_eq!(first_pixel(result), [200, 100, 50, 255]);
+    }
+
+    #[test]
+    fn test_blend_screen_and_opacity() {
+        let base = solid_image_base64(4, 4, [128, 0, 255, 255]);
+        let top = solid_image_base64(2, 2, [128, 128, 128, 255]);
+        let result = ImageProcessor::process_image(&base, &blend_options(top.clone(), "screen"));
+        assert_eq!(first_pixel(result), [192, 128, 255, 255]);
+
+        let options = ImageProcessingOptions { blend_opacity: Some(0.0), ..blend_options(top, "screen") };
+        let result = ImageProcessor::process_image(&base, &options);
+        assert_eq!(first_pixel(result), [128, 0, 255, 255]);
+    }
+
+    #[test]
+    fn test_blend_rejects_unknown_mode() {
+        let base = create_test_image_base64();
+        let result = ImageProcessor::process_image(&base, &blend_options(base.clone(), "dodge"));
+        assert!(result.error.unwrap().contains("Unknown blend mode"));
+    }
+
+    #[test]
+    fn test_quantize_limits_colors() {
+        let mut pixels = Vec::new();
+        for y in 0..32u32 {
+            for x in 0..32u32 {
+                pixels.extend_from_slice(&[(x * 8) as u8, (y * 8) as u8, ((x + y) * 4) as u8, 255]);
+            }
+        }
+        let image = PhotonImage::new(pixels, 32, 32);
+        let bytes = ImageProcessor::photon_image_to_bytes(&image, "png", None).unwrap();
+        let input = ImageProcessor::bytes_to_base64_data_url(&bytes, "png");
+
+        let options = ImageProcessingOptions::builder()
+            .operation(Operation::Effect)
+            .filter("quantize")
+            .palette_size(16)
+            .build();
+        let result = ImageProcessor::process_image(&input, &options);
+        assert!(result.success, "{:?}", result.error);
+
+        let palette = result.metadata.unwrap().palette.unwrap();
+        assert!(!palette.is_empty() && palette.len() <= 16);
+        assert!(palette.iter().all(|color| parse_hex_color(color).is_ok()));
+
+        let output = ImageProcessor::base64_to_photon_image(&result.image_data.unwrap()).unwrap();
+        let pixels = output.get_raw_pixels();
+        let distinct: std::collections::HashSet<&[u8]> = pixels.chunks_exact(4).collect();
+        assert!(distinct.len() <= 16, "{} colors", distinct.len());
+    }
+
+    #[test]
+    fn test_dither_gradient_to_black_and_white() {
+        let input = encode_pattern(64, 16, |x, _| (x * 255.0) as u8);
+        let options = ImageProcessingOptions::builder()
+            .operation(Operation::Effect)
+            .filter("dither")
+            .build();
+        let result = ImageProcessor::process_image(&input, &options);
+        assert!(result.success, "{:?}", result.error);
+
+        let average = |pixels: &[u8]| {
+            pixels.chunks_exact(4).map(|p| p[0] as f32).sum::<f32>() / (pixels.len() / 4) as f32
+        };
+        let before = ImageProcessor::base64_to_photon_image(&input).unwrap().get_raw_pixels();
+        let output = ImageProcessor::base64_to_photon_image(&result.image_data.unwrap()).unwrap();
+        let after = output.get_raw_pixels();
+
+        assert!(after.chunks_exact(4).all(|p| p[..3] == [0, 0, 0] || p[..3] == [255, 255, 255]));
+        assert!(after.chunks_exact(4).any(|p| p[0] == 0) && after.chunks_exact(4).any(|p| p[0] == 255));
+        assert!((average(&before) - average(&after)).abs() < 8.0);
+    }
+
+    #[test]
+    fn test_dither_to_palette() {
+        let input = encode_pattern(32, 32, |x, y| ((x + y) * 127.0) as u8);
+        let options = ImageProcessingOptions::builder()
+            .operation(Operation::Effect)
+            .filter("dither")
+            .palette_size(4)
+            .build();
+        let result = ImageProcessor::process_image(&input, &options);
+        assert!(result.success, "{:?}", result.error);
+        assert!(result.metadata.unwrap().palette.unwrap().len() <= 4);
+    }
+
+    #[test]
+    fn test_opacity_scales_alpha() {
+        let options = ImageProcessingOptions::builder()
+            .operation(Operation::Adjust)
+            .opacity(0.5)
+            .build();
+        let result = ImageProcessor::process_image(&create_test_image_base64(), &options);
+        assert!(result.success, "{:?}", result.error);
+        let output = ImageProcessor::base64_to_photon_image(&result.image_data.unwrap()).unwrap();
+        assert!(output.get_raw_pixels().chunks_exact(4).all(|p| (127..=128).contains(&p[3])));
+
+        let options = ImageProcessingOptions { output_format: Some("jpeg".to_string()), ..options };
+        let result = ImageProcessor::process_image(&create_test_image_base64(), &options);
+        assert!(result.error.unwrap().contains("alpha"));
+    }
+
+    #[test]
+    fn test_max_bytes_lowers_jpeg_quality() {
+        let input = encode_pattern(96, 96, |x, y| ((x * 37.0).sin() * (y * 53.0).cos() * 127.0 + 128.0) as u8);
+        let options = ImageProcessingOptions::builder()
+            .operation(Operation::Adjust)
+            .output_format("jpeg")
+            .quality(95)
+            .build();
+        let unconstrained = ImageProcessor::process_image(&input, &options).metadata.unwrap();
+        assert_eq!(unconstrained.quality, Some(95));
+
+        let budget = unconstrained.size_bytes * 2 / 3;
+        let options = ImageProcessingOptions { max_bytes: Some(budget), ..options };
+        let result = ImageProcessor::process_image(&input, &options);
+        assert!(result.success, "{:?}", result.error);
+        let metadata = result.metadata.unwrap();
+        assert!(metadata.size_bytes <= budget);
+        assert!((10..95).contains(&metadata.quality.unwrap()));
+
+        let options = ImageProcessingOptions { max_bytes: Some(100), ..options };
+        let error = ImageProcessor::process_image_to_bytes(&input, &options).unwrap_err();
+        assert_eq!(error.code(), "exceeds_max_bytes");
+    }
+
+    #[test]
+    fn test_config_defaults_apply_to_unset_options() {
+        let input = encode_pattern(64, 64, |x, y| ((x * 0.3).sin() * (y * 0.2).cos() * 127.0 + 128.0) as u8);
+        let options = ImageProcessingOptions::builder().operation(Operation::Adjust).build();
+        let low_quality = ProcessorConfig { default_format: "jpeg".to_string(), default_quality: 30, ..Default::default() };
+        let high_quality = ProcessorConfig { default_quality: 95, ..low_quality.clone() };
+
+        let size = |options: &ImageProcessingOptions, config: &ProcessorConfig| {
+            let metadata = ImageProcessor::process_image_with_config(&input, options, config).metadata.unwrap();
+            assert_eq!(metadata.format, "jpeg");
+            (metadata.size_bytes, metadata.quality)
+        };
+        let (low_size, low) = size(&options, &low_quality);
+        let (high_size, high) = size(&options, &high_quality);
+        assert_eq!((low, high), (Some(30), Some(95)));
+        assert!(low_size < high_size);
+
+        // Values set on the options win over the configured defaults
+        let explicit = ImageProcessingOptions { quality: Some(95), ..options };
+        assert_eq!(size(&explicit, &low_quality), (high_size, Some(95)));
+    }
+
+    #[test]
+    fn test_configure_rejects_invalid_defaults() {
+        let rejection = |update: fn(&mut ProcessorConfig)| {
+            let mut config = ProcessorConfig::default();
+            update(&mut config);
+            configure(config).unwrap_err().code()
+        };
+        assert_eq!(rejection(|config| config.default_format = "tga".to_string()), "unsupported_format");
+        assert_eq!(rejection(|config| config.default_quality = 0), "invalid_parameter");
+        assert_eq!(rejection(|config| config.default_resize_filter = "bicubic".to_string()), "invalid_parameter");
+        assert_eq!(rejection(|config| config.max_pixels = 0), "invalid_parameter");
+        #[cfg(not(feature = "avif"))]
+        assert_eq!(rejection(|config| config.default_format = "avif".to_string()), "unsupported_format");
+        assert_eq!(ProcessorConfig::global(), &ProcessorConfig::default());
+    }
+
+    #[test]
+    fn test_resize_filter_option() {
+        let input = encode_pattern(8, 8, |x, _| if x < 0.5 { 0 } else { 255 });
+        let resize = |filter: &str| {
+            let options = ImageProcessingOptions::builder()
+                .operation(Operation::Transform)
+                .resize(3, 3)
+                .keep_aspect_ratio(false)
+                .resize_filter(filter)
+                .build();
+            ImageProcessor::process_image_to_bytes(&input, &options).map(|(bytes, _)| bytes)
+        };
+        let decode = |bytes: Vec<u8>| image::load_from_memory(&bytes).unwrap().to_luma8().into_raw();
+
+        // Nearest only picks source pixels; triangle blends across the edge
+        assert!(decode(resize("nearest").unwrap()).iter().all(|&value| value == 0 || value == 255));
+        assert!(decode(resize("triangle").unwrap()).iter().any(|&value| value != 0 && value != 255));
+        assert_eq!(resize("bicubic").unwrap_err().code(), "invalid_parameter");
+    }
+
+    #[test]
+    fn test_jpeg_progressive_and_subsampling() {
+        let input = encode_pattern(64, 64, |x, y| ((x * 0.3).sin() * (y * 0.2).cos() * 127.0 + 128.0) as u8);
+        let encode = |progressive: Option<bool>, subsampling: Option<&str>| {
+            let options = ImageProcessingOptions {
+                jpeg_progressive: progressive,
+                jpeg_subsampling: subsampling.map(String::from),
+                ..ImageProcessingOptions::builder().operation(Operation::Adjust).output_format("jpeg").quality(80).build()
+            };
+            ImageProcessor::process_image_to_bytes(&input, &options).map(|(bytes, _)| bytes)
+        };
+        let has_marker = |bytes: &[u8], marker: u8| bytes.windows(2).any(|pair| pair == [0xFF, marker]);
+
+        let baseline = encode(Some(false), None).unwrap();
+        let progressive = encode(Some(true), None).unwrap();
+        assert_ne!(baseline, progressive);
+        assert!(has_marker(&baseline, 0xC0) && !has_marker(&baseline, 0xC2));
+        assert!(has_marker(&progressive, 0xC2));
+        for bytes in [&baseline, &progressive] {
+            assert_eq!(image::load_from_memory(bytes).unwrap().width(), 64);
+        }
+
+        let full = encode(None, Some("4:4:4")).unwrap();
+        let quarter = encode(None, Some("4:2:0")).unwrap();
+        assert!(quarter.len() < full.len());
+
+        let error = encode(None, Some("4:1:1")).unwrap_err();
+        assert_eq!(error.code(), "invalid_parameter");
+    }
+
+    #[test]
+    fn test_crop_by_percentage() {
+        let input = encode_pattern(4, 4, |x, y| (x * 200.0 + y * 40.0) as u8);
+        let options = ImageProcessingOptions::builder()
+            .operation(Operation::Transform)
+            .crop_pct(25.0, 25.0, 50.0, 50.0)
+            .build();
+        let (output, metadata) = ImageProcessor::process_image_to_bytes(&input, &options).unwrap();
+        assert_eq!((metadata.width, metadata.height), (2, 2));
+        // The center 2x2 starts at source pixel (1, 1)
+        let pixels = ImageProcessor::bytes_to_photon_image(&output).unwrap().get_raw_pixels();
+        assert_eq!(pixels[0], (0.25 * 200.0 + 0.25 * 40.0) as u8);
+
+        let mixed = ImageProcessingOptions { crop_x: Some(0), ..options.clone() };
+        let out_of_range = ImageProcessingOptions { crop_width_pct: Some(150.0), ..options };
+        for invalid in [mixed, out_of_range] {
+            assert_eq!(ImageProcessor::process_image_to_bytes(&input, &invalid).unwrap_err().code(), "invalid_parameter");
+        }
+    }
+
+    #[test]
+    fn test_transform_handles_degenerate_sizes() {
+        let transform = |image: &PhotonImage, options: ImageProcessingOptions| {
+            let mut image = PhotonImage::new(image.get_raw_pixels(), image.get_width(), image.get_height());
+            ImageProcessor::apply_transform(&mut image, &options, &mut Vec::new()).map(|_| (image.get_width(), image.get_height()))
+        };
+        let resize = |width: Option<u32>, height: Option<u32>, keep_aspect_ratio: bool| ImageProcessingOptions {
+            operation: Operation::Transform,
+            resize_width: width,
+            resize_height: height,
+            keep_aspect_ratio: Some(keep_aspect_ratio),
             ..Default::default()
         };
 
-        let result = ImageProcessor::process_image(&test_image, &options);
+        let pixel = PhotonImage::new(vec![200, 100, 50, 255], 1, 1);
+        assert_eq!(transform(&pixel, resize(Some(4), Some(4), true)).unwrap(), (4, 4));
+        assert_eq!(transform(&pixel, resize(Some(3), Some(2), false)).unwrap(), (3, 2));
+        assert_eq!(transform(&pixel, resize(Some(5), None, true)).unwrap(), (5, 5));
+        assert_eq!(transform(&pixel, resize(None, Some(1), true)).unwrap(), (1, 1));
+        let crop = ImageProcessingOptions { crop_x: Some(0), crop_y: Some(0), crop_width: Some(1), crop_height: Some(1), ..resize(None, None, true) };
+        assert_eq!(transform(&pixel, crop).unwrap(), (1, 1));
+
+        // A thin strip keeps at least one row instead of rounding to zero
+        let strip = PhotonImage::new([9u8, 9, 9, 255].repeat(1000), 1000, 1);
+        assert_eq!(transform(&strip, resize(Some(10), Some(10), true)).unwrap(), (10, 1));
+
+        let error = transform(&pixel, resize(Some(0), Some(4), false)).unwrap_err();
+        assert_eq!(error, ImageError::InvalidParameter("Resize dimensions must be greater than 0".to_string()));
+        let empty = PhotonImage::new(Vec::new(), 0, 0);
+        let error = transform(&empty, resize(Some(4), Some(4), true)).unwrap_err();
+        assert_eq!(error, ImageError::InvalidParameter("Cannot process a zero-dimension image".to_string()));
+    }
+
+    #[test]
+    fn test_transpose_swaps_dimensions_and_pixels() {
+        // 2x3 image whose red channel encodes 10 * x + y
+        let mut pixels = Vec::new();
+        for y in 0..3u8 {
+            for x in 0..2u8 {
+                pixels.extend_from_slice(&[10 * x + y, 0, 0, 255]);
+            }
+        }
+        let bytes = ImageProcessor::photon_image_to_bytes(&PhotonImage::new(pixels, 2, 3), "png", None).unwrap();
+        let input = ImageProcessor::bytes_to_base64(&bytes);
+
+        let options = ImageProcessingOptions::builder()
+            .operation(Operation::Transform)
+            .transpose(true)
+            .build();
+        let result = ImageProcessor::process_image(&input, &options);
+        assert!(result.success, "{:?}", result.error);
+        let metadata = result.metadata.unwrap();
+        assert_eq!((metadata.width, metadata.height), (3, 2));
+
+        let output = ImageProcessor::base64_to_photon_image(&result.image_data.unwrap()).unwrap();
+        let reds: Vec<u8> = output.get_raw_pixels().chunks_exact(4).map(|p| p[0]).collect();
+        // Output pixel (x, y) holds input pixel (y, x)
+        assert_eq!(reds, vec![0, 1, 2, 10, 11, 12]);
+    }
+
+    #[test]
+    fn test_output_encoding_modes() {
+        let input = create_test_image_base64();
+        let encode = |encoding: &str| {
+            let options = ImageProcessingOptions::builder()
+                .operation(Operation::Adjust)
+                .output_encoding(encoding)
+                .build();
+            ImageProcessor::process_image(&input, &options)
+        };
+
+        let result = encode("data_url");
+        assert!(result.image_data.unwrap().starts_with("data:image/png;base64,"));
+        assert!(result.binary_data.is_none());
+
+        let result = encode("base64");
+        assert!(result.image_data.unwrap().starts_with("iVBOR"));
+        assert!(result.binary_data.is_none());
+
+        let result = encode("binary");
+        assert!(result.image_data.is_none());
+        assert!(result.binary_data.unwrap().starts_with(b"\x89PNG"));
+
+        let result = encode("hex");
+        assert!(!result.success);
+        assert!(result.error.unwrap().contains("output_encoding"));
+
+        // output_as_binary keeps populating both fields when no encoding is given
+        let options = ImageProcessingOptions::builder()
+            .operation(Operation::Adjust)
+            .output_as_binary(true)
+            .build();
+        let result = ImageProcessor::process_image(&input, &options);
+        assert!(result.image_data.is_some() && result.binary_data.is_some());
+    }
+
+    #[test]
+    fn test_auto_levels_stretches_low_contrast_gradient() {
+        let input = encode_pattern(64, 4, |x, _| 100 + (x * 50.0) as u8);
+        let options = ImageProcessingOptions::builder()
+            .operation(Operation::Adjust)
+            .auto_levels(true)
+            .build();
+        let result = ImageProcessor::process_image(&input, &options);
+        assert!(result.success, "{:?}", result.error);
+        let output = ImageProcessor::base64_to_photon_image(&result.image_data.unwrap()).unwrap();
+        let pixels = output.get_raw_pixels();
+        let reds: Vec<u8> = pixels.chunks_exact(4).map(|p| p[0]).collect();
+        assert_eq!(reds.iter().min(), Some(&0));
+        assert_eq!(reds.iter().max(), Some(&255));
+
+        // A full-range image passes through unchanged
+        let full_range = encode_pattern(64, 4, |x, _| (x * 64.0 / 63.0 * 255.0).round() as u8);
+        let expected = ImageProcessor::base64_to_photon_image(&full_range).unwrap().get_raw_pixels();
+        let result = ImageProcessor::process_image(&full_range, &options);
+        let output = ImageProcessor::base64_to_photon_image(&result.image_data.unwrap()).unwrap();
+        assert_eq!(output.get_raw_pixels(), expected);
+
+        let options = ImageProcessingOptions { clip_percent: Some(60.0), ..options };
+        assert!(!ImageProcessor::process_image(&input, &options).success);
+    }
+
+    #[test]
+    fn test_white_balance_temperature_shifts_red_and_blue() {
+        let input = solid_image_base64(8, 8, [128, 128, 128, 255]);
+        let channel_means = |options: &ImageProcessingOptions| {
+            let result = ImageProcessor::process_image(&input, options);
+            assert!(result.success, "{:?}", result.error);
+            let output = ImageProcessor::base64_to_photon_image(&result.image_data.unwrap()).unwrap();
+            let pixels = output.get_raw_pixels();
+            let count = (pixels.len() / 4) as f32;
+            let mean = |channel: usize| pixels.chunks_exact(4).map(|p| p[channel] as f32).sum::<f32>() / count;
+            (mean(0), mean(1), mean(2))
+        };
+
+        let warm = ImageProcessingOptions::builder()
+            .operation(Operation::Adjust)
+            .temperature(0.5)
+            .build();
+        let (red, green, blue) = channel_means(&warm);
+        assert!(red > 128.0 && blue < 128.0);
+        assert_eq!(green, 128.0);
+
+        let magenta = ImageProcessingOptions::builder()
+            .operation(Operation::Adjust)
+            .tint(0.5)
+            .build();
+        let (red, green, _) = channel_means(&magenta);
+        assert!(green < 128.0 && red > 128.0);
+
+        let options = ImageProcessingOptions::builder()
+            .operation(Operation::Adjust)
+            .temperature(2.0)
+            .build();
+        assert!(!ImageProcessor::process_image(&input, &options).success);
+    }
+
+    #[test]
+    fn test_trim_removes_uniform_border() {
+        // 5x5 white image with a single red pixel in the center
+        let mut pixels = [255u8, 255, 255, 255].repeat(25);
+        pixels[12 * 4..12 * 4 + 4].copy_from_slice(&[255, 0, 0, 255]);
+        let bytes = ImageProcessor::photon_image_to_bytes(&PhotonImage::new(pixels, 5, 5), "png", None).unwrap();
+        let input = ImageProcessor::bytes_to_base64(&bytes);
+
+        let options = ImageProcessingOptions::builder().operation(Operation::Trim).build();
+        let result = ImageProcessor::process_image(&input, &options);
+        assert!(result.success, "{:?}", result.error);
+        let metadata = result.metadata.unwrap();
+        assert_eq!((metadata.width, metadata.height), (1, 1));
+        let output = ImageProcessor::base64_to_photon_image(&result.image_data.unwrap()).unwrap();
+        assert_eq!(output.get_raw_pixels(), vec![255, 0, 0, 255]);
+
+        // Within tolerance the red pixel counts as border too, so nothing is trimmed
+        let options = ImageProcessingOptions::builder()
+            .operation(Operation::Trim)
+            .trim_tolerance(255)
+            .build();
+        let metadata = ImageProcessor::process_image(&input, &options).metadata.unwrap();
+        assert_eq!((metadata.width, metadata.height), (5, 5));
+    }
+
+    #[test]
+    fn test_metadata_reports_aspect_ratio_and_megapixels() {
+        let input = solid_image_base64(1920, 1080, [0, 0, 0, 255]);
+        let options = ImageProcessingOptions::builder().operation(Operation::Adjust).build();
+        let metadata = ImageProcessor::process_image(&input, &options).metadata.unwrap();
+        assert!((metadata.aspect_ratio - 1.778).abs() < 0.001);
+        assert!((metadata.megapixels - 2.0736).abs() < 0.0001);
+    }
+
+    #[test]
+    fn test_get_image_info_reads_color_layout() {
+        let info = ImageProcessor::get_image_info(&create_test_image_base64()).unwrap();
+        assert_eq!((info.width, info.height), (2, 2));
+        assert_eq!(info.format, "png");
+        assert_eq!(info.color_type.as_deref(), Some("rgba"));
+        assert_eq!(info.bit_depth, Some(8));
+        assert_eq!(info.has_alpha, Some(true));
+
+        let gray = image::GrayImage::from_pixel(3, 1, image::Luma([128]));
+        let mut jpeg = Vec::new();
+        gray.write_to(&mut Cursor::new(&mut jpeg), ImageFormat::Jpeg).unwrap();
+        let info = ImageProcessor::get_image_info(&ImageProcessor::bytes_to_base64(&jpeg)).unwrap();
+        assert_eq!(info.format, "jpeg");
+        assert_eq!(info.color_type.as_deref(), Some("grayscale"));
+        assert_eq!(info.has_alpha, Some(false));
+
+        let error = ImageProcessor::get_image_info("aGVsbG8=").unwrap_err();
+        assert_eq!(error.code(), "load_image");
+    }
+
+    #[test]
+    fn test_metadata_only_skips_encoding() {
+        let image = PhotonImage::new([10u8, 20, 30, 255].repeat(12), 4, 3);
+        let jpeg = ImageProcessor::photon_image_to_bytes(&image, "jpeg", None).unwrap();
+        let options = ImageProcessingOptions::builder()
+            .operation(Operation::Effect)
+            .filter("oil")
+            .metadata_only(true)
+            .build();
+
+        let result = ImageProcessor::process_image(&ImageProcessor::bytes_to_base64(&jpeg), &options);
+        assert!(result.success, "{:?}", result.error);
+        assert!(result.image_data.is_none() && result.binary_data.is_none());
+        let metadata = result.metadata.unwrap();
+        assert_eq!((metadata.width, metadata.height), (4, 3));
+        assert_eq!(metadata.format, "jpeg");
+        assert_eq!(metadata.size_bytes, jpeg.len());
+
+        let result = ImageProcessor::process_image_bytes(&jpeg, &options);
+        assert!(result.image_data.is_none() && result.binary_data.is_none());
+        assert_eq!(result.metadata.unwrap().format, "jpeg");
+    }
+
+    #[test]
+    fn test_strip_metadata_removes_gps_exif() {
+        let read_exif = |bytes: &[u8]| {
+            let mut decoder = image::codecs::jpeg::JpegDecoder::new(Cursor::new(bytes)).unwrap();
+            decoder.exif_metadata().unwrap()
+        };
+        let image = PhotonImage::new([90u8, 120, 150, 255].repeat(16), 4, 4);
+        let jpeg = ImageProcessor::encode(&image, "jpeg", None, Some(&crate::exif::sample_with_gps()), None).unwrap();
+        assert!(crate::exif::has_gps(&read_exif(&jpeg).unwrap()));
+        let settings = JpegSettings { progressive: true, sampling: jpeg_encoder::SamplingFactor::R_4_2_0 };
+        let progressive = ImageProcessor::encode(&image, "jpeg", None, Some(&crate::exif::sample_with_gps()), Some(settings)).unwrap();
+        assert_eq!(read_exif(&progressive), read_exif(&jpeg));
+
+        let options = ImageProcessingOptions::builder()
+            .operation(Operation::Adjust)
+            .output_format("jpeg")
+            .output_encoding("binary")
+            .build();
+        let output = ImageProcessor::process_image_bytes(&jpeg, &options).binary_data.unwrap();
+        assert_eq!(read_exif(&output), None);
+
+        // Keeping metadata forwards the remaining EXIF but never the location
+        let options = ImageProcessingOptions { strip_metadata: Some(false), ..options };
+        let output = ImageProcessor::process_image_bytes(&jpeg, &options).binary_data.unwrap();
+        let exif = read_exif(&output).unwrap();
+        assert!(!exif.is_empty());
+        assert!(!crate::exif::has_gps(&exif));
+    }
+
+    #[test]
+    fn test_normalize_orients_and_strips_exif() {
+        // Stored sideways: left half dark, right half light, EXIF orientation 6 (rotate 90° clockwise)
+        let pixels: Vec<u8> = (0..8).flat_map(|_| (0..16).flat_map(|x| if x < 8 { [20, 20, 20, 255] } else { [230, 230, 230, 255] })).collect();
+        let mut exif = vec![b'I', b'I', 42, 0, 8, 0, 0, 0, 1, 0];
+        exif.extend_from_slice(&[0x12, 0x01, 3, 0, 1, 0, 0, 0, 6, 0, 0, 0, 0, 0, 0, 0]);
+        let jpeg = ImageProcessor::encode(&PhotonImage::new(pixels, 16, 8), "jpeg", Some(95), Some(&exif), None).unwrap();
+
+        let options = ImageProcessingOptions::builder()
+            .operation(Operation::Normalize)
+            .output_format("jpeg")
+            .output_encoding("binary")
+            .strip_metadata(false)
+            .build();
+        let output = ImageProcessor::process_image_bytes(&jpeg, &options).binary_data.unwrap();
+        let mut decoder = image::codecs::jpeg::JpegDecoder::new(Cursor::new(&output)).unwrap();
+        assert_eq!(decoder.exif_metadata().unwrap(), None);
+
+        // Upright it is 8x16 with the dark half on top
+        let upright = ImageProcessor::bytes_to_photon_image(&output).unwrap();
+        assert_eq!((upright.get_width(), upright.get_height()), (8, 16));
+        let pixels = upright.get_raw_pixels();
+        assert!(pixels[(2 * 8 + 4) * 4] < 60);
+        assert!(pixels[(13 * 8 + 4) * 4] > 190);
+    }
+
+    #[test]
+    fn test_max_input_bytes_rejects_before_decoding() {
+        let input = create_test_image_base64();
+        let size = ImageProcessor::base64_to_bytes(&input).unwrap().len();
+        assert_eq!(ImageProcessor::decoded_len(&input), size);
+
+        let options = ImageProcessingOptions::builder().filter("sepia").max_input_bytes(size).build();
+        assert!(ImageProcessor::process_image(&input, &options).success);
+
+        let tight = ImageProcessingOptions { max_input_bytes: Some(size - 1), ..options };
+        let result = ImageProcessor::process_image(&input, &tight);
+        assert_eq!(result.error_code.as_deref(), Some("input_too_large"));
+        // Oversized garbage is rejected by size before base64 decoding can fail
+        let huge = "A".repeat(4 * size);
+        assert_eq!(ImageProcessor::process_image_to_bytes(&huge, &tight).unwrap_err().code(), "input_too_large");
+        let bytes = ImageProcessor::base64_to_bytes(&input).unwrap();
+        assert_eq!(ImageProcessor::process_image_bytes(&bytes, &tight).error_code.as_deref(), Some("input_too_large"));
+
+        // Every other base64 entry point enforces the same limit
+        let mut gif = Vec::new();
+        image::codecs::gif::GifEncoder::new(&mut gif)
+            .encode_frame(image::Frame::new(image::RgbaImage::from_pixel(2, 2, image::Rgba([255, 0, 0, 255]))))
+            .unwrap();
+        let gif = ImageProcessor::bytes_to_base64(&gif);
+        let gif_limit = ImageProcessingOptions { max_input_bytes: Some(ImageProcessor::decoded_len(&gif) - 1), ..tight.clone() };
+        let result = ImageProcessor::process_gif_frames(&gif, &gif_limit);
+        assert_eq!(result.error_code.as_deref(), Some("input_too_large"));
+        let result = ImageProcessor::compose_grid(vec![input.clone()], 1, 2, 2, &tight);
+        assert_eq!(result.error_code.as_deref(), Some("input_too_large"));
+        let blend = ImageProcessingOptions::builder()
+            .operation(Operation::Blend)
+            .blend_base64(huge)
+            .max_input_bytes(size)
+            .build();
+        assert_eq!(ImageProcessor::process_image(&input, &blend).error_code.as_deref(), Some("input_too_large"));
+    }
+
+    #[test]
+    fn test_identity_converts_format() {
+        let options = ImageProcessingOptions::builder()
+            .operation(Operation::Identity)
+            .output_format("jpeg")
+            .build();
+        let (output, metadata) = ImageProcessor::process_image_to_bytes(&create_test_image_base64(), &options).unwrap();
+        assert_eq!(metadata.format, "jpeg");
+        assert_eq!(image::guess_format(&output).unwrap(), ImageFormat::Jpeg);
+        assert_eq!((metadata.width, metadata.height), (2, 2));
+
+        // Lossless output keeps every pixel
+        let png = ImageProcessingOptions { output_format: Some("png".to_string()), ..options };
+        let output = ImageProcessor::process_image_to_bytes(&create_test_image_base64(), &png).unwrap().0;
+        assert_eq!(
+            ImageProcessor::bytes_to_photon_image(&output).unwrap().get_raw_pixels(),
+            ImageProcessor::base64_to_photon_image(&create_test_image_base64()).unwrap().get_raw_pixels(),
+        );
+    }
+
+    #[test]
+    fn test_extract_gps_from_jpeg() {
+        let image = PhotonImage::new([90u8, 120, 150, 255].repeat(16), 4, 4);
+        let jpeg = ImageProcessor::encode(&image, "jpeg", None, Some(&crate::exif::sample_with_gps()), None).unwrap();
+        // 51°30'7"N 0°7'39"W
+        let (latitude, longitude) = ImageProcessor::extract_gps(&ImageProcessor::bytes_to_base64(&jpeg)).unwrap().unwrap();
+        assert!((latitude - 51.501_944).abs() < 1e-5, "{}", latitude);
+        assert!((longitude + 0.1275).abs() < 1e-5, "{}", longitude);
+
+        assert_eq!(ImageProcessor::extract_gps(&create_test_image_base64()).unwrap(), None);
+        assert!(ImageProcessor::extract_gps("not base64!").is_err());
+    }
+
+    #[test]
+    fn test_sobel_magnitude_highlights_boundary() {
+        // Left half black, right half white: the edge sits between columns 3 and 4
+        let input = encode_pattern(8, 8, |x, _| if x < 0.5 { 0 } else { 255 });
+        let options = ImageProcessingOptions::builder()
+            .operation(Operation::Effect)
+            .filter("sobel")
+            .build();
+        let result = ImageProcessor::process_image(&input, &options);
+        assert!(result.success, "{:?}", result.error);
+
+        let output = ImageProcessor::base64_to_photon_image(&result.image_data.unwrap()).unwrap();
+        let pixels = output.get_raw_pixels();
+        let row: Vec<u8> = pixels.chunks_exact(4).take(8).map(|p| p[0]).collect();
+        assert_eq!(row, vec![0, 0, 0, 255, 255, 0, 0, 0]);
+    }
+
+    #[test]
+    fn test_threshold_channel_and_invert() {
+        // Red ramps left to right while green stays at zero, so luma stays dark
+        let pixels: Vec<u8> = (0..8u8).flat_map(|x| [x * 36, 0, 0, 255]).collect();
+        let bytes = ImageProcessor::photon_image_to_bytes(&PhotonImage::new(pixels, 8, 1), "png", None).unwrap();
+        let input = ImageProcessor::bytes_to_base64(&bytes);
+        let threshold = |channel: &str, invert: bool| {
+            let options = ImageProcessingOptions::builder()
+                .operation(Operation::Effect)
+                .filter("threshold")
+                .threshold_channel(channel)
+                .threshold_invert(invert)
+                .build();
+            let result = ImageProcessor::process_image(&input, &options);
+            assert!(result.success, "{:?}", result.error);
+            let output = ImageProcessor::base64_to_photon_image(&result.image_data.unwrap()).unwrap();
+            output.get_raw_pixels().chunks_exact(4).map(|p| p[0]).collect::<Vec<u8>>()
+        };
+
+        assert_eq!(threshold("luma", false), vec![0; 8]);
+        assert_eq!(threshold("r", false), vec![0, 0, 0, 0, 255, 255, 255, 255]);
+        assert_eq!(threshold("r", true), vec![255, 255, 255, 255, 0, 0, 0, 0]);
+
+        let options = ImageProcessingOptions::builder()
+            .operation(Operation::Effect)
+            .filter("threshold")
+            .threshold_channel("alpha")
+            .build();
+        assert!(!ImageProcessor::process_image(&input, &options).success);
+    }
+
+    #[test]
+    fn test_posterize_reduces_levels() {
+        let input = encode_pattern(64, 1, |x, _| (x * 256.0) as u8);
+        let posterize = |levels: u32| {
+            let options = ImageProcessingOptions::builder()
+                .operation(Operation::Effect)
+                .filter("posterize")
+                .levels(levels)
+                .build();
+            let result = ImageProcessor::process_image(&input, &options);
+            assert!(result.success, "{:?}", result.error);
+            let output = ImageProcessor::base64_to_photon_image(&result.image_data.unwrap()).unwrap();
+            let mut values: Vec<u8> = output.get_raw_pixels().chunks_exact(4).map(|p| p[0]).collect();
+            values.dedup();
+            values
+        };
+
+        assert_eq!(posterize(2), vec![0, 255]);
+        assert_eq!(posterize(4), vec![0, 85, 170, 255]);
+
+        let options = ImageProcessingOptions::builder()
+            .operation(Operation::Effect)
+            .filter("posterize")
+            .levels(1)
+            .build();
+        assert!(!ImageProcessor::process_image(&input, &options).success);
+    }
+
+    #[test]
+    fn test_resize_single_dimension_keeps_aspect_ratio() {
+        let input = solid_image_base64(40, 20, [10, 20, 30, 255]);
+        let resize = |width: Option<u32>, height: Option<u32>| {
+            let options = ImageProcessingOptions {
+                operation: Operation::Transform,
+                resize_width: width,
+                resize_height: height,
+                ..Default::default()
+            };
+            let metadata = ImageProcessor::process_image(&input, &options).metadata.unwrap();
+            (metadata.width, metadata.height)
+        };
+
+        assert_eq!(resize(Some(10), None), (10, 5));
+        assert_eq!(resize(None, Some(30)), (60, 30));
+    }
+
+    #[cfg(feature = "svg")]
+    #[test]
+    fn test_svg_input_is_rasterized() {
+        let svg = r##"<svg xmlns="http://www.w3.org/2000/svg" width="10" height="5">
+            <rect width="10" height="5" fill="#ff0000"/>
+        </svg>"##;
+        let input = ImageProcessor::bytes_to_base64(svg.as_bytes());
+
+        let options = ImageProcessingOptions::builder().operation(Operation::Adjust).build();
+        let result = ImageProcessor::process_image(&input, &options);
+        assert!(result.success, "{:?}", result.error);
+        let metadata = result.metadata.unwrap();
+        assert_eq!((metadata.width, metadata.height, metadata.format.as_str()), (10, 5, "png"));
+        let output = ImageProcessor::base64_to_photon_image(&result.image_data.unwrap()).unwrap();
+        assert!(output.get_raw_pixels().chunks_exact(4).all(|p| p == [255, 0, 0, 255]));
+
+        let options = ImageProcessingOptions::builder()
+            .operation(Operation::Adjust)
+            .svg_width(40)
+            .build();
+        let metadata = ImageProcessor::process_image(&input, &options).metadata.unwrap();
+        assert_eq!((metadata.width, metadata.height), (40, 20));
+    }
+
+    #[cfg(not(feature = "svg"))]
+    #[test]
+    fn test_svg_input_requires_feature() {
+        let input = ImageProcessor::bytes_to_base64(b"<svg xmlns=\"http://www.w3.org/2000/svg\"/>");
+        let result = ImageProcessor::process_image(&input, &ImageProcessingOptions::default());
+        assert!(result.error.unwrap().contains("svg"));
+    }
+
+    #[test]
+    fn test_parse_aspect_ratio() {
+        assert_eq!(parse_aspect_ratio("16:9").unwrap(), 16.0 / 9.0);
+        assert_eq!(parse_aspect_ratio(" 4 / 3 ").unwrap(), 4.0 / 3.0);
+        assert_eq!(parse_aspect_ratio("2.39x1").unwrap(), 2.39);
+        assert_eq!(parse_aspect_ratio("1.5").unwrap(), 1.5);
+        for invalid in ["", "16:", "0:9", "16:0", "-1:1", "wide"] {
+            assert!(parse_aspect_ratio(invalid).is_err(), "{}", invalid);
+        }
+    }
+
+    #[test]
+    fn test_chroma_key_removes_green_background() {
+        // Red 2x2 square centred on a 4x4 green background, with slightly noisy green
+        let mut pixels = Vec::new();
+        for y in 0..4 {
+            for x in 0..4 {
+                let square = (1..3).contains(&x) && (1..3).contains(&y);
+                pixels.extend_from_slice(if square { &[255, 0, 0, 255] } else { &[10, 245, 5, 255] });
+            }
+        }
+        let bytes = ImageProcessor::photon_image_to_bytes(&PhotonImage::new(pixels, 4, 4), "png", None).unwrap();
+        let options = ImageProcessingOptions::builder()
+            .operation(Operation::ChromaKey)
+            .output_format("jpeg")
+            .build();
+
+        let result = ImageProcessor::process_image(&ImageProcessor::bytes_to_base64(&bytes), &options);
+        assert!(result.success, "{:?}", result.error);
+        assert!(result.warnings.iter().any(|warning| warning.contains("png was used")));
+        let output = ImageProcessor::base64_to_bytes(result.image_data.as_deref().unwrap()).unwrap();
+        let keyed = image::load_from_memory_with_format(&output, ImageFormat::Png).unwrap().to_rgba8();
+        for (x, y, pixel) in keyed.enumerate_pixels() {
+            let square = (1..3).contains(&x) && (1..3).contains(&y);
+            assert_eq!(pixel[3], if square { 255 } else { 0 }, "alpha at ({}, {})", x, y);
+        }
+
+        let strict = ImageProcessingOptions { key_tolerance: Some(0), ..options };
+        let output = ImageProcessor::process_image_to_bytes(&ImageProcessor::bytes_to_base64(&bytes), &strict).unwrap().0;
+        assert!(image::load_from_memory(&output).unwrap().to_rgba8().pixels().all(|pixel| pixel[3] == 255));
+    }
+
+    #[test]
+    fn test_luma_alpha_clears_white_background() {
+        // Black logo pixels on a near-white background
+        let pixels: Vec<u8> = [[0, 0, 0, 255], [250, 250, 248, 255], [255, 255, 255, 255], [128, 128, 128, 255]].concat();
+        let input = ImageProcessor::bytes_to_base64(
+            &ImageProcessor::photon_image_to_bytes(&PhotonImage::new(pixels, 2, 2), "png", None).unwrap(),
+        );
+        let alphas = |options: &ImageProcessingOptions| {
+            let output = ImageProcessor::process_image_to_bytes(&input, options).unwrap().0;
+            image::load_from_memory(&output).unwrap().to_rgba8().pixels().map(|pixel| pixel[3]).collect::<Vec<_>>()
+        };
+
+        let options = ImageProcessingOptions::builder().operation(Operation::LumaAlpha).build();
+        assert_eq!(alphas(&options), [255, 0, 0, 255]);
+
+        let dark = ImageProcessingOptions { luma_threshold: Some(128), luma_above: Some(false), ..options };
+        assert_eq!(alphas(&dark), [0, 255, 255, 0]);
+    }
+
+    #[cfg(feature = "tracing")]
+    #[tracing_test::traced_test]
+    #[test]
+    fn test_filter_emits_tracing_span() {
+        let options = ImageProcessingOptions::builder().filter("sepia").build();
+        assert!(ImageProcessor::process_image(&create_test_image_base64(), &options).success);
+        assert!(logs_contain("process_image{operation=\"filter\"}"));
+        assert!(logs_contain("render{operation=\"filter\" width=2 height=2}"));
+        assert!(logs_contain("rendered image"));
+    }
+
+    #[test]
+    fn test_results_are_byte_stable_without_timing() {
+        let input = create_test_image_base64();
+        let options = ImageProcessingOptions::builder().filter("sepia").build();
+        let run = || serde_json::to_string(&ImageProcessor::process_image(&input, &options)).unwrap();
+        assert_eq!(run(), run());
+
+        let batch = ImageProcessor::process_batch(vec![input.clone(), input.clone()], &options);
+        assert_eq!(batch.total_time_ms, 0);
+
+        let timed = ImageProcessingOptions { include_timing: Some(true), ..options.clone() };
+        assert!(ImageProcessor::process_image(&input, &timed).success);
+    }
+
+    #[test]
+    fn test_blur_regions_leaves_rest_intact() {
+        // Checkerboard so a blur visibly changes every pixel it touches
+        let input = encode_pattern(4, 4, |x, y| if ((x + y) * 4.0) as u32 & 1 == 0 { 0 } else { 255 });
+        let before = ImageProcessor::bytes_to_photon_image(&ImageProcessor::base64_to_bytes(&input).unwrap())
+            .unwrap()
+            .get_raw_pixels();
+        let options = ImageProcessingOptions::builder()
+            .operation(Operation::BlurRegions)
+            .regions(vec![[0, 0, 2, 2]])
+            .build();
+        let output = ImageProcessor::process_image_to_bytes(&input, &options).unwrap().0;
+        let after = ImageProcessor::bytes_to_photon_image(&output).unwrap().get_raw_pixels();
+
+        for (index, (old, new)) in before.chunks_exact(4).zip(after.chunks_exact(4)).enumerate() {
+            let (x, y) = (index % 4, index / 4);
+            if x < 2 && y < 2 {
+                assert_ne!(old, new, "pixel ({}, {}) should be blurred", x, y);
+            } else {
+                assert_eq!(old, new, "pixel ({}, {}) should be untouched", x, y);
+            }
+        }
+
+        for region in [[3, 3, 2, 1], [0, 0, 0, 2]] {
+            let invalid = ImageProcessingOptions { regions: Some(vec![region]), ..options.clone() };
+            assert_eq!(ImageProcessor::process_image_to_bytes(&input, &invalid).unwrap_err().code(), "invalid_parameter");
+        }
+    }
+
+    #[test]
+    fn test_canvas_pads_without_scaling() {
+        let source = [[255, 0, 0, 255], [0, 255, 0, 255], [0, 0, 255, 255], [10, 20, 30, 255]].concat();
+        let input = ImageProcessor::bytes_to_base64(
+            &ImageProcessor::photon_image_to_bytes(&PhotonImage::new(source.clone(), 2, 2), "png", None).unwrap(),
+        );
+        let render = |options: &ImageProcessingOptions| {
+            let output = ImageProcessor::process_image_to_bytes(&input, options).unwrap().0;
+            image::load_from_memory(&output).unwrap().to_rgba8()
+        };
+        let options = ImageProcessingOptions::builder()
+            .operation(Operation::Canvas)
+            .canvas_width(4)
+            .canvas_height(4)
+            .canvas_color("#ffffff")
+            .build();
+
+        let padded = render(&options);
+        assert_eq!(padded.dimensions(), (4, 4));
+        for (x, y, pixel) in padded.enumerate_pixels() {
+            let expected = if (1..3).contains(&x) && (1..3).contains(&y) {
+                let index = ((y - 1) * 2 + (x - 1)) as usize * 4;
+                [source[index], source[index + 1], source[index + 2], source[index + 3]]
+            } else {
+                [255, 255, 255, 255]
+            };
+            assert_eq!(pixel.0, expected, "pixel at ({}, {})", x, y);
+        }
+
+        // A smaller canvas anchored bottom-right keeps only the last pixel
+        let clipped = ImageProcessingOptions {
+            canvas_width: Some(1),
+            canvas_height: Some(1),
+            anchor: Some("bottom_right".to_string()),
+            ..options
+        };
+        assert_eq!(render(&clipped).get_pixel(0, 0).0, [10, 20, 30, 255]);
+    }
+
+    #[test]
+    fn test_crop_aspect_center_crops_square() {
+        // Rows/columns are numbered by their red/green value so the kept region is visible
+        let pixels = (0..64u32 * 64)
+            .flat_map(|i| [(i / 64) as u8, (i % 64) as u8, 0, 255])
+            .collect();
+        let png = ImageProcessor::photon_image_to_bytes(&PhotonImage::new(pixels, 64, 64), "png", None).unwrap();
+        let input = ImageProcessor::bytes_to_base64(&png);
+
+        let crop = |ratio: &str| {
+            let options = ImageProcessingOptions::builder()
+                .operation(Operation::CropAspect)
+                .target_aspect(ratio)
+                .build();
+            let result = ImageProcessor::process_image(&input, &options);
+            ImageProcessor::base64_to_photon_image(&result.image_data.unwrap()).unwrap()
+        };
+
+        // 16:9 keeps the full width and trims top and bottom
+        let wide = crop("16:9");
+        assert_eq!((wide.get_width(), wide.get_height()), (64, 36));
+        assert_eq!(&wide.get_raw_pixels()[..2], &[14, 0]);
+
+        // 9:16 keeps the full height and trims the sides
+        let tall = crop("9:16");
+        assert_eq!((tall.get_width(), tall.get_height()), (36, 64));
+        assert_eq!(&tall.get_raw_pixels()[..2], &[0, 14]);
+
+        let options = ImageProcessingOptions::builder().operation(Operation::CropAspect).build();
+        assert!(ImageProcessor::process_image(&input, &options).error.unwrap().contains("target_aspect"));
+    }
+
+    #[test]
+    fn test_ignored_and_clamped_adjustments_warn() {
+        let input = create_test_image_base64();
+        let options = ImageProcessingOptions::builder()
+            .operation(Operation::Adjust)
+            .contrast(1.5)
+            .brightness(10.0)
+            .build();
+        let result = ImageProcessor::process_image(&input, &options);
         assert!(result.success);
-        assert!(result.metadata.is_some());
-        
-        if let Some(metadata) = result.metadata {
-            assert_eq!(metadata.width, 4);
-            assert_eq!(metadata.height, 4);
+        assert_eq!(result.warnings.len(), 2);
+        assert!(result.warnings[0].contains("clamped"));
+        assert!(result.warnings[1].contains("Contrast"));
+
+        let options = ImageProcessingOptions::builder().operation(Operation::Adjust).brightness(1.2).build();
+        assert!(ImageProcessor::process_image(&input, &options).warnings.is_empty());
+    }
+
+    #[test]
+    fn test_grayscale_modes_differ_for_saturated_color() {
+        let png = ImageProcessor::photon_image_to_bytes(&PhotonImage::new(vec![0, 255, 0, 255], 1, 1), "png", None).unwrap();
+        let input = ImageProcessor::bytes_to_base64(&png);
+        let gray = |mode: &str| {
+            let options = ImageProcessingOptions::builder().filter("grayscale").grayscale_mode(mode).build();
+            let result = ImageProcessor::process_image(&input, &options);
+            let pixels = ImageProcessor::base64_to_photon_image(&result.image_data.unwrap()).unwrap().get_raw_pixels();
+            assert_eq!(pixels[0], pixels[2]);
+            pixels[0]
+        };
+
+        assert_eq!(gray("average"), 85);
+        assert_eq!(gray("luminance"), 182);
+        assert_eq!(gray("lightness"), 128);
+        assert_eq!(gray("single_channel_g"), 255);
+        assert_eq!(gray("single_channel_r"), 0);
+
+        let options = ImageProcessingOptions::builder().filter("grayscale").grayscale_mode("hsl").build();
+        assert!(ImageProcessor::process_image(&input, &options).error.unwrap().contains("grayscale mode"));
+    }
+
+    #[test]
+    fn test_parse_color() {
+        assert_eq!(parse_color("#f00").unwrap(), [255, 0, 0, 255]);
+        assert_eq!(parse_color("00ff0080").unwrap(), [0, 255, 0, 128]);
+        assert_eq!(parse_color("rgb(1, 2, 3)").unwrap(), [1, 2, 3, 255]);
+        assert_eq!(parse_color(" rgba(10,20,30,0.5) ").unwrap(), [10, 20, 30, 128]);
+        for invalid in ["", "#12345", "rgb(1,2)", "rgba(1,2,3,2)", "rgb(256,0,0)", "red"] {
+            assert!(parse_color(invalid).is_err(), "{}", invalid);
         }
     }
-}
 
-impl Default for ImageProcessingOptions {
-    fn default() -> Self {
-        Self {
-            operation: "filter".to_string(),
-            filter: None,
-            intensity: None,
-            brightness: None,
-            contrast: None,
-            saturation: None,
-            hue_rotation: None,
-            resize_width: None,
-            resize_height: None,
-            keep_aspect_ratio: None,
-            crop_x: None,
-            crop_y: None,
-            crop_width: None,
-            crop_height: None,
-            rotation_angle: None,
-            flip_horizontal: None,
-            flip_vertical: None,
-            output_format: None,
-            quality: None,
-            output_as_binary: None,
+    #[test]
+    fn test_create_solid_image() {
+        let result = ImageProcessor::create_solid_image(2, 2, "#ff0000", "png");
+        assert!(result.success);
+        let metadata = result.metadata.unwrap();
+        assert_eq!((metadata.width, metadata.height, metadata.format.as_str()), (2, 2, "png"));
+        let image = ImageProcessor::base64_to_photon_image(&result.image_data.unwrap()).unwrap();
+        assert!(image.get_raw_pixels().chunks_exact(4).all(|p| p == [255, 0, 0, 255]));
+
+        assert!(!ImageProcessor::create_solid_image(0, 2, "#ff0000", "png").success);
+        assert!(ImageProcessor::create_solid_image(100_000, 100_000, "#ff0000", "png").error.unwrap().contains("exceed the limit"));
+        assert!(ImageProcessor::create_solid_image(2, 2, "crimson", "png").error.unwrap().contains("Invalid color"));
+        assert!(!ImageProcessor::create_solid_image(2, 2, "#ff0000", "tiff").success);
+    }
+
+    #[test]
+    fn test_batch_fail_fast_stops_at_first_failure() {
+        let mut images = vec![create_test_image_base64(); 5];
+        images[1] = "not an image".to_string();
+
+        let options = ImageProcessingOptions::builder().filter("grayscale").fail_fast(true).build();
+        let result = ImageProcessor::process_batch(images.clone(), &options);
+        assert_eq!(result.results.len(), 2);
+        assert_eq!((result.processed, result.successful, result.failed), (2, 1, 1));
+
+        let mut progress = Vec::new();
+        let result = ImageProcessor::process_batch_with_progress(images.clone(), &options, |index, _, _| progress.push(index));
+        assert_eq!((result.results.len(), progress), (2, vec![0, 1]));
+
+        let options = ImageProcessingOptions::builder().filter("grayscale").build();
+        assert_eq!(ImageProcessor::process_batch(images, &options).results.len(), 5);
+    }
+
+    #[test]
+    fn test_no_upscale_skips_enlargement() {
+        let png = ImageProcessor::photon_image_to_bytes(&PhotonImage::new(vec![128; 10 * 10 * 4], 10, 10), "png", None).unwrap();
+        let input = ImageProcessor::bytes_to_base64(&png);
+        let resize = |width: u32, height: u32, no_upscale: bool| {
+            let options = ImageProcessingOptions::builder()
+                .operation(Operation::Transform)
+                .resize_width(width)
+                .resize_height(height)
+                .no_upscale(no_upscale)
+                .build();
+            ImageProcessor::process_image(&input, &options)
+        };
+
+        let skipped = resize(100, 100, true);
+        let metadata = skipped.metadata.unwrap();
+        assert_eq!((metadata.width, metadata.height), (10, 10));
+        assert_eq!(skipped.warnings.len(), 1);
+        assert!(skipped.warnings[0].contains("no_upscale"));
+
+        let shrunk = resize(5, 5, true);
+        assert_eq!(shrunk.metadata.unwrap().width, 5);
+        assert!(shrunk.warnings.is_empty());
+        assert_eq!(resize(100, 100, false).metadata.unwrap().width, 100);
+    }
+
+    #[test]
+    fn test_loaded_image_chains_operations() {
+        let loaded = ImageProcessor::from_base64(&create_test_image_base64()).unwrap();
+        let original = loaded.image().get_raw_pixels();
+
+        let loaded = loaded.filter("invert").unwrap().resize(3, 2).unwrap().adjust(1.0, 1.0).unwrap();
+        assert_eq!((loaded.image().get_width(), loaded.image().get_height()), (3, 2));
+        assert_eq!(loaded.image().get_raw_pixels()[0], 255 - original[0]);
+        assert!(loaded.warnings().is_empty());
+
+        let png = loaded.encode("png", None).unwrap();
+        let decoded = ImageProcessor::bytes_to_photon_image(&png).unwrap();
+        assert_eq!(decoded.get_raw_pixels(), loaded.image().get_raw_pixels());
+
+        assert!(ImageProcessor::from_base64("not an image").is_err());
+        let loaded = ImageProcessor::from_base64(&create_test_image_base64()).unwrap();
+        assert!(loaded.filter("glow").is_err());
+    }
+
+    #[test]
+    fn test_supported_output_formats() {
+        let formats = ImageProcessor::supported_output_formats();
+        assert!(formats.contains(&"png"));
+        assert!(formats.contains(&"jpeg"));
+        assert!(formats.iter().all(|format| KNOWN_OUTPUT_FORMATS.contains(format)));
+        assert_eq!(formats.contains(&"avif"), cfg!(feature = "avif"));
+
+        // Every listed format really encodes
+        let probe = PhotonImage::new(vec![0, 0, 0, 255], 1, 1);
+        for format in formats {
+            assert!(ImageProcessor::photon_image_to_bytes(&probe, format, None).is_ok(), "{}", format);
+        }
+    }
+
+    #[cfg(feature = "avif")]
+    #[test]
+    fn test_avif_output() {
+        let options = ImageProcessingOptions::builder().filter("invert").output_format("avif").quality(60).build();
+        let (bytes, metadata) = ImageProcessor::process_image_to_bytes(&create_test_image_base64(), &options).unwrap();
+        assert_eq!(image::guess_format(&bytes).unwrap(), image::ImageFormat::Avif);
+        assert_eq!((metadata.format.as_str(), metadata.size_bytes), ("avif", bytes.len()));
+        assert!(ImageProcessor::supported_output_formats().contains(&"avif"));
+    }
+
+    #[cfg(not(feature = "avif"))]
+    #[test]
+    fn test_avif_requires_feature() {
+        assert!(!ImageProcessor::supported_output_formats().contains(&"avif"));
+    }
+
+    #[test]
+    fn test_validate_options_flags_fields_unused_by_operation() {
+        let options = ImageProcessingOptions::builder()
+            .filter("sepia")
+            .resize_width(10)
+            .flip_horizontal(true)
+            .build();
+        let error = validate_options(&options).unwrap_err();
+        assert_eq!(error, "Options ignored by the 'filter' operation: resize_width, flip_horizontal");
+
+        let result = ImageProcessor::process_image(&create_test_image_base64(), &options);
+        assert!(result.success);
+        assert_eq!(result.warnings, vec![error]);
+
+        let options = ImageProcessingOptions::builder()
+            .operation(Operation::Transform)
+            .resize_width(10)
+            .crop(0, 0, 1, 1)
+            .build();
+        assert!(validate_options(&options).is_ok());
+    }
+
+    #[test]
+    fn test_process_gif_frames_keeps_frames_and_delays() {
+        use image::codecs::gif::{GifDecoder, GifEncoder};
+        use image::{AnimationDecoder, Delay, Frame};
+
+        let frame = |color: [u8; 4], millis: u32| {
+            let buffer = image::RgbaImage::from_pixel(4, 4, image::Rgba(color));
+            Frame::from_parts(buffer, 0, 0, Delay::from_numer_denom_ms(millis, 1))
+        };
+        let mut gif = Vec::new();
+        GifEncoder::new(&mut gif)
+            .encode_frames([frame([255, 0, 0, 255], 100), frame([0, 0, 255, 255], 200)])
+            .unwrap();
+
+        let options = ImageProcessingOptions::builder().filter("grayscale").output_encoding("binary").build();
+        let result = ImageProcessor::process_gif_frames(&ImageProcessor::bytes_to_base64(&gif), &options);
+        assert!(result.success, "{:?}", result.error);
+        assert_eq!(result.metadata.unwrap().format, "gif");
+
+        let frames = GifDecoder::new(Cursor::new(result.binary_data.unwrap()))
+            .unwrap()
+            .into_frames()
+            .collect_frames()
+            .unwrap();
+        assert_eq!(frames.len(), 2);
+        let delays: Vec<_> = frames.iter().map(|frame| frame.delay().numer_denom_ms()).collect();
+        assert_eq!(delays, vec![(100, 1), (200, 1)]);
+        for frame in &frames {
+            let pixel = frame.buffer().get_pixel(0, 0);
+            assert!(pixel[0].abs_diff(pixel[1]) <= 2 && pixel[1].abs_diff(pixel[2]) <= 2, "{:?}", pixel);
         }
+
+        assert!(!ImageProcessor::process_gif_frames(&create_test_image_base64(), &options).success);
+
+        // Per-frame warnings are reported once, and unusable output settings are surfaced
+        let gif = ImageProcessor::bytes_to_base64(&gif);
+        let options = ImageProcessingOptions::builder().filter("sepia").intensity(5.0).max_bytes(1000).build();
+        let result = ImageProcessor::process_gif_frames(&gif, &options);
+        assert!(result.success, "{:?}", result.error);
+        assert_eq!(result.warnings, vec![
+            "Options ignored for animated GIF output: max_bytes".to_string(),
+            "Filter intensity 5 is outside 0.0-2.0 and was clamped to 2".to_string(),
+        ]);
+
+        let gif_out = ImageProcessingOptions::builder().filter("sepia").output_format("gif").build();
+        assert!(ImageProcessor::process_gif_frames(&gif, &gif_out).success);
+        let png_out = ImageProcessingOptions::builder().filter("sepia").output_format("png").build();
+        let result = ImageProcessor::process_gif_frames(&gif, &png_out);
+        assert_eq!(result.error_code.as_deref(), Some("unsupported_format"));
+    }
+
+    #[test]
+    fn test_batch_results_carry_input_index() {
+        let mut images = vec![create_test_image_base64(); 6];
+        images[3] = "not an image".to_string();
+
+        let options = ImageProcessingOptions::builder().filter("sepia").build();
+        let result = ImageProcessor::process_batch(images.clone(), &options);
+        let indices: Vec<usize> = result.results.iter().map(|result| result.index).collect();
+        assert_eq!(indices, (0..6).collect::<Vec<_>>());
+        assert!(!result.results[3].success);
+
+        let per_image = (0..6).map(|_| ImageProcessingOptions::builder().filter("sepia").build()).collect();
+        let with_options = ImageProcessor::process_batch_with_options(images.clone(), per_image).unwrap();
+        assert_eq!(with_options.results[5].index, 5);
+        let with_progress = ImageProcessor::process_batch_with_progress(images, &options, |_, _, _| {});
+        assert_eq!(with_progress.results[4].index, 4);
+    }
+
+    #[test]
+    fn test_base64_tolerates_line_wrapping() {
+        let base64 = ImageProcessor::bytes_to_base64(&ImageProcessor::base64_to_bytes(&create_test_image_base64()).unwrap());
+        let wrapped: Vec<&str> = base64.as_bytes().chunks(76).map(|line| std::str::from_utf8(line).unwrap()).collect();
+        let wrapped = format!("  {}\r\n", wrapped.join("\r\n"));
+        assert_eq!(
+            ImageProcessor::base64_to_bytes(&wrapped).unwrap(),
+            ImageProcessor::base64_to_bytes(&base64).unwrap()
+        );
+        let options = ImageProcessingOptions::builder().filter("sepia").build();
+        let result = ImageProcessor::process_image(&format!("data:image/png;base64,{}", wrapped), &options);
+        assert!(result.success, "{:?}", result.error);
+    }
+
+    #[test]
+    fn test_base64_rejects_plainly_invalid_input() {
+        let error = ImageProcessor::base64_to_bytes("this is {not} base64!").unwrap_err();
+        assert_eq!(error.code(), "decode_base64");
+        assert!(error.to_string().contains("does not look like base64 or a data URL"));
     }
-}
\ No newline at end of file
+
+    #[test]
+    fn test_url_safe_base64_input_and_output() {
+        // Bytes chosen so the two alphabets differ: standard "+/+/", URL-safe "-_-_"
+        let bytes = [0xfb, 0xff, 0xbf];
+        assert_eq!(ImageProcessor::base64_to_bytes("-_-_").unwrap(), bytes);
+        assert_eq!(ImageProcessor::base64_to_bytes("+/+/").unwrap(), bytes);
+        assert!(ImageProcessor::base64_to_bytes_with_variant("-_-_", Some("standard")).is_err());
+        assert!(ImageProcessor::base64_to_bytes_with_variant("AAAA", Some("base32")).is_err());
+
+        let png = ImageProcessor::base64_to_bytes(&create_test_image_base64()).unwrap();
+        let url_safe = ImageProcessor::bytes_to_base64_url_safe(&png).trim_end_matches('=').to_string();
+        let options = ImageProcessingOptions::builder()
+            .filter("invert")
+            .output_encoding("base64")
+            .base64_variant("url_safe")
+            .build();
+        let result = ImageProcessor::process_image(&url_safe, &options);
+        assert!(result.success, "{:?}", result.error);
+        let output = result.image_data.unwrap();
+        assert!(!output.contains(['+', '/']));
+        assert!(ImageProcessor::base64_to_bytes_with_variant(&output, Some("url_safe")).is_ok());
+    }
+
+    #[test]
+    fn test_estimate_batch_memory_reads_headers() {
+        let encode = |width: u32, height: u32| {
+            let image = PhotonImage::new(vec![0; (width * height * 4) as usize], width, height);
+            ImageProcessor::bytes_to_base64(&ImageProcessor::photon_image_to_bytes(&image, "png", None).unwrap())
+        };
+        let images = vec![encode(30, 20), encode(7, 5), "not an image".to_string()];
+        assert_eq!(ImageProcessor::estimate_batch_memory(&images), (30 * 20 + 7 * 5) * 4);
+        assert_eq!(ImageProcessor::image_dimensions(&ImageProcessor::base64_to_bytes(&images[0]).unwrap()).unwrap(), (30, 20));
+        assert_eq!(ImageProcessor::estimate_batch_memory(&[]), 0);
+    }
+
+    #[test]
+    fn test_compose_grid_with_partial_row() {
+        let solid = |color: &str, width: u32, height: u32| {
+            let image = ImageProcessor::solid_image(width, height, color).unwrap();
+            ImageProcessor::bytes_to_base64(&ImageProcessor::photon_image_to_bytes(&image, "png", None).unwrap())
+        };
+        let images = vec![solid("#ff0000", 8, 8), solid("#00ff00", 16, 8), solid("#0000ff", 4, 4)];
+        let options = ImageProcessingOptions::builder().output_encoding("binary").build();
+
+        let result = ImageProcessor::compose_grid(images.clone(), 2, 10, 10, &options);
+        assert!(result.success, "{:?}", result.error);
+        let metadata = result.metadata.unwrap();
+        assert_eq!((metadata.width, metadata.height), (20, 20));
+
+        let grid = image::load_from_memory(&result.binary_data.unwrap()).unwrap().to_rgba8();
+        assert_eq!(grid.get_pixel(5, 5).0, [255, 0, 0, 255]);
+        // The 2:1 image is letterboxed to 10x5 in the centre of its cell
+        assert_eq!(grid.get_pixel(15, 5).0, [0, 255, 0, 255]);
+        assert_eq!(grid.get_pixel(15, 1).0, [0, 0, 0, 0]);
+        assert_eq!(grid.get_pixel(5, 15).0, [0, 0, 255, 255]);
+        // The empty cell of the final row stays transparent
+        assert_eq!(grid.get_pixel(15, 15).0, [0, 0, 0, 0]);
+
+        assert!(!ImageProcessor::compose_grid(images.clone(), 0, 10, 10, &options).success);
+        assert!(!ImageProcessor::compose_grid(Vec::new(), 2, 10, 10, &options).success);
+        let capped = ImageProcessingOptions::builder().max_pixels(100).build();
+        assert!(ImageProcessor::compose_grid(images, 2, 10, 10, &capped).error.unwrap().contains("exceed"));
+    }
+}
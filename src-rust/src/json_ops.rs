@@ -0,0 +1,655 @@
+//! Structural transforms on JSON values
+
+use serde::de::{DeserializeSeed, Deserializer, SeqAccess, Visitor};
+use serde_json::{Map, Number, Value};
+use std::cmp::Ordering;
+use std::fmt;
+
+/// Call `f` with the index and value of each element of the top-level JSON
+/// array in `input`, deserializing one element at a time so the whole array
+/// is never held in memory. Returns the number of elements visited; an error
+/// from `f` stops the stream and is returned unchanged.
+pub fn for_each_element(
+    input: &str,
+    f: impl FnMut(usize, Value) -> Result<(), String>,
+) -> Result<usize, String> {
+    let mut callback_error = None;
+    let mut deserializer = serde_json::Deserializer::from_str(input);
+    let visited = ElementStream { f, callback_error: &mut callback_error }
+        .deserialize(&mut deserializer)
+        .and_then(|count| deserializer.end().map(|_| count));
+
+    match (visited, callback_error) {
+        (_, Some(error)) => Err(error),
+        (Ok(count), None) => Ok(count),
+        (Err(e), None) => Err(format!("Parse error: {}", e)),
+    }
+}
+
+/// Sequence visitor feeding array elements to a callback as they are parsed
+struct ElementStream<'a, F> {
+    f: F,
+    callback_error: &'a mut Option<String>,
+}
+
+impl<'de, F: FnMut(usize, Value) -> Result<(), String>> DeserializeSeed<'de> for ElementStream<'_, F> {
+    type Value = usize;
+
+    fn deserialize<D: Deserializer<'de>>(self, deserializer: D) -> Result<usize, D::Error> {
+        deserializer.deserialize_seq(self)
+    }
+}
+
+impl<'de, F: FnMut(usize, Value) -> Result<(), String>> Visitor<'de> for ElementStream<'_, F> {
+    type Value = usize;
+
+    fn expecting(&self, formatter: &mut fmt::Formatter) -> fmt::Result {
+        formatter.write_str("a JSON array")
+    }
+
+    fn visit_seq<A: SeqAccess<'de>>(mut self, mut seq: A) -> Result<usize, A::Error> {
+        let mut count = 0;
+        while let Some(element) = seq.next_element::<Value>()? {
+            if let Err(error) = (self.f)(count, element) {
+                *self.callback_error = Some(error);
+                return Err(serde::de::Error::custom("stopped by callback"));
+            }
+            count += 1;
+        }
+        Ok(count)
+    }
+}
+
+/// Convert every number to its native `i64`/`u64`/`f64` form.
+///
+/// Numbers are parsed with arbitrary precision, so `1.50` and integers wider
+/// than 64 bits keep their exact source text. This restores the standard
+/// representation, rounding anything that does not fit a 64-bit integer to
+/// the nearest `f64`.
+pub fn normalize_numbers(value: &mut Value) {
+    match value {
+        Value::Number(number) => {
+            let normalized = if let Some(n) = number.as_u64() {
+                Number::from(n)
+            } else if let Some(n) = number.as_i64() {
+                Number::from(n)
+            } else {
+                number.as_f64().and_then(Number::from_f64).unwrap_or_else(|| number.clone())
+            };
+            *number = normalized;
+        }
+        Value::Array(items) => items.iter_mut().for_each(normalize_numbers),
+        Value::Object(object) => object.values_mut().for_each(normalize_numbers),
+        _ => {}
+    }
+}
+
+/// Apply an RFC 7386 JSON Merge Patch to `target` in place.
+///
+/// Object patches merge recursively and `null` members delete the matching
+/// key; any other patch value replaces the target wholesale.
+pub fn merge_patch(target: &mut Value, patch: &Value) {
+    let Value::Object(patch) = patch else {
+        *target = patch.clone();
+        return;
+    };
+
+    if !target.is_object() {
+        *target = Value::Object(Map::new());
+    }
+    if let Value::Object(object) = target {
+        for (key, value) in patch {
+            if value.is_null() {
+                object.shift_remove(key);
+            } else {
+                merge_patch(object.entry(key.clone()).or_insert(Value::Null), value);
+            }
+        }
+    }
+}
+
+/// Compute a structural diff from `before` to `after`.
+///
+/// Each entry is `{op, path, old, new}` where `op` is `added`, `removed` or
+/// `changed`, `path` is an RFC 6901 JSON Pointer, and `old`/`new` are `null`
+/// on the side where the value is absent. Objects are compared key by key
+/// and arrays index by index.
+pub fn diff_json(before: &Value, after: &Value) -> Vec<Value> {
+    let mut entries = Vec::new();
+    diff_into(String::new(), before, after, &mut entries);
+    entries
+}
+
+fn diff_into(path: String, before: &Value, after: &Value, entries: &mut Vec<Value>) {
+    match (before, after) {
+        (Value::Object(old), Value::Object(new)) => {
+            for (key, old_value) in old {
+                let child = format!("{}/{}", path, escape_pointer_token(key));
+                match new.get(key) {
+                    Some(new_value) => diff_into(child, old_value, new_value, entries),
+                    None => entries.push(diff_entry("removed", child, old_value, &Value::Null)),
+                }
+            }
+            for (key, new_value) in new {
+                if !old.contains_key(key) {
+                    let child = format!("{}/{}", path, escape_pointer_token(key));
+                    entries.push(diff_entry("added", child, &Value::Null, new_value));
+                }
+            }
+        }
+        (Value::Array(old), Value::Array(new)) => {
+            for index in 0..old.len().max(new.len()) {
+                let child = format!("{}/{}", path, index);
+                match (old.get(index), new.get(index)) {
+                    (Some(old_value), Some(new_value)) => {
+                        diff_into(child, old_value, new_value, entries)
+                    }
+                    (Some(old_value), None) => {
+                        entries.push(diff_entry("removed", child, old_value, &Value::Null))
+                    }
+                    (None, Some(new_value)) => {
+                        entries.push(diff_entry("added", child, &Value::Null, new_value))
+                    }
+                    (None, None) => {}
+                }
+            }
+        }
+        _ if before != after => entries.push(diff_entry("changed", path, before, after)),
+        _ => {}
+    }
+}
+
+fn diff_entry(op: &str, path: String, old: &Value, new: &Value) -> Value {
+    serde_json::json!({"op": op, "path": path, "old": old, "new": new})
+}
+
+/// Set the value at an RFC 6901 JSON Pointer.
+///
+/// The final token may name a new object key, or for arrays an existing
+/// index, the next index or `-` to append. Missing intermediate containers
+/// are created as empty objects only when `create_missing` is set.
+pub fn pointer_set(
+    root: &mut Value,
+    pointer: &str,
+    value: Value,
+    create_missing: bool,
+) -> Result<(), String> {
+    let tokens = parse_pointer(pointer)?;
+    let Some((last, parents)) = tokens.split_last() else {
+        *root = value;
+        return Ok(());
+    };
+
+    let mut target = root;
+    for token in parents {
+        target = match target {
+            Value::Object(object) => {
+                if !create_missing && !object.contains_key(token) {
+                    return Err(format!("Path '{}' does not exist at '{}'", pointer, token));
+                }
+                object.entry(token.clone()).or_insert_with(|| Value::Object(Map::new()))
+            }
+            Value::Array(items) => {
+                let index = array_index(token, items.len())?;
+                &mut items[index]
+            }
+            _ => return Err(format!("Cannot descend into a scalar at '{}'", token)),
+        };
+    }
+
+    match target {
+        Value::Object(object) => {
+            object.insert(last.clone(), value);
+        }
+        Value::Array(items) if last == "-" || last.parse() == Ok(items.len()) => items.push(value),
+        Value::Array(items) => {
+            let index = array_index(last, items.len())?;
+            items[index] = value;
+        }
+        _ => return Err(format!("Cannot set '{}' on a scalar", last)),
+    }
+    Ok(())
+}
+
+/// Split a JSON Pointer into unescaped reference tokens
+fn parse_pointer(pointer: &str) -> Result<Vec<String>, String> {
+    if pointer.is_empty() {
+        return Ok(Vec::new());
+    }
+    let Some(rest) = pointer.strip_prefix('/') else {
+        return Err(format!("Invalid JSON Pointer '{}': must be empty or start with '/'", pointer));
+    };
+    Ok(rest.split('/').map(|token| token.replace("~1", "/").replace("~0", "~")).collect())
+}
+
+fn array_index(token: &str, len: usize) -> Result<usize, String> {
+    match token.parse::<usize>() {
+        Ok(index) if index < len => Ok(index),
+        Ok(index) => Err(format!("Array index {} is out of bounds (length {})", index, len)),
+        Err(_) => Err(format!("Invalid array index '{}'", token)),
+    }
+}
+
+/// Escape a key for use as a JSON Pointer reference token
+fn escape_pointer_token(key: &str) -> String {
+    key.replace('~', "~0").replace('/', "~1")
+}
+
+/// Remove duplicate array elements, keeping the first occurrence of each.
+///
+/// Elements are compared by deep equality, or, when `key` is given, by the
+/// value of that field (every element must then be an object; elements
+/// missing the field are always kept).
+pub fn dedup_array(data: &Value, key: Option<&str>) -> Result<Value, String> {
+    let Value::Array(items) = data else {
+        return Err("dedup expects a JSON array".to_string());
+    };
+
+    let mut seen: Vec<&Value> = Vec::new();
+    let mut unique = Vec::new();
+    for (index, item) in items.iter().enumerate() {
+        let identity = match key {
+            Some(key) => match item {
+                Value::Object(object) => object.get(key),
+                _ => {
+                    return Err(format!("Element {} is not an object; dedup_key requires objects", index))
+                }
+            },
+            None => Some(item),
+        };
+
+        match identity {
+            Some(identity) if seen.contains(&identity) => {}
+            Some(identity) => {
+                seen.push(identity);
+                unique.push(item.clone());
+            }
+            None => unique.push(item.clone()),
+        }
+    }
+
+    Ok(Value::Array(unique))
+}
+
+/// Recursively sort object keys so equal documents serialize identically.
+///
+/// Array order is preserved unless `sort_arrays` is set, in which case arrays
+/// made up only of scalars are sorted too (null < booleans < numbers < strings).
+pub fn sort_keys(value: &mut Value, sort_arrays: bool) {
+    match value {
+        Value::Object(object) => {
+            object.sort_keys();
+            for child in object.values_mut() {
+                sort_keys(child, sort_arrays);
+            }
+        }
+        Value::Array(items) => {
+            for child in items.iter_mut() {
+                sort_keys(child, sort_arrays);
+            }
+            if sort_arrays && items.iter().all(|item| !item.is_object() && !item.is_array()) {
+                items.sort_by(compare_scalars);
+            }
+        }
+        _ => {}
+    }
+}
+
+fn compare_scalars(a: &Value, b: &Value) -> Ordering {
+    fn rank(value: &Value) -> u8 {
+        match value {
+            Value::Null => 0,
+            Value::Bool(_) => 1,
+            Value::Number(_) => 2,
+            _ => 3,
+        }
+    }
+
+    match (a, b) {
+        (Value::Bool(a), Value::Bool(b)) => a.cmp(b),
+        (Value::Number(a), Value::Number(b)) => {
+            let (a, b) = (a.as_f64().unwrap_or(0.0), b.as_f64().unwrap_or(0.0));
+            a.partial_cmp(&b).unwrap_or(Ordering::Equal)
+        }
+        (Value::String(a), Value::String(b)) => a.cmp(b),
+        _ => rank(a).cmp(&rank(b)),
+    }
+}
+
+/// Flatten nested objects and arrays into a single-level object.
+///
+/// Object keys are joined with `delimiter` and array elements get an `[i]`
+/// suffix, so `{"a": {"b": [{"c": 1}]}}` becomes `{"a.b[0].c": 1}`. Empty
+/// objects and arrays are kept as leaves so no key is silently dropped.
+/// Scalars are returned unchanged.
+pub fn flatten_json(value: &Value, delimiter: &str) -> Value {
+    match value {
+        Value::Object(_) | Value::Array(_) => {
+            let mut out = Map::new();
+            flatten_into(String::new(), value, delimiter, &mut out);
+            Value::Object(out)
+        }
+        _ => value.clone(),
+    }
+}
+
+fn flatten_into(prefix: String, value: &Value, delimiter: &str, out: &mut Map<String, Value>) {
+    match value {
+        Value::Object(object) if !object.is_empty() => {
+            for (key, child) in object {
+                let path = if prefix.is_empty() {
+                    key.clone()
+                } else {
+                    format!("{}{}{}", prefix, delimiter, key)
+                };
+                flatten_into(path, child, delimiter, out);
+            }
+        }
+        Value::Array(items) if !items.is_empty() => {
+            for (index, child) in items.iter().enumerate() {
+                flatten_into(format!("{}[{}]", prefix, index), child, delimiter, out);
+            }
+        }
+        _ => {
+            out.insert(prefix, value.clone());
+        }
+    }
+}
+
+/// Rebuild a nested value from the keys produced by [`flatten_json`].
+///
+/// Gaps in array indices are filled with `null`. Keys that disagree about
+/// the shape of a path (e.g. `a` and `a.b`) are reported as errors.
+pub fn unflatten_json(value: &Value, delimiter: &str) -> Result<Value, String> {
+    let Value::Object(object) = value else {
+        return Err("unflatten expects a flat JSON object".to_string());
+    };
+    if delimiter.is_empty() {
+        return Err("flatten_delimiter must not be empty".to_string());
+    }
+
+    let mut root = Value::Null;
+    for (key, leaf) in object {
+        let segments = parse_flat_key(key, delimiter);
+        insert_path(&mut root, &segments, leaf.clone())
+            .map_err(|e| format!("Cannot unflatten key '{}': {}", key, e))?;
+    }
+
+    Ok(if root.is_null() { Value::Object(Map::new()) } else { root })
+}
+
+enum Segment {
+    Key(String),
+    Index(usize),
+}
+
+fn parse_flat_key(key: &str, delimiter: &str) -> Vec<Segment> {
+    let mut segments = Vec::new();
+    for part in key.split(delimiter) {
+        match split_indices(part) {
+            Some((name, indices)) => {
+                if !name.is_empty() {
+                    segments.push(Segment::Key(name.to_string()));
+                }
+                segments.extend(indices.into_iter().map(Segment::Index));
+            }
+            None => segments.push(Segment::Key(part.to_string())),
+        }
+    }
+    segments
+}
+
+/// Split `name[0][1]` into `("name", [0, 1])`; `None` if the brackets are
+/// not well-formed indices, in which case the part is a literal key.
+fn split_indices(part: &str) -> Option<(&str, Vec<usize>)> {
+    let Some(open) = part.find('[') else {
+        return Some((part, Vec::new()));
+    };
+
+    let mut indices = Vec::new();
+    let mut rest = &part[open..];
+    while !rest.is_empty() {
+        let close = rest.find(']')?;
+        indices.push(rest.strip_prefix('[')?[..close - 1].parse().ok()?);
+        rest = &rest[close + 1..];
+    }
+    Some((&part[..open], indices))
+}
+
+fn insert_path(target: &mut Value, segments: &[Segment], leaf: Value) -> Result<(), String> {
+    let Some((first, rest)) = segments.split_first() else {
+        return if target.is_null() {
+            *target = leaf;
+            Ok(())
+        } else {
+            Err("path is already set".to_string())
+        };
+    };
+
+    match first {
+        Segment::Key(key) => {
+            if target.is_null() {
+                *target = Value::Object(Map::new());
+            }
+            let Value::Object(object) = target else {
+                return Err(format!("expected an object at '{}'", key));
+            };
+            insert_path(object.entry(key.clone()).or_insert(Value::Null), rest, leaf)
+        }
+        Segment::Index(index) => {
+            if target.is_null() {
+                *target = Value::Array(Vec::new());
+            }
+            let Value::Array(items) = target else {
+                return Err(format!("expected an array at index {}", index));
+            };
+            if items.len() <= *index {
+                items.resize(index + 1, Value::Null);
+            }
+            insert_path(&mut items[*index], rest, leaf)
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    #[test]
+    fn test_flatten_nested_objects() {
+        let data = json!({"a": {"b": {"c": 1}}, "d": "x"});
+        assert_eq!(flatten_json(&data, "."), json!({"a.b.c": 1, "d": "x"}));
+    }
+
+    #[test]
+    fn test_flatten_arrays() {
+        let data = json!({"tags": ["a", "b"], "matrix": [[1, 2]]});
+        assert_eq!(
+            flatten_json(&data, "."),
+            json!({"tags[0]": "a", "tags[1]": "b", "matrix[0][0]": 1, "matrix[0][1]": 2})
+        );
+    }
+
+    #[test]
+    fn test_flatten_mixed_with_custom_delimiter() {
+        let data = json!({"a": {"b": [{"c": 1}, {"c": 2}]}, "empty": {}, "none": []});
+        let flat = flatten_json(&data, "/");
+        assert_eq!(flat, json!({"a/b[0]/c": 1, "a/b[1]/c": 2, "empty": {}, "none": []}));
+        assert_eq!(unflatten_json(&flat, "/").unwrap(), data);
+    }
+
+    #[test]
+    fn test_unflatten_round_trip() {
+        let data = json!({"user": {"name": "a", "roles": ["admin", "dev"]}, "items": [{"id": 1}]});
+        let flat = flatten_json(&data, ".");
+        assert_eq!(unflatten_json(&flat, ".").unwrap(), data);
+    }
+
+    #[test]
+    fn test_unflatten_conflicting_keys() {
+        let error = unflatten_json(&json!({"a": 1, "a.b": 2}), ".").unwrap_err();
+        assert!(error.contains("a.b"), "{}", error);
+    }
+
+    #[test]
+    fn test_merge_patch_deletes_null_keys() {
+        let mut target = json!({"a": 1, "b": 2});
+        merge_patch(&mut target, &json!({"b": null, "c": 3}));
+        assert_eq!(target, json!({"a": 1, "c": 3}));
+    }
+
+    #[test]
+    fn test_merge_patch_nested_merge() {
+        let mut target = json!({"user": {"name": "a", "email": "a@example.com"}, "tags": ["x"]});
+        merge_patch(&mut target, &json!({"user": {"email": null, "age": 30}, "tags": ["y"]}));
+        assert_eq!(target, json!({"user": {"name": "a", "age": 30}, "tags": ["y"]}));
+    }
+
+    #[test]
+    fn test_merge_patch_scalar_replaces_target() {
+        let mut target = json!({"a": {"b": 1}});
+        merge_patch(&mut target, &json!("replaced"));
+        assert_eq!(target, json!("replaced"));
+
+        let mut target = json!(["not", "an", "object"]);
+        merge_patch(&mut target, &json!({"a": {"b": null, "c": 1}}));
+        assert_eq!(target, json!({"a": {"c": 1}}));
+    }
+
+    #[test]
+    fn test_diff_identical_is_empty() {
+        let data = json!({"a": [1, {"b": null}], "c": "x"});
+        assert!(diff_json(&data, &data.clone()).is_empty());
+    }
+
+    #[test]
+    fn test_diff_single_nested_change() {
+        let before = json!({"user": {"name": "a", "roles": ["admin"]}});
+        let after = json!({"user": {"name": "b", "roles": ["admin"]}});
+        assert_eq!(
+            diff_json(&before, &after),
+            vec![json!({"op": "changed", "path": "/user/name", "old": "a", "new": "b"})]
+        );
+    }
+
+    #[test]
+    fn test_diff_added_and_removed() {
+        let before = json!({"a/b": 1, "items": [1, 2, 3]});
+        let after = json!({"items": [1, 5], "c": true});
+        assert_eq!(diff_json(&before, &after), vec![
+            json!({"op": "removed", "path": "/a~1b", "old": 1, "new": null}),
+            json!({"op": "changed", "path": "/items/1", "old": 2, "new": 5}),
+            json!({"op": "removed", "path": "/items/2", "old": 3, "new": null}),
+            json!({"op": "added", "path": "/c", "old": null, "new": true}),
+        ]);
+    }
+
+    #[test]
+    fn test_dedup_primitives() {
+        let data = json!([3, "a", 3, 1, "a", null, 1.5, null]);
+        assert_eq!(dedup_array(&data, None).unwrap(), json!([3, "a", 1, null, 1.5]));
+    }
+
+    #[test]
+    fn test_dedup_objects_deep_and_by_key() {
+        let data = json!([
+            {"id": 1, "name": "a"},
+            {"id": 2, "name": "b"},
+            {"name": "a", "id": 1},
+            {"id": 1, "name": "c"},
+            {"name": "no id"},
+        ]);
+
+        assert_eq!(dedup_array(&data, None).unwrap().as_array().unwrap().len(), 4);
+        assert_eq!(dedup_array(&data, Some("id")).unwrap(), json!([
+            {"id": 1, "name": "a"},
+            {"id": 2, "name": "b"},
+            {"name": "no id"},
+        ]));
+    }
+
+    #[test]
+    fn test_dedup_rejects_non_arrays() {
+        assert!(dedup_array(&json!({"a": 1}), None).is_err());
+        assert!(dedup_array(&json!([1, 2]), Some("id")).is_err());
+    }
+
+    #[test]
+    fn test_sort_keys_canonical_output() {
+        let mut a = json!({"b": 1, "a": {"d": [3, 1], "c": null}});
+        let mut b = json!({"a": {"c": null, "d": [3, 1]}, "b": 1});
+        assert_ne!(a.to_string(), b.to_string());
+
+        sort_keys(&mut a, false);
+        sort_keys(&mut b, false);
+        assert_eq!(a.to_string(), b.to_string());
+        assert_eq!(a.to_string(), r#"{"a":{"c":null,"d":[3,1]},"b":1}"#);
+    }
+
+    #[test]
+    fn test_sort_keys_with_arrays() {
+        let mut value = json!({"tags": ["b", 2, "a", null, true, 1.5], "rows": [{"z": 1, "y": 2}, [2, 1]]});
+        sort_keys(&mut value, true);
+        assert_eq!(
+            value.to_string(),
+            r#"{"rows":[{"y":2,"z":1},[1,2]],"tags":[null,true,1.5,2,"a","b"]}"#
+        );
+    }
+
+    #[test]
+    fn test_pointer_set_existing_and_append() {
+        let mut value = json!({"items": [{"name": "a"}], "a/b": {}});
+        pointer_set(&mut value, "/items/0/name", json!("b"), false).unwrap();
+        pointer_set(&mut value, "/items/-", json!({"name": "c"}), false).unwrap();
+        pointer_set(&mut value, "/a~1b/x", json!(1), false).unwrap();
+        assert_eq!(value, json!({"items": [{"name": "b"}, {"name": "c"}], "a/b": {"x": 1}}));
+    }
+
+    #[test]
+    fn test_pointer_set_missing_parents() {
+        let mut value = json!({});
+        let error = pointer_set(&mut value, "/a/b/c", json!(1), false).unwrap_err();
+        assert!(error.contains("does not exist"), "{}", error);
+
+        pointer_set(&mut value, "/a/b/c", json!(1), true).unwrap();
+        assert_eq!(value, json!({"a": {"b": {"c": 1}}}));
+    }
+
+    #[test]
+    fn test_pointer_set_errors() {
+        let mut value = json!({"items": [1], "n": 1});
+        assert!(pointer_set(&mut value, "items", json!(1), true).is_err());
+        assert!(pointer_set(&mut value, "/items/5", json!(1), true).is_err());
+        assert!(pointer_set(&mut value, "/n/x", json!(1), true).is_err());
+    }
+
+    #[test]
+    fn test_normalize_numbers() {
+        let input = "[1.50, 18446744073709551615, -5, 123456789012345678901234]";
+        let mut value: Value = serde_json::from_str(input).unwrap();
+        assert_eq!(value.to_string(), "[1.50,18446744073709551615,-5,123456789012345678901234]");
+
+        normalize_numbers(&mut value);
+        assert_eq!(value.to_string(), "[1.5,18446744073709551615,-5,1.2345678901234569e+23]");
+    }
+
+    #[test]
+    fn test_for_each_element_streams_array() {
+        let mut seen = Vec::new();
+        let count = for_each_element("[1, {\"a\": 2}, [3]]", |index, value| {
+            seen.push((index, value));
+            Ok(())
+        }).unwrap();
+        assert_eq!(count, 3);
+        assert_eq!(seen[1], (1, json!({"a": 2})));
+
+        let error = for_each_element("[1, 2, 3]", |index, _| {
+            if index == 1 { Err("boom".to_string()) } else { Ok(()) }
+        }).unwrap_err();
+        assert_eq!(error, "boom");
+
+        assert!(for_each_element("{\"a\": 1}", |_, _| Ok(())).unwrap_err().starts_with("Parse error"));
+        assert!(for_each_element("[1] [2]", |_, _| Ok(())).is_err());
+    }
+}
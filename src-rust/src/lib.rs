@@ -4,43 +4,449 @@
 //! exposed to JavaScript through WebAssembly bindings.
 
 use serde::{Deserialize, Serialize};
+use sha2::Digest;
 
 // Image processing module
 pub mod image_processor;
 pub use image_processor::*;
 
+// Data format conversions
+pub mod formats;
+
+// Structural JSON transforms
+pub mod json_ops;
+
+// gzip/deflate/zstd codecs for base64 payloads
+pub mod compression;
+
+// EXIF parsing for metadata carried through re-encoding
+pub mod exif;
+
+/// Outcome of a data operation
+///
+/// The payload defaults to a raw JSON value, which is what the WASM layer
+/// returns; native callers can use [`ProcessResult::into_typed`] to get their
+/// own types instead.
 #[derive(Debug, Serialize, Deserialize)]
-pub struct ProcessResult {
+#[cfg_attr(feature = "wasm", derive(tsify::Tsify))]
+pub struct ProcessResult<T = serde_json::Value> {
     pub success: bool,
-    pub data: Option<serde_json::Value>,
+    pub data: Option<T>,
     pub error: Option<String>,
+    /// Options that were ignored or had no effect on the output
+    #[serde(default)]
+    pub warnings: Vec<String>,
+}
+
+impl<T> ProcessResult<T> {
+    /// Successful result carrying `data`
+    pub fn ok(data: T) -> Self {
+        Self {
+            success: true,
+            data: Some(data),
+            error: None,
+            warnings: Vec::new(),
+        }
+    }
+
+    /// Failed result carrying an error message
+    pub fn err(error: impl Into<String>) -> Self {
+        Self {
+            success: false,
+            data: None,
+            error: Some(error.into()),
+            warnings: Vec::new(),
+        }
+    }
+
+    /// Attach warnings to the result
+    pub fn with_warnings(mut self, warnings: Vec<String>) -> Self {
+        self.warnings = warnings;
+        self
+    }
+}
+
+impl ProcessResult {
+    /// Deserialize the JSON payload into `T`, turning a mismatch into a failed result
+    pub fn into_typed<T: serde::de::DeserializeOwned>(self) -> ProcessResult<T> {
+        let typed = match (self.data, self.error) {
+            (_, Some(error)) => ProcessResult::err(error),
+            (None, None) => ProcessResult { success: self.success, data: None, error: None, warnings: Vec::new() },
+            (Some(data), None) => match serde_json::from_value(data) {
+                Ok(data) => ProcessResult::ok(data),
+                Err(e) => ProcessResult::err(format!("Result type mismatch: {}", e)),
+            },
+        };
+        typed.with_warnings(self.warnings)
+    }
 }
 
 #[derive(Debug, Serialize, Deserialize)]
+#[cfg_attr(feature = "wasm", derive(tsify::Tsify))]
 pub struct ProcessOptions {
     pub format: String,
     pub validate: bool,
     pub extra_data: bool,
+    /// JSON Schema (as a JSON string) checked when `validate` is true
+    #[serde(default)]
+    pub schema: Option<String>,
+    /// JSONPath expression used by the `jsonpath` format
+    #[serde(default)]
+    pub query: Option<String>,
+    /// Field delimiter for the `csv` format (defaults to `,`)
+    #[serde(default)]
+    pub delimiter: Option<char>,
+    /// Whether the first CSV row is a header row (defaults to true)
+    #[serde(default)]
+    pub has_headers: Option<bool>,
+    /// Expand nested values into dotted columns for `json_to_csv` instead of failing
+    #[serde(default)]
+    pub flatten_nested: Option<bool>,
+    /// Separator between object keys for the `flatten` and `unflatten` formats (defaults to `.`)
+    #[serde(default)]
+    pub flatten_delimiter: Option<String>,
+    /// Spaces per nesting level for the `prettify` format (defaults to 2)
+    #[serde(default)]
+    pub indent: Option<usize>,
+    /// Field that identifies duplicates for the `dedup` format (defaults to whole-element equality)
+    #[serde(default)]
+    pub dedup_key: Option<String>,
+    /// Also sort arrays of scalars in the `sort_keys` format
+    #[serde(default)]
+    pub sort_arrays: Option<bool>,
+    /// Keep numbers exactly as written (integers wider than 64 bits, trailing
+    /// zeros) instead of normalizing them to `i64`/`u64`/`f64`
+    #[serde(default)]
+    pub preserve_precision: Option<bool>,
+    /// Codec (`gzip`, `deflate` or `zstd`) the base64 input is compressed with
+    #[serde(default)]
+    pub decompress: Option<String>,
+    /// Codec used to compress the output, returned as a base64 string
+    #[serde(default)]
+    pub compress: Option<String>,
+}
+
+impl Default for ProcessOptions {
+    fn default() -> Self {
+        Self {
+            format: "json".to_string(),
+            validate: false,
+            extra_data: false,
+            schema: None,
+            query: None,
+            delimiter: None,
+            has_headers: None,
+            flatten_nested: None,
+            flatten_delimiter: None,
+            indent: None,
+            dedup_key: None,
+            sort_arrays: None,
+            preserve_precision: None,
+            decompress: None,
+            compress: None,
+        }
+    }
+}
+
+impl ProcessOptions {
+    fn flatten_delimiter(&self) -> &str {
+        self.flatten_delimiter.as_deref().unwrap_or(".")
+    }
 }
 
 /// Main processing function - implement your core logic here
-pub fn process_data(input: &str, _options: &ProcessOptions) -> ProcessResult {
-    // Example implementation - replace with your actual logic
-    // Note: options parameter is prefixed with _ to indicate intentional non-use in this example
-    match serde_json::from_str::<serde_json::Value>(input) {
-        Ok(data) => ProcessResult {
-            success: true,
-            data: Some(data),
-            error: None,
-        },
-        Err(e) => ProcessResult {
-            success: false,
-            data: None,
-            error: Some(format!("Parse error: {}", e)),
+pub fn process_data(input: &str, options: &ProcessOptions) -> ProcessResult {
+    let inflated;
+    let input = match options.decompress.as_deref() {
+        Some(codec) => match compression::decompress(input, codec) {
+            Ok(text) => {
+                inflated = text;
+                inflated.as_str()
+            }
+            Err(e) => return ProcessResult::err(e),
         },
+        None => input,
+    };
+    let data = match parse_input(input, options) {
+        Ok(data) => data,
+        Err(e) => return ProcessResult::err(e),
+    };
+
+    match transform(data, options) {
+        Ok(data) => ProcessResult::ok(data).with_warnings(option_warnings(options)),
+        Err(e) => ProcessResult::err(e),
+    }
+}
+
+/// Formats `process_data` understands, as input parsers or output transforms
+const KNOWN_FORMATS: &[&str] = &[
+    "json", "csv", "yaml", "xml", "toml", "sort_keys", "jsonpath", "json_to_csv", "flatten",
+    "unflatten", "dedup", "minify", "prettify", "json_to_yaml", "json_to_toml",
+];
+
+/// Describe options that were silently ignored for this request
+fn option_warnings(options: &ProcessOptions) -> Vec<String> {
+    let mut warnings = Vec::new();
+    if !KNOWN_FORMATS.contains(&options.format.as_str()) {
+        warnings.push(format!("Unknown format '{}' was treated as plain JSON", options.format));
+    }
+    warnings
+}
+
+/// Run parsed input through normalization, validation, the `format`
+/// transform and optional compression
+fn transform(mut data: serde_json::Value, options: &ProcessOptions) -> Result<serde_json::Value, String> {
+    if !options.preserve_precision.unwrap_or(false) {
+        json_ops::normalize_numbers(&mut data);
+    }
+
+    if options.validate {
+        if let Some(schema) = &options.schema {
+            validate_against_schema(&data, schema)?;
+        }
+    }
+
+    let output = match options.format.as_str() {
+        "sort_keys" => {
+            json_ops::sort_keys(&mut data, options.sort_arrays.unwrap_or(false));
+            Ok(data)
+        }
+        "jsonpath" => query_json_path(&data, options.query.as_deref()),
+        "json_to_csv" => {
+            let flatten_nested = options.flatten_nested.unwrap_or(false);
+            formats::json_to_csv(&data, options.delimiter, flatten_nested)
+                .map(serde_json::Value::String)
+        }
+        "flatten" => Ok(json_ops::flatten_json(&data, options.flatten_delimiter())),
+        "unflatten" => json_ops::unflatten_json(&data, options.flatten_delimiter()),
+        "dedup" => json_ops::dedup_array(&data, options.dedup_key.as_deref()),
+        "minify" => Ok(serde_json::Value::String(data.to_string())),
+        "prettify" => {
+            formats::prettify_json(&data, options.indent.unwrap_or(2)).map(serde_json::Value::String)
+        }
+        "json_to_yaml" => formats::json_to_yaml(&data).map(serde_json::Value::String),
+        "json_to_toml" => formats::json_to_toml(&data).map(serde_json::Value::String),
+        _ => Ok(data),
+    };
+    // Text outputs are compressed as-is, anything else as compact JSON
+    match options.compress.as_deref() {
+        Some(codec) => output.and_then(|data| {
+            let text = match data {
+                serde_json::Value::String(text) => text,
+                data => data.to_string(),
+            };
+            compression::compress(&text, codec).map(serde_json::Value::String)
+        }),
+        None => output,
+    }
+}
+
+/// Stream the elements of a top-level JSON array through the `process_data`
+/// pipeline one at a time, handing each processed element to `f`
+///
+/// Memory use is bounded by the largest element rather than the whole array.
+/// The input must be JSON; on success `data` is `{"count": n}`, and the first
+/// failing element stops the stream with an `Element N: ` prefixed error.
+pub fn process_large_array(
+    input: &str,
+    options: &ProcessOptions,
+    mut f: impl FnMut(serde_json::Value),
+) -> ProcessResult {
+    let streamed = json_ops::for_each_element(input, |index, element| {
+        let data = transform(element, options).map_err(|e| format!("Element {}: {}", index, e))?;
+        f(data);
+        Ok(())
+    });
+
+    match streamed {
+        Ok(count) => ProcessResult::ok(serde_json::json!({ "count": count })),
+        Err(e) => ProcessResult::err(e),
+    }
+}
+
+/// Parse the input according to `options.format`, treating anything that is
+/// not a recognized input format as JSON
+fn parse_input(input: &str, options: &ProcessOptions) -> Result<serde_json::Value, String> {
+    match options.format.as_str() {
+        "csv" => formats::csv_to_json(input, options.delimiter, options.has_headers.unwrap_or(true)),
+        "yaml" => formats::yaml_to_json(input),
+        "xml" => formats::xml_to_json(input),
+        "toml" => formats::toml_to_json(input),
+        _ => serde_json::from_str(input).map_err(|e| format!("Parse error: {}", e)),
+    }
+}
+
+/// Parse a JSON document with numbers in their native form, as `process_data`
+/// does by default, so `1.50` and `1.5` compare and serialize alike
+fn parse_json(input: &str) -> Result<serde_json::Value, serde_json::Error> {
+    let mut value = serde_json::from_str(input)?;
+    json_ops::normalize_numbers(&mut value);
+    Ok(value)
+}
+
+/// Apply an RFC 7386 JSON Merge Patch to `target`, returning the patched document
+pub fn merge_patch(target: &str, patch: &str) -> ProcessResult {
+    let mut target = match parse_json(target) {
+        Ok(target) => target,
+        Err(e) => return ProcessResult::err(format!("Target parse error: {}", e)),
+    };
+    let patch = match parse_json(patch) {
+        Ok(patch) => patch,
+        Err(e) => return ProcessResult::err(format!("Patch parse error: {}", e)),
+    };
+
+    json_ops::merge_patch(&mut target, &patch);
+    ProcessResult::ok(target)
+}
+
+/// Diff two JSON documents into an array of `{op, path, old, new}` entries
+pub fn diff_json(a: &str, b: &str) -> ProcessResult {
+    let before = match parse_json(a) {
+        Ok(before) => before,
+        Err(e) => return ProcessResult::err(format!("Parse error in first document: {}", e)),
+    };
+    let after = match parse_json(b) {
+        Ok(after) => after,
+        Err(e) => return ProcessResult::err(format!("Parse error in second document: {}", e)),
+    };
+
+    ProcessResult::ok(serde_json::Value::Array(json_ops::diff_json(&before, &after)))
+}
+
+/// Read the value at an RFC 6901 JSON Pointer such as `/items/0/name`
+pub fn json_pointer_get(input: &str, pointer: &str) -> ProcessResult {
+    let data = match parse_json(input) {
+        Ok(data) => data,
+        Err(e) => return ProcessResult::err(format!("Parse error: {}", e)),
+    };
+    if !pointer.is_empty() && !pointer.starts_with('/') {
+        return ProcessResult::err(format!(
+            "Invalid JSON Pointer '{}': must be empty or start with '/'",
+            pointer
+        ));
+    }
+
+    match data.pointer(pointer) {
+        Some(value) => ProcessResult::ok(value.clone()),
+        None => ProcessResult::err(format!("No value at JSON Pointer '{}'", pointer)),
+    }
+}
+
+/// Set the value at an RFC 6901 JSON Pointer, returning the updated document.
+/// `value` is parsed as JSON; missing parent objects are created only when
+/// `create_missing` is true.
+pub fn json_pointer_set(
+    input: &str,
+    pointer: &str,
+    value: &str,
+    create_missing: bool,
+) -> ProcessResult {
+    let mut data = match parse_json(input) {
+        Ok(data) => data,
+        Err(e) => return ProcessResult::err(format!("Parse error: {}", e)),
+    };
+    let value = match parse_json(value) {
+        Ok(value) => value,
+        Err(e) => return ProcessResult::err(format!("Value parse error: {}", e)),
+    };
+
+    match json_ops::pointer_set(&mut data, pointer, value, create_missing) {
+        Ok(()) => ProcessResult::ok(data),
+        Err(e) => ProcessResult::err(e),
     }
 }
 
+/// Guess whether `input` is JSON, YAML, CSV or XML without parsing it
+///
+/// Returns `{format, confidence}`; `format` is `unknown` when nothing matches.
+pub fn detect_format(input: &str) -> ProcessResult {
+    let (format, confidence) = formats::detect_format(input);
+    ProcessResult::ok(serde_json::json!({ "format": format, "confidence": confidence }))
+}
+
+/// Hex digest of the raw input bytes using `md5`, `sha1` or `sha256`
+pub fn hash_input(input: &str, algo: &str) -> ProcessResult {
+    let digest = match algo.to_ascii_lowercase().as_str() {
+        "md5" => md5::Md5::digest(input.as_bytes()).to_vec(),
+        "sha1" => sha1::Sha1::digest(input.as_bytes()).to_vec(),
+        "sha256" => sha2::Sha256::digest(input.as_bytes()).to_vec(),
+        _ => {
+            return ProcessResult::err(format!(
+                "Unknown hash algorithm '{}' (expected md5, sha1 or sha256)",
+                algo
+            ))
+        }
+    };
+
+    let hex: String = digest.iter().map(|byte| format!("{:02x}", byte)).collect();
+    ProcessResult::ok(serde_json::Value::String(hex))
+}
+
+/// Select the nodes matched by a JSONPath expression, returned as an array
+fn query_json_path(
+    data: &serde_json::Value,
+    query: Option<&str>,
+) -> Result<serde_json::Value, String> {
+    let query = query.ok_or("The jsonpath format requires a query")?;
+    let path = serde_json_path::JsonPath::parse(query)
+        .map_err(|e| format!("Invalid JSONPath: {}", e))?;
+
+    let matches = path.query(data).all().into_iter().cloned().collect();
+    Ok(serde_json::Value::Array(matches))
+}
+
+/// Validate `data` against a JSON Schema, collecting every violation into one message
+fn validate_against_schema(data: &serde_json::Value, schema: &str) -> Result<(), String> {
+    let schema: serde_json::Value = serde_json::from_str(schema)
+        .map_err(|e| format!("Schema parse error: {}", e))?;
+    let validator = jsonschema::validator_for(&schema)
+        .map_err(|e| format!("Invalid schema: {}", e))?;
+
+    let errors: Vec<String> = validator.iter_errors(data)
+        .map(|e| {
+            let path = e.instance_path.to_string();
+            if path.is_empty() {
+                e.to_string()
+            } else {
+                format!("{}: {}", path, e)
+            }
+        })
+        .collect();
+
+    if errors.is_empty() {
+        Ok(())
+    } else {
+        Err(format!("Schema validation failed: {}", errors.join("; ")))
+    }
+}
+
+/// Manifest of the operations, filters, effects and output formats this build supports
+pub fn capabilities() -> serde_json::Value {
+    serde_json::json!({
+        "version": env!("CARGO_PKG_VERSION"),
+        "operations": Operation::all().iter().map(Operation::as_str).collect::<Vec<_>>(),
+        "filters": Filter::all().iter().map(Filter::as_str).collect::<Vec<_>>(),
+        "effects": Effect::all().iter().map(Effect::as_str).collect::<Vec<_>>(),
+        "output_formats": ImageProcessor::supported_output_formats(),
+        "features": {
+            "rayon": cfg!(all(feature = "rayon", not(target_arch = "wasm32"))),
+            "svg": cfg!(feature = "svg"),
+            "avif": cfg!(feature = "avif"),
+            "tracing": cfg!(feature = "tracing"),
+            "timing": cfg!(not(target_arch = "wasm32")),
+        },
+    })
+}
+
+/// Version, git commit and build profile of this build, for correlating bug reports
+pub fn build_info() -> serde_json::Value {
+    serde_json::json!({
+        "version": env!("CARGO_PKG_VERSION"),
+        "git_hash": env!("N8N_RUST_GIT_HASH"),
+        "build_profile": env!("N8N_RUST_BUILD_PROFILE"),
+    })
+}
+
 /// Batch processing function
 pub fn process_batch(inputs: &[String], options: &ProcessOptions) -> Vec<ProcessResult> {
     inputs.iter()
@@ -48,13 +454,25 @@ pub fn process_batch(inputs: &[String], options: &ProcessOptions) -> Vec<Process
         .collect()
 }
 
+/// Process newline-delimited JSON, one result per non-blank line.
+/// Errors are prefixed with the 1-based line number they came from.
+pub fn process_ndjson(input: &str, options: &ProcessOptions) -> Vec<ProcessResult> {
+    input.lines()
+        .enumerate()
+        .filter(|(_, line)| !line.trim().is_empty())
+        .map(|(index, line)| {
+            let mut result = process_data(line, options);
+            if let Some(error) = result.error.take() {
+                result.error = Some(format!("Line {}: {}", index + 1, error));
+            }
+            result
+        })
+        .collect()
+}
+
 /// Validation function
 pub fn validate_input(input: &str) -> ProcessResult {
-    let options = ProcessOptions {
-        format: "json".to_string(),
-        validate: true,
-        extra_data: false,
-    };
+    let options = ProcessOptions { validate: true, ..Default::default() };
     
     process_data(input, &options)
 }
@@ -70,11 +488,7 @@ mod tests {
     #[test]
     fn test_process_valid_json() {
         let input = r#"{"name": "test", "value": 42}"#;
-        let options = ProcessOptions {
-            format: "json".to_string(),
-            validate: true,
-            extra_data: false,
-        };
+        let options = ProcessOptions { validate: true, ..Default::default() };
         
         let result = process_data(input, &options);
         assert!(result.success);
@@ -85,11 +499,7 @@ mod tests {
     #[test]
     fn test_process_invalid_json() {
         let input = r#"{"invalid": json}"#;
-        let options = ProcessOptions {
-            format: "json".to_string(),
-            validate: true,
-            extra_data: false,
-        };
+        let options = ProcessOptions { validate: true, ..Default::default() };
         
         let result = process_data(input, &options);
         assert!(!result.success);
@@ -102,12 +512,24 @@ mod tests {
         let valid_input = r#"{"test": "data"}"#;
         let result = validate_input(valid_input);
         assert!(result.success);
+        // Without a schema, validation is the syntax check itself
+        assert!(result.warnings.is_empty());
 
         let invalid_input = r#"invalid json"#;
         let result = validate_input(invalid_input);
         assert!(!result.success);
     }
 
+    #[test]
+    fn test_process_options_require_format() {
+        assert!(serde_json::from_str::<ProcessOptions>("{}").is_err());
+
+        let options: ProcessOptions =
+            serde_json::from_str(r#"{"format":"csv","validate":false,"extra_data":false}"#).unwrap();
+        assert_eq!(options.format, "csv");
+        assert!(options.schema.is_none());
+    }
+
     #[test]
     fn test_batch_processing() {
         let inputs = vec![
@@ -115,11 +537,7 @@ mod tests {
             r#"{"item": 2}"#.to_string(),
             r#"invalid"#.to_string(),
         ];
-        let options = ProcessOptions {
-            format: "json".to_string(),
-            validate: true,
-            extra_data: false,
-        };
+        let options = ProcessOptions { validate: true, ..Default::default() };
         
         let results = process_batch(&inputs, &options);
         assert_eq!(results.len(), 3);
@@ -127,4 +545,370 @@ mod tests {
         assert!(results[1].success);
         assert!(!results[2].success);
     }
-}
\ No newline at end of file
+
+    #[test]
+    fn test_schema_validation() {
+        let options = ProcessOptions {
+            validate: true,
+            schema: Some(r#"{"type": "object", "required": ["name"]}"#.to_string()),
+            ..Default::default()
+        };
+
+        let result = process_data(r#"{"name": "test"}"#, &options);
+        assert!(result.success);
+
+        let result = process_data(r#"{"value": 42}"#, &options);
+        assert!(!result.success);
+        let error = result.error.unwrap();
+        assert!(error.starts_with("Schema validation failed"));
+        assert!(error.contains("\"name\" is a required property"));
+    }
+
+    #[test]
+    fn test_schema_ignored_without_validate() {
+        let options = ProcessOptions {
+            validate: false,
+            schema: Some(r#"{"required": ["name"]}"#.to_string()),
+            ..Default::default()
+        };
+
+        assert!(process_data(r#"{"value": 42}"#, &options).success);
+    }
+
+    #[test]
+    fn test_ignored_options_warn() {
+        let options = ProcessOptions {
+            format: "json_to_xml".to_string(),
+            validate: true,
+            ..Default::default()
+        };
+        let result = process_data(r#"{"value": 42}"#, &options);
+        assert!(result.success);
+        assert_eq!(result.warnings.len(), 1);
+        assert!(result.warnings[0].contains("json_to_xml"));
+
+        assert!(process_data(r#"{"value": 42}"#, &ProcessOptions::default()).warnings.is_empty());
+    }
+
+    #[test]
+    fn test_jsonpath_query() {
+        let input = r#"{"items": [{"id": 1, "name": "a"}, {"id": 2, "name": "b"}], "total": 2}"#;
+        let options = ProcessOptions {
+            format: "jsonpath".to_string(),
+            query: Some("$.items[*].id".to_string()),
+            ..Default::default()
+        };
+
+        let result = process_data(input, &options);
+        assert!(result.success);
+        assert_eq!(result.data.unwrap(), serde_json::json!([1, 2]));
+    }
+
+    #[test]
+    fn test_jsonpath_invalid_syntax() {
+        let options = ProcessOptions {
+            format: "jsonpath".to_string(),
+            query: Some("$.items[".to_string()),
+            ..Default::default()
+        };
+
+        let result = process_data(r#"{"items": []}"#, &options);
+        assert!(!result.success);
+        assert!(result.error.unwrap().starts_with("Invalid JSONPath"));
+    }
+
+    #[test]
+    fn test_csv_format() {
+        let options = ProcessOptions {
+            format: "csv".to_string(),
+            ..Default::default()
+        };
+
+        let result = process_data("id,name\n1,a\n2,b\n", &options);
+        assert!(result.success);
+        assert_eq!(result.data.unwrap(), serde_json::json!([
+            {"id": "1", "name": "a"},
+            {"id": "2", "name": "b"},
+        ]));
+    }
+
+    #[test]
+    fn test_json_to_csv_format() {
+        let options = ProcessOptions {
+            format: "json_to_csv".to_string(),
+            ..Default::default()
+        };
+
+        let result = process_data(r#"[{"id": 1, "name": "a"}, {"id": 2}]"#, &options);
+        assert!(result.success);
+        assert_eq!(result.data.unwrap(), serde_json::json!("id,name\n1,a\n2,\n"));
+    }
+
+    #[test]
+    fn test_yaml_format() {
+        let options = ProcessOptions {
+            format: "yaml".to_string(),
+            ..Default::default()
+        };
+
+        let result = process_data("items:\n  - id: 1\n  - id: 2\n", &options);
+        assert!(result.success);
+        assert_eq!(result.data.unwrap(), serde_json::json!({"items": [{"id": 1}, {"id": 2}]}));
+    }
+
+    #[test]
+    fn test_toml_format() {
+        let options = ProcessOptions {
+            format: "toml".to_string(),
+            ..Default::default()
+        };
+
+        let result = process_data("[table]\nkey = \"value\"\nlist = [1, 2]\n", &options);
+        assert!(result.success, "{:?}", result.error);
+        assert_eq!(result.data.unwrap(), serde_json::json!({"table": {"key": "value", "list": [1, 2]}}));
+
+        let result = process_data("[table\n", &options);
+        assert!(result.error.unwrap().starts_with("TOML parse error"));
+    }
+
+    #[test]
+    fn test_xml_format() {
+        let options = ProcessOptions {
+            format: "xml".to_string(),
+            ..Default::default()
+        };
+
+        let result = process_data(r#"<root a="1"><child>x</child></root>"#, &options);
+        assert!(result.success);
+        assert_eq!(result.data.unwrap(), serde_json::json!({"root": {"@a": "1", "child": "x"}}));
+    }
+
+    #[test]
+    fn test_flatten_format() {
+        let options = ProcessOptions {
+            format: "flatten".to_string(),
+            flatten_delimiter: Some("_".to_string()),
+            ..Default::default()
+        };
+
+        let result = process_data(r#"{"a": {"b": [{"c": 1}]}}"#, &options);
+        assert!(result.success);
+        assert_eq!(result.data.unwrap(), serde_json::json!({"a_b[0]_c": 1}));
+    }
+
+    #[test]
+    fn test_merge_patch() {
+        let target = r#"{"title": "a", "author": {"name": "x"}}"#;
+        let result = merge_patch(target, r#"{"title": null, "author": {"id": 1}}"#);
+        assert!(result.success);
+        assert_eq!(result.data.unwrap(), serde_json::json!({"author": {"name": "x", "id": 1}}));
+        let result = merge_patch(r#"{"price": 1.50}"#, "{}");
+        assert_eq!(serde_json::to_string(&result.data.unwrap()).unwrap(), r#"{"price":1.5}"#);
+
+        let result = merge_patch("{}", "{invalid");
+        assert!(result.error.unwrap().starts_with("Patch parse error"));
+    }
+
+    #[test]
+    fn test_diff_json() {
+        let result = diff_json(r#"{"a": {"b": 1}}"#, r#"{"a": {"b": 1}}"#);
+        assert_eq!(result.data.unwrap(), serde_json::json!([]));
+
+        let result = diff_json(r#"{"a": {"b": 1}}"#, r#"{"a": {"b": 2}}"#);
+        let entries = result.data.unwrap();
+        assert_eq!(entries.as_array().unwrap().len(), 1);
+        assert_eq!(entries[0]["path"], "/a/b");
+
+        // Numbers compare by value, not by their source text
+        let result = diff_json(r#"{"a": 1.50}"#, r#"{"a": 1.5}"#);
+        assert_eq!(result.data.unwrap(), serde_json::json!([]));
+    }
+
+    #[test]
+    fn test_process_ndjson() {
+        let input = "{\"id\": 1}\n{\"id\": \n\n{\"id\": 3}\r\n";
+        let results = process_ndjson(input, &ProcessOptions::default());
+
+        assert_eq!(results.len(), 3);
+        assert!(results[0].success);
+        assert!(!results[1].success);
+        assert!(results[1].error.as_ref().unwrap().starts_with("Line 2: Parse error"));
+        assert_eq!(results[2].data.as_ref().unwrap(), &serde_json::json!({"id": 3}));
+    }
+
+    #[test]
+    fn test_minify_and_prettify() {
+        let input = "{\n  \"name\" : \"a b\",\n  \"items\": [ 1, 2 ]\n}";
+
+        let options = ProcessOptions { format: "minify".to_string(), ..Default::default() };
+        let minified = process_data(input, &options).data.unwrap();
+        assert_eq!(minified, serde_json::json!(r#"{"name":"a b","items":[1,2]}"#));
+
+        let options = ProcessOptions { format: "prettify".to_string(), ..Default::default() };
+        let prettified = process_data(r#"{"a":{"b":1}}"#, &options).data.unwrap();
+        assert!(prettified.as_str().unwrap().contains('\n'));
+        assert!(prettified.as_str().unwrap().contains("\n    \"b\": 1"));
+    }
+
+    #[test]
+    fn test_dedup_format() {
+        let options = ProcessOptions {
+            format: "dedup".to_string(),
+            dedup_key: Some("email".to_string()),
+            ..Default::default()
+        };
+
+        let input = r#"[{"email": "a@x.io", "n": 1}, {"email": "a@x.io", "n": 2}]"#;
+        let result = process_data(input, &options);
+        assert_eq!(result.data.unwrap(), serde_json::json!([{"email": "a@x.io", "n": 1}]));
+
+        let result = process_data(r#"{"not": "an array"}"#, &options);
+        assert!(!result.success);
+    }
+
+    #[test]
+    fn test_sort_keys_format() {
+        let options = ProcessOptions {
+            format: "sort_keys".to_string(),
+            ..Default::default()
+        };
+
+        let a = process_data(r#"{"b": {"y": 1, "x": 2}, "a": [2, 1]}"#, &options).data.unwrap();
+        let b = process_data(r#"{"a": [2, 1], "b": {"x": 2, "y": 1}}"#, &options).data.unwrap();
+        assert_eq!(a.to_string(), b.to_string());
+    }
+
+    #[test]
+    fn test_json_pointer_get() {
+        let input = r#"{"items": [{"name": "a"}, {"name": "b"}]}"#;
+        assert_eq!(json_pointer_get(input, "/items/1/name").data.unwrap(), serde_json::json!("b"));
+        assert!(!json_pointer_get(input, "/items/5").success);
+        assert!(!json_pointer_get(input, "items").success);
+    }
+
+    #[test]
+    fn test_json_pointer_set_creates_parent() {
+        let result = json_pointer_set(r#"{"a": 1}"#, "/meta/tags", r#"["x"]"#, true);
+        assert_eq!(result.data.unwrap(), serde_json::json!({"a": 1, "meta": {"tags": ["x"]}}));
+
+        let result = json_pointer_set(r#"{"a": 1}"#, "/meta/tags", r#"["x"]"#, false);
+        assert!(!result.success);
+    }
+
+    #[test]
+    fn test_big_integer_round_trip() {
+        let input = r#"{"id": 12345678901234567890, "big": 123456789012345678901234567890}"#;
+
+        let result = process_data(input, &ProcessOptions::default());
+        let data = result.data.unwrap();
+        assert_eq!(data["id"].to_string(), "12345678901234567890");
+        assert_eq!(data["big"].as_f64(), Some(1.2345678901234568e29));
+
+        let options = ProcessOptions {
+            preserve_precision: Some(true),
+            ..Default::default()
+        };
+        let result = process_data(input, &options);
+        let data = result.data.unwrap();
+        assert_eq!(data["id"].to_string(), "12345678901234567890");
+        assert_eq!(data["big"].to_string(), "123456789012345678901234567890");
+    }
+
+    #[test]
+    fn test_hash_input() {
+        let digest = |algo| hash_input("hello", algo).data.unwrap();
+        assert_eq!(digest("md5"), "5d41402abc4b2a76b9719d911017c592");
+        assert_eq!(digest("sha1"), "aaf4c61ddcc5e8a2dabede0f3b482cd9aea9434d");
+        assert_eq!(digest("SHA256"), "2cf24dba5fb0a30e26e83b2ac5b9e29e1b161e5c1fa7425e73043362938b9824");
+
+        let result = hash_input("hello", "crc32");
+        assert!(result.error.unwrap().starts_with("Unknown hash algorithm"));
+    }
+
+    #[test]
+    fn test_capabilities_manifest() {
+        let manifest = capabilities();
+        assert!(manifest["operations"].as_array().unwrap().contains(&serde_json::json!("blend")));
+        assert!(!manifest["output_formats"].as_array().unwrap().is_empty());
+        assert_eq!(manifest["effects"].as_array().unwrap().len(), Effect::all().len());
+    }
+
+    #[test]
+    fn test_build_info() {
+        let info = build_info();
+        assert_eq!(info["version"], env!("CARGO_PKG_VERSION"));
+        assert!(!info["git_hash"].as_str().unwrap().is_empty());
+        assert!(matches!(info["build_profile"].as_str(), Some("debug" | "release")));
+    }
+
+    #[test]
+    fn test_process_result_into_typed() {
+        #[derive(Debug, Deserialize, PartialEq)]
+        struct User {
+            name: String,
+            age: u32,
+        }
+
+        let options = ProcessOptions::default();
+        let result = process_data(r#"{"name": "Ada", "age": 36}"#, &options).into_typed::<User>();
+        assert!(result.success);
+        assert_eq!(result.data, Some(User { name: "Ada".to_string(), age: 36 }));
+
+        let result = process_data(r#"{"name": "Ada"}"#, &options).into_typed::<User>();
+        assert!(!result.success);
+        assert!(result.error.unwrap().contains("missing field `age`"));
+
+        let result = process_data("not json", &options).into_typed::<User>();
+        assert!(result.error.unwrap().starts_with("Parse error"));
+    }
+
+    #[test]
+    fn test_detect_format_result() {
+        let result = detect_format("a,b\n1,2\n");
+        assert!(result.success);
+        assert_eq!(result.data.unwrap(), serde_json::json!({"format": "csv", "confidence": 0.85}));
+    }
+
+    #[test]
+    fn test_gzip_round_trip() {
+        let compress = ProcessOptions {
+            compress: Some("gzip".to_string()),
+            ..Default::default()
+        };
+        let packed = process_data(r#"{"name": "demo", "tags": ["a", "b"]}"#, &compress);
+        assert!(packed.success, "{:?}", packed.error);
+        let packed = packed.data.unwrap();
+
+        let decompress = ProcessOptions {
+            decompress: Some("gzip".to_string()),
+            ..Default::default()
+        };
+        let result = process_data(packed.as_str().unwrap(), &decompress);
+        assert!(result.success, "{:?}", result.error);
+        assert_eq!(result.data.unwrap(), serde_json::json!({"name": "demo", "tags": ["a", "b"]}));
+
+        let result = process_data("{}", &decompress);
+        assert!(result.error.unwrap().contains("not valid base64"));
+    }
+
+    #[test]
+    fn test_process_large_array_streams_elements() {
+        let input = serde_json::to_string(&(0..1000).map(|id| serde_json::json!({"id": id})).collect::<Vec<_>>()).unwrap();
+        let mut calls = 0;
+        let result = process_large_array(&input, &ProcessOptions::default(), |element| {
+            assert_eq!(element["id"], calls);
+            calls += 1;
+        });
+        assert!(result.success, "{:?}", result.error);
+        assert_eq!(calls, 1000);
+        assert_eq!(result.data.unwrap(), serde_json::json!({"count": 1000}));
+
+        let options = ProcessOptions {
+            validate: true,
+            schema: Some(r#"{"type": "object"}"#.to_string()),
+            ..Default::default()
+        };
+        let result = process_large_array(r#"[{}, 2, {}]"#, &options, |_| {});
+        assert!(result.error.unwrap().starts_with("Element 1: "));
+    }
+}
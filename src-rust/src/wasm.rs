@@ -3,8 +3,18 @@
 //! This module exposes Rust functions to JavaScript using wasm-bindgen
 
 use wasm_bindgen::prelude::*;
-use crate::{process_data, process_batch, validate_input, ProcessOptions};
-use crate::image_processor::{ImageProcessor, ImageProcessingOptions};
+use crate::{
+    detect_format, hash_input, json_pointer_get, json_pointer_set, merge_patch, process_data, process_batch,
+    process_large_array, process_ndjson, validate_input, ProcessOptions,
+};
+use crate::image_processor::{Effect, Filter, ImageError, ImageProcessor, ImageProcessingOptions, ProcessorConfig};
+use std::cell::RefCell;
+use std::sync::atomic::{AtomicBool, Ordering};
+
+thread_local! {
+    // Metadata of the most recent binary result, read back via get_last_binary_metadata
+    static LAST_BINARY_METADATA: RefCell<Option<String>> = const { RefCell::new(None) };
+}
 
 // Enable console.error panic hook for better debugging
 #[wasm_bindgen(start)]
@@ -48,63 +58,194 @@ pub fn process_batch_wasm(inputs_json: &str, options_json: &str) -> Result<Strin
         .map_err(|e| JsValue::from_str(&format!("Serialize error: {}", e)))
 }
 
+/// Process newline-delimited JSON - exposed to JavaScript
+#[wasm_bindgen]
+pub fn process_ndjson_wasm(input: &str, options_json: &str) -> Result<String, JsValue> {
+    let options: ProcessOptions = serde_json::from_str(options_json)
+        .map_err(|e| JsValue::from_str(&format!("Options parse error: {}", e)))?;
+
+    let results = process_ndjson(input, &options);
+
+    serde_json::to_string(&results)
+        .map_err(|e| JsValue::from_str(&format!("Serialize error: {}", e)))
+}
+
+/// Stream a large JSON array, calling `callback(elementJson, index)` for each
+/// processed element - exposed to JavaScript
+///
+/// The first exception thrown by the callback is rethrown once the stream ends.
+#[wasm_bindgen]
+pub fn process_large_array_wasm(input: &str, options_json: &str, callback: &js_sys::Function) -> Result<String, JsValue> {
+    let options: ProcessOptions = serde_json::from_str(options_json)
+        .map_err(|e| JsValue::from_str(&format!("Options parse error: {}", e)))?;
+
+    let mut index = 0u32;
+    let mut thrown = None;
+    let result = process_large_array(input, &options, |element| {
+        let called = callback.call2(&JsValue::NULL, &JsValue::from_str(&element.to_string()), &JsValue::from(index));
+        if let (Err(exception), None) = (called, &thrown) {
+            thrown = Some(exception);
+        }
+        index += 1;
+    });
+    if let Some(exception) = thrown {
+        return Err(exception);
+    }
+
+    serde_json::to_string(&result)
+        .map_err(|e| JsValue::from_str(&format!("Serialize error: {}", e)))
+}
+
+/// Apply a JSON Merge Patch (RFC 7386) - exposed to JavaScript
+#[wasm_bindgen]
+pub fn merge_patch_wasm(target: &str, patch: &str) -> Result<String, JsValue> {
+    let result = merge_patch(target, patch);
+
+    serde_json::to_string(&result)
+        .map_err(|e| JsValue::from_str(&format!("Serialize error: {}", e)))
+}
+
+/// Read a value by JSON Pointer - exposed to JavaScript
+#[wasm_bindgen]
+pub fn json_pointer_get_wasm(input: &str, pointer: &str) -> Result<String, JsValue> {
+    let result = json_pointer_get(input, pointer);
+
+    serde_json::to_string(&result)
+        .map_err(|e| JsValue::from_str(&format!("Serialize error: {}", e)))
+}
+
+/// Set a value by JSON Pointer - exposed to JavaScript
+#[wasm_bindgen]
+pub fn json_pointer_set_wasm(
+    input: &str,
+    pointer: &str,
+    value: &str,
+    create_missing: bool,
+) -> Result<String, JsValue> {
+    let result = json_pointer_set(input, pointer, value, create_missing);
+
+    serde_json::to_string(&result)
+        .map_err(|e| JsValue::from_str(&format!("Serialize error: {}", e)))
+}
+
+/// Hash raw input (md5/sha1/sha256) - exposed to JavaScript
+#[wasm_bindgen]
+pub fn hash_input_wasm(input: &str, algo: &str) -> Result<String, JsValue> {
+    let result = hash_input(input, algo);
+
+    serde_json::to_string(&result)
+        .map_err(|e| JsValue::from_str(&format!("Serialize error: {}", e)))
+}
+
+/// Guess the format of a text input - exposed to JavaScript
+#[wasm_bindgen]
+pub fn detect_format_wasm(input: &str) -> Result<String, JsValue> {
+    let result = detect_format(input);
+
+    serde_json::to_string(&result)
+        .map_err(|e| JsValue::from_str(&format!("Serialize error: {}", e)))
+}
+
 /// Get library version
 #[wasm_bindgen]
 pub fn get_version() -> String {
     env!("CARGO_PKG_VERSION").to_string()
 }
 
+/// Get version, git commit hash and build profile as JSON
+#[wasm_bindgen]
+pub fn get_build_info() -> String {
+    crate::build_info().to_string()
+}
+
 // Image Processing WASM Functions
 
+/// Failed result JSON for errors raised before or around image processing
+fn failure_json(error_code: &str, error: String) -> String {
+    serde_json::json!({
+        "success": false,
+        "image_data": null,
+        "metadata": null,
+        "error": error,
+        "error_code": error_code
+    })
+    .to_string()
+}
+
 /// Process a single image with the given options
+///
+/// Failures are still returned as `Ok` JSON with `success: false`; `error_code`
+/// is an `ImageError` code, or `invalid_input` / `invalid_options` / `serialize`
+/// for errors outside the processor.
 #[wasm_bindgen]
 pub fn process_image_wasm(base64_input: &str, options_json: &str) -> Result<String, JsValue> {
     // Wrap everything in a catch_unwind to handle panics gracefully
     let result = std::panic::catch_unwind(|| {
         // Validate inputs first
         if base64_input.is_empty() {
-            return Err("Empty base64 input provided".to_string());
+            return Err(("invalid_input", "Empty base64 input provided".to_string()));
         }
         
         if options_json.is_empty() {
-            return Err("Empty options JSON provided".to_string());
+            return Err(("invalid_options", "Empty options JSON provided".to_string()));
         }
         
         // Parse options with detailed error info
         let options: ImageProcessingOptions = serde_json::from_str(options_json)
-            .map_err(|e| format!("Options parse error: {}. JSON: {}", e, options_json))?;
+            .map_err(|e| ("invalid_options", format!("Options parse error: {}. JSON: {}", e, options_json)))?;
         
         // Process the image
         let result = ImageProcessor::process_image(base64_input, &options);
         
         // Serialize result
         serde_json::to_string(&result)
-            .map_err(|e| format!("Serialize error: {}", e))
+            .map_err(|e| ("serialize", format!("Serialize error: {}", e)))
     });
     
     match result {
         Ok(Ok(json_string)) => Ok(json_string),
-        Ok(Err(error_msg)) => {
-            let error_result = serde_json::json!({
-                "success": false,
-                "image_data": null,
-                "metadata": null,
-                "error": error_msg
-            });
-            Ok(error_result.to_string())
-        }
-        Err(_panic) => {
-            let panic_result = serde_json::json!({
-                "success": false,
-                "image_data": null,
-                "metadata": null,
-                "error": "Internal error: Rust code panicked during image processing"
-            });
-            Ok(panic_result.to_string())
-        }
+        Ok(Err((error_code, error_msg))) => Ok(failure_json(error_code, error_msg)),
+        Err(_panic) => Ok(failure_json(ImageError::Panic.code(), ImageError::Panic.to_string())),
     }
 }
 
+/// Process a single image passed as raw bytes (a Uint8Array on the JS side)
+#[wasm_bindgen]
+pub fn process_image_bytes_wasm(input: &[u8], options_json: &str) -> Result<String, JsValue> {
+    let options: ImageProcessingOptions = serde_json::from_str(options_json)
+        .map_err(|e| JsValue::from_str(&format!("Options parse error: {}", e)))?;
+
+    let result = ImageProcessor::process_image_bytes(input, &options);
+
+    serde_json::to_string(&result)
+        .map_err(|e| JsValue::from_str(&format!("Serialize error: {}", e)))
+}
+
+/// Process a single image and return the encoded bytes directly (as a Uint8Array)
+///
+/// Metadata for the returned image is available from `get_last_binary_metadata`
+/// until the next call.
+#[wasm_bindgen]
+pub fn process_image_binary_wasm(base64_input: &str, options_json: &str) -> Result<Vec<u8>, JsValue> {
+    let options: ImageProcessingOptions = serde_json::from_str(options_json)
+        .map_err(|e| JsValue::from_str(&format!("Options parse error: {}", e)))?;
+
+    let (bytes, metadata) = ImageProcessor::process_image_to_bytes(base64_input, &options)
+        .map_err(|e| JsValue::from_str(&e.to_string()))?;
+
+    let metadata_json = serde_json::to_string(&metadata)
+        .map_err(|e| JsValue::from_str(&format!("Serialize error: {}", e)))?;
+    LAST_BINARY_METADATA.with(|last| *last.borrow_mut() = Some(metadata_json));
+
+    Ok(bytes)
+}
+
+/// Metadata JSON for the last successful `process_image_binary_wasm` call, or "null"
+#[wasm_bindgen]
+pub fn get_last_binary_metadata() -> String {
+    LAST_BINARY_METADATA.with(|last| last.borrow().clone().unwrap_or_else(|| "null".to_string()))
+}
+
 /// Process multiple images in batch
 #[wasm_bindgen]
 pub fn process_image_batch_wasm(images_json: &str, options_json: &str) -> Result<String, JsValue> {
@@ -120,29 +261,272 @@ pub fn process_image_batch_wasm(images_json: &str, options_json: &str) -> Result
         .map_err(|e| JsValue::from_str(&format!("Serialize error: {}", e)))
 }
 
+/// Process multiple images in batch, calling `callback(index, total, success)` after each image
+///
+/// Exceptions thrown by the callback are ignored so they cannot abort the batch.
+#[wasm_bindgen]
+pub fn process_image_batch_with_progress_wasm(images_json: &str, options_json: &str, callback: &js_sys::Function) -> Result<String, JsValue> {
+    let images: Vec<String> = serde_json::from_str(images_json)
+        .map_err(|e| JsValue::from_str(&format!("Images parse error: {}", e)))?;
+    
+    let options: ImageProcessingOptions = serde_json::from_str(options_json)
+        .map_err(|e| JsValue::from_str(&format!("Options parse error: {}", e)))?;
+    
+    let result = ImageProcessor::process_batch_with_progress(images, &options, |index, total, success| {
+        let _ = callback.call3(
+            &JsValue::NULL,
+            &JsValue::from(index as u32),
+            &JsValue::from(total as u32),
+            &JsValue::from_bool(success),
+        );
+    });
+    
+    serde_json::to_string(&result)
+        .map_err(|e| JsValue::from_str(&format!("Serialize error: {}", e)))
+}
+
+/// Shared flag for stopping a `process_image_batch_cancelable_wasm` run
+#[wasm_bindgen]
+#[derive(Default)]
+pub struct CancelToken {
+    cancelled: AtomicBool,
+}
+
+#[wasm_bindgen]
+impl CancelToken {
+    #[wasm_bindgen(constructor)]
+    pub fn new() -> CancelToken {
+        CancelToken::default()
+    }
+
+    /// Request that the batch stop before its next image
+    pub fn cancel(&self) {
+        self.cancelled.store(true, Ordering::Relaxed);
+    }
+
+    pub fn is_cancelled(&self) -> bool {
+        self.cancelled.load(Ordering::Relaxed)
+    }
+}
+
+/// Process multiple images in batch until `token` is cancelled
+///
+/// Cancellation is checked at image boundaries, not mid-image, and the result
+/// holds the images processed so far with `cancelled` set. The optional
+/// `callback(index, total, success)` runs after each image, which is where a
+/// single-threaded caller can check its own abort signal and cancel the token.
+#[wasm_bindgen]
+pub fn process_image_batch_cancelable_wasm(
+    images_json: &str,
+    options_json: &str,
+    token: &CancelToken,
+    callback: Option<js_sys::Function>,
+) -> Result<String, JsValue> {
+    let images: Vec<String> = serde_json::from_str(images_json)
+        .map_err(|e| JsValue::from_str(&format!("Images parse error: {}", e)))?;
+    
+    let options: ImageProcessingOptions = serde_json::from_str(options_json)
+        .map_err(|e| JsValue::from_str(&format!("Options parse error: {}", e)))?;
+    
+    let result = ImageProcessor::process_batch_cancelable(images, &options, &token.cancelled, |index, total, success| {
+        if let Some(callback) = &callback {
+            let _ = callback.call3(
+                &JsValue::NULL,
+                &JsValue::from(index as u32),
+                &JsValue::from(total as u32),
+                &JsValue::from_bool(success),
+            );
+        }
+    });
+    
+    serde_json::to_string(&result)
+        .map_err(|e| JsValue::from_str(&format!("Serialize error: {}", e)))
+}
+
+/// Process multiple images in batch, pairing each image with its own options
+#[wasm_bindgen]
+pub fn process_image_batch_with_options_wasm(images_json: &str, options_json: &str) -> Result<String, JsValue> {
+    let images: Vec<String> = serde_json::from_str(images_json)
+        .map_err(|e| JsValue::from_str(&format!("Images parse error: {}", e)))?;
+    
+    let options: Vec<ImageProcessingOptions> = serde_json::from_str(options_json)
+        .map_err(|e| JsValue::from_str(&format!("Options parse error: {}", e)))?;
+    
+    let result = ImageProcessor::process_batch_with_options(images, options)
+        .map_err(|e| JsValue::from_str(&e.to_string()))?;
+    
+    serde_json::to_string(&result)
+        .map_err(|e| JsValue::from_str(&format!("Serialize error: {}", e)))
+}
+
+/// Apply the requested operation to every frame of an animated GIF
+#[wasm_bindgen]
+pub fn process_gif_frames_wasm(base64_input: &str, options_json: &str) -> Result<String, JsValue> {
+    let options: ImageProcessingOptions = serde_json::from_str(options_json)
+        .map_err(|e| JsValue::from_str(&format!("Options parse error: {}", e)))?;
+
+    let result = ImageProcessor::process_gif_frames(base64_input, &options);
+
+    serde_json::to_string(&result)
+        .map_err(|e| JsValue::from_str(&format!("Serialize error: {}", e)))
+}
+
+/// Estimated bytes needed to decode every image of a batch, from headers only
+#[wasm_bindgen]
+pub fn estimate_batch_memory_wasm(images_json: &str) -> Result<f64, JsValue> {
+    let images: Vec<String> = serde_json::from_str(images_json)
+        .map_err(|e| JsValue::from_str(&format!("Images parse error: {}", e)))?;
+
+    Ok(ImageProcessor::estimate_batch_memory(&images) as f64)
+}
+
+/// Lay images out in a grid of `cols` columns, scaling each into its cell
+#[wasm_bindgen]
+pub fn compose_grid_wasm(
+    images_json: &str,
+    cols: u32,
+    cell_width: u32,
+    cell_height: u32,
+    options_json: &str,
+) -> Result<String, JsValue> {
+    let images: Vec<String> = serde_json::from_str(images_json)
+        .map_err(|e| JsValue::from_str(&format!("Images parse error: {}", e)))?;
+
+    let options: ImageProcessingOptions = serde_json::from_str(options_json)
+        .map_err(|e| JsValue::from_str(&format!("Options parse error: {}", e)))?;
+
+    let result = ImageProcessor::compose_grid(images, cols, cell_width, cell_height, &options);
+
+    serde_json::to_string(&result)
+        .map_err(|e| JsValue::from_str(&format!("Serialize error: {}", e)))
+}
+
+/// Create a solid-color image, e.g. as a compositing base or placeholder
+#[wasm_bindgen]
+pub fn create_solid_image_wasm(width: u32, height: u32, color: &str, format: &str) -> Result<String, JsValue> {
+    let result = ImageProcessor::create_solid_image(width, height, color, format);
+
+    serde_json::to_string(&result)
+        .map_err(|e| JsValue::from_str(&format!("Serialize error: {}", e)))
+}
+
+/// Compute a perceptual hash (dHash) of an image as 16 hex digits
+#[wasm_bindgen]
+pub fn perceptual_hash_wasm(base64_input: &str) -> Result<String, JsValue> {
+    ImageProcessor::perceptual_hash(base64_input)
+        .map_err(|e| JsValue::from_str(&e.to_string()))
+}
+
+/// Count differing bits between two perceptual hashes
+#[wasm_bindgen]
+pub fn hamming_distance_wasm(a: &str, b: &str) -> Result<u32, JsValue> {
+    ImageProcessor::hamming_distance(a, b)
+        .map_err(|e| JsValue::from_str(&e.to_string()))
+}
+
+/// Count distinct RGBA colors, approximately for images above a megapixel
+#[wasm_bindgen]
+pub fn count_unique_colors_wasm(base64_input: &str) -> Result<f64, JsValue> {
+    ImageProcessor::count_unique_colors(base64_input)
+        .map(|count| count as f64)
+        .map_err(|e| JsValue::from_str(&e.to_string()))
+}
+
 /// Get available filters list
 #[wasm_bindgen]
 pub fn get_available_filters() -> String {
-    let filters = vec![
-        "grayscale", "sepia", "invert", "vintage", "noir", "warm", "cool",
-        "dramatic", "firenze", "golden", "lix", "lofi", "neue", "obsidian",
-        "pastel_pink", "ryo"
-    ];
+    let filters: Vec<&str> = Filter::all().iter().map(Filter::as_str).collect();
     
     serde_json::to_string(&filters).unwrap_or_else(|_| "[]".to_string())
 }
 
+/// Get the output formats this build can encode
+#[wasm_bindgen]
+pub fn get_supported_output_formats() -> String {
+    serde_json::to_string(&ImageProcessor::supported_output_formats()).unwrap_or_else(|_| "[]".to_string())
+}
+
 /// Get available effects list
 #[wasm_bindgen]
 pub fn get_available_effects() -> String {
-    let effects = vec![
-        "edge_detection", "emboss", "laplace", "sobel_horizontal", "sobel_vertical",
-        "blur", "sharpen", "threshold", "solarize", "posterize"
-    ];
+    let effects: Vec<&str> = Effect::all().iter().map(Effect::as_str).collect();
     
     serde_json::to_string(&effects).unwrap_or_else(|_| "[]".to_string())
 }
 
+/// Describe everything this build supports, for building UIs dynamically
+#[wasm_bindgen]
+pub fn get_capabilities() -> String {
+    crate::capabilities().to_string()
+}
+
+/// Install process-wide image defaults from `ProcessorConfig` JSON; only the first call succeeds
+#[wasm_bindgen]
+pub fn configure_wasm(config_json: &str) -> Result<(), JsValue> {
+    let config: ProcessorConfig = serde_json::from_str(config_json)
+        .map_err(|e| JsValue::from_str(&format!("Config parse error: {}", e)))?;
+
+    crate::image_processor::configure(config)
+        .map_err(|e| JsValue::from_str(&e.to_string()))
+}
+
+/// Describe an image from its header (format, color type, bit depth, alpha) as metadata JSON
+#[wasm_bindgen]
+pub fn get_image_info_wasm(base64_input: &str) -> Result<String, JsValue> {
+    let info = ImageProcessor::get_image_info(base64_input)
+        .map_err(|e| JsValue::from_str(&e.to_string()))?;
+
+    serde_json::to_string(&info)
+        .map_err(|e| JsValue::from_str(&format!("Serialize error: {}", e)))
+}
+
+/// GPS position from the image's EXIF as `{"latitude", "longitude"}` JSON, or `null`
+#[wasm_bindgen]
+pub fn extract_gps_wasm(base64_input: &str) -> Result<String, JsValue> {
+    let position = ImageProcessor::extract_gps(base64_input)
+        .map_err(|e| JsValue::from_str(&e.to_string()))?
+        .map(|(latitude, longitude)| serde_json::json!({ "latitude": latitude, "longitude": longitude }));
+
+    serde_json::to_string(&position)
+        .map_err(|e| JsValue::from_str(&format!("Serialize error: {}", e)))
+}
+
+/// Decoded RGBA pixels and their dimensions, returned by `get_raw_pixels_wasm`
+#[wasm_bindgen]
+pub struct RawPixels {
+    width: u32,
+    height: u32,
+    pixels: Vec<u8>,
+}
+
+#[wasm_bindgen]
+impl RawPixels {
+    #[wasm_bindgen(getter)]
+    pub fn width(&self) -> u32 {
+        self.width
+    }
+
+    #[wasm_bindgen(getter)]
+    pub fn height(&self) -> u32 {
+        self.height
+    }
+
+    /// RGBA bytes row by row, `width * height * 4` long; each read copies into a new `Uint8Array`
+    #[wasm_bindgen(getter)]
+    pub fn pixels(&self) -> Vec<u8> {
+        self.pixels.clone()
+    }
+}
+
+/// Decode an image to raw RGBA bytes for per-pixel work in JS (canvas `ImageData`, ML tensors)
+#[wasm_bindgen]
+pub fn get_raw_pixels_wasm(base64_input: &str) -> Result<RawPixels, JsValue> {
+    let image = ImageProcessor::base64_to_photon_image(base64_input)
+        .map_err(|e| JsValue::from_str(&e.to_string()))?;
+
+    Ok(RawPixels { width: image.get_width(), height: image.get_height(), pixels: image.get_raw_pixels() })
+}
+
 /// Validate image format and get metadata
 #[wasm_bindgen]
 pub fn validate_image_wasm(base64_input: &str) -> Result<String, JsValue> {
@@ -159,7 +543,7 @@ pub fn validate_image_wasm(base64_input: &str) -> Result<String, JsValue> {
         Err(e) => {
             let error_result = serde_json::json!({
                 "valid": false,
-                "error": e
+                "error": e.to_string()
             });
             Ok(error_result.to_string())
         }
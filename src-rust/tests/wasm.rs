@@ -0,0 +1,156 @@
+//! WebAssembly binding tests
+//!
+//! Run with `wasm-pack test --node -- --features wasm`
+
+#![cfg(all(target_arch = "wasm32", feature = "wasm"))]
+
+use n8n_rust_core::wasm::*;
+use n8n_rust_core::ImageProcessor;
+use photon_rs::PhotonImage;
+use std::cell::Cell;
+use std::rc::Rc;
+use wasm_bindgen::prelude::*;
+use wasm_bindgen::JsCast;
+use wasm_bindgen_test::*;
+
+fn test_image_base64() -> String {
+    let image = PhotonImage::new([255u8, 0, 0, 255].repeat(4), 2, 2);
+    let bytes = ImageProcessor::photon_image_to_bytes(&image, "png", None).unwrap();
+    ImageProcessor::bytes_to_base64(&bytes)
+}
+
+#[wasm_bindgen_test]
+fn binary_output_decodes_to_image() {
+    let options = r#"{"operation":"filter","filter":"grayscale","output_format":"png"}"#;
+    let bytes = process_image_binary_wasm(&test_image_base64(), options).unwrap();
+
+    let decoded = image::load_from_memory(&bytes).unwrap();
+    assert_eq!(decoded.width(), 2);
+    assert_eq!(decoded.height(), 2);
+
+    let metadata: serde_json::Value = serde_json::from_str(&get_last_binary_metadata()).unwrap();
+    assert_eq!(metadata["size_bytes"], bytes.len());
+}
+
+#[wasm_bindgen_test]
+fn bytes_input_is_processed() {
+    let image = PhotonImage::new([0u8, 255, 0, 255].repeat(4), 2, 2);
+    let png = ImageProcessor::photon_image_to_bytes(&image, "png", None).unwrap();
+
+    let result = process_image_bytes_wasm(&png, r#"{"operation":"filter","filter":"invert"}"#).unwrap();
+    let result: serde_json::Value = serde_json::from_str(&result).unwrap();
+    assert_eq!(result["success"], true);
+}
+
+#[wasm_bindgen_test]
+fn progress_callback_runs_once_per_image() {
+    let calls = Rc::new(Cell::new(0u32));
+    let counter = calls.clone();
+    let callback = Closure::wrap(Box::new(move |_index: u32, _total: u32, _success: bool| {
+        counter.set(counter.get() + 1);
+    }) as Box<dyn FnMut(u32, u32, bool)>);
+
+    let images = serde_json::to_string(&vec![test_image_base64(); 3]).unwrap();
+    let result = process_image_batch_with_progress_wasm(
+        &images,
+        r#"{"operation":"filter","filter":"sepia"}"#,
+        callback.as_ref().unchecked_ref(),
+    )
+    .unwrap();
+
+    let result: serde_json::Value = serde_json::from_str(&result).unwrap();
+    assert_eq!(result["successful"], 3);
+    assert_eq!(calls.get(), 3);
+}
+
+#[wasm_bindgen_test]
+fn large_array_invokes_callback_per_element() {
+    let calls = Rc::new(Cell::new(0u32));
+    let counter = calls.clone();
+    let callback = Closure::<dyn FnMut(String, u32)>::new(move |element: String, index: u32| {
+        assert_eq!(element, index.to_string());
+        counter.set(counter.get() + 1);
+    });
+    let input = serde_json::to_string(&(0..1000).collect::<Vec<u32>>()).unwrap();
+
+    let options = r#"{"format":"json","validate":false,"extra_data":false}"#;
+    let result = process_large_array_wasm(&input, options, callback.as_ref().unchecked_ref()).unwrap();
+    let result: serde_json::Value = serde_json::from_str(&result).unwrap();
+    assert_eq!(result["data"]["count"], 1000);
+    assert_eq!(calls.get(), 1000);
+
+    let throwing = js_sys::Function::new_no_args("throw new Error('boom')");
+    let error = process_large_array_wasm("[1, 2]", options, &throwing).unwrap_err();
+    assert_eq!(error.dyn_into::<js_sys::Error>().unwrap().message(), "boom");
+}
+
+#[wasm_bindgen_test]
+fn throwing_progress_callback_does_not_abort_batch() {
+    let callback = js_sys::Function::new_no_args("throw new Error('boom')");
+    let images = serde_json::to_string(&vec![test_image_base64(); 2]).unwrap();
+
+    let result = process_image_batch_with_progress_wasm(&images, r#"{"operation":"filter","filter":"sepia"}"#, &callback).unwrap();
+    let result: serde_json::Value = serde_json::from_str(&result).unwrap();
+    assert_eq!(result["successful"], 2);
+}
+
+#[wasm_bindgen_test]
+fn cancelled_batch_returns_partial_result() {
+    let token = CancelToken::new();
+    let images = serde_json::to_string(&vec![test_image_base64(); 3]).unwrap();
+    let options = r#"{"operation":"filter","filter":"sepia"}"#;
+
+    let result = process_image_batch_cancelable_wasm(&images, options, &token, None).unwrap();
+    let result: serde_json::Value = serde_json::from_str(&result).unwrap();
+    assert_eq!(result["processed"], 3);
+
+    token.cancel();
+    let result = process_image_batch_cancelable_wasm(&images, options, &token, None).unwrap();
+    let result: serde_json::Value = serde_json::from_str(&result).unwrap();
+    assert_eq!(result["processed"], 0);
+    assert_eq!(result["cancelled"], true);
+}
+
+#[wasm_bindgen_test]
+fn failures_report_error_codes() {
+    let error_code = |input: &str, options: &str| {
+        let result: serde_json::Value = serde_json::from_str(&process_image_wasm(input, options).unwrap()).unwrap();
+        assert_eq!(result["success"], false);
+        result["error_code"].as_str().unwrap().to_string()
+    };
+    let sepia = r#"{"operation":"filter","filter":"sepia"}"#;
+
+    assert_eq!(error_code("", sepia), "invalid_input");
+    assert_eq!(error_code(&test_image_base64(), "{not json"), "invalid_options");
+    assert_eq!(error_code("not an image!", sepia), "decode_base64");
+    assert_eq!(error_code(&test_image_base64(), r#"{"operation":"filter","filter":"sepia","output_format":"tga"}"#), "unsupported_format");
+}
+
+#[wasm_bindgen_test]
+fn raw_pixels_cover_every_pixel() {
+    let raw = get_raw_pixels_wasm(&test_image_base64()).unwrap();
+    assert_eq!((raw.width(), raw.height()), (2, 2));
+    assert_eq!(raw.pixels().len(), (raw.width() * raw.height() * 4) as usize);
+    assert_eq!(&raw.pixels()[..4], &[255, 0, 0, 255]);
+
+    assert!(get_raw_pixels_wasm("not an image").is_err());
+}
+
+#[wasm_bindgen_test]
+fn typescript_declarations_cover_public_types() {
+    use n8n_rust_core::{
+        ImageMetadata, ImageProcessingOptions, ImageProcessingResult, Operation, ProcessOptions,
+        ProcessResult,
+    };
+    use tsify::Tsify;
+
+    assert!(<ProcessResult>::DECL.contains("export interface ProcessResult<T> {"));
+    assert!(<ProcessResult>::DECL.contains("data: T | null;"));
+    assert!(ProcessOptions::DECL.contains("format: string;"));
+    assert!(ProcessOptions::DECL.contains("schema?: string | null;"));
+    assert!(ImageProcessingResult::DECL.contains("metadata: ImageMetadata | null;"));
+    assert!(ImageMetadata::DECL.contains("width: number;"));
+    assert!(ImageProcessingOptions::DECL.contains("operation: Operation;"));
+    assert!(ImageProcessingOptions::DECL.contains("quality?: number | null;"));
+    assert!(Operation::DECL.contains(r#""filter" | "transform""#));
+}